@@ -0,0 +1,87 @@
+#ifndef RUST_SUPERKMERS_H
+#define RUST_SUPERKMERS_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Status code returned by every C API function.
+typedef enum SkStatus {
+  SK_OK = 0,
+  // A required pointer argument was NULL.
+  SK_NULL_POINTER = 1,
+  // The scheme spec is not valid UTF-8 or names an unknown scheme or mode.
+  SK_INVALID_SCHEME = 2,
+  // k or l is not supported by the scheme.
+  SK_INVALID_PARAMETERS = 3,
+  // The library panicked; the extractor should be freed.
+  SK_PANIC = 4,
+} SkStatus;
+
+// Opaque extractor handle.
+typedef struct SkExtractor SkExtractor;
+
+// A superkmer: a maximal run of consecutive k-mers sharing the same minimizer.
+//
+// # Fields
+// - `start` — position of the first base in the original sequence.
+// - `mint` — 2-bit packed minimizer value (canonical by default, forward-strand if
+//   `.non_canonical()` is used). Encoding: A=0, C=1, G=2, T=3, MSB-first.
+// - `size` — length of the superkmer in bases (≥ k).
+// - `mpos` — relative position of the minimizer within the superkmer (0-based offset from `start`).
+// - `mint_is_rc` — `true` if the canonical minimizer is the reverse complement of the forward-strand l-mer
+//   at position `start + mpos`. Always `false` when using `.non_canonical()`, and for
+//   reverse-complement palindromes (even l), which the syncmer and UHS schemes demote.
+//
+// The layout is C-compatible, so the C API (`ffi`) hands out result arrays as they are.
+typedef struct Superkmer {
+  size_t start;
+  uint32_t mint;
+  uint16_t size;
+  uint16_t mpos;
+  bool mint_is_rc;
+} Superkmer;
+
+// Create an extractor for `scheme` (NUL-terminated spec) with k-mer length `k` and
+// minimizer length `l`. On success `*out` owns the extractor; free it with
+// `sk_extractor_free`. On error `*out` is set to NULL.
+//
+// # Safety
+// `scheme` must be NULL or a valid C string, `out` NULL or valid for writes.
+enum SkStatus sk_extractor_new(const char *scheme, size_t k, size_t l, struct SkExtractor **out);
+
+// Free an extractor. NULL is ignored.
+//
+// # Safety
+// `ext` must be NULL or come from `sk_extractor_new`, and not be used afterwards.
+void sk_extractor_free(struct SkExtractor *ext);
+
+// Cut `len` bases at `seq` into superkmers. With `with_n`, k-mers containing
+// N/n are skipped; otherwise the sequence must be ACGT only. `*superkmers` and `*count`
+// receive the extractor's result array.
+//
+// # Safety
+// `ext` must come from `sk_extractor_new`, `seq` be valid for `len` bytes (or NULL
+// with `len` 0), and `superkmers` and `count` valid for writes.
+enum SkStatus sk_extractor_process(struct SkExtractor *ext,
+                                   const uint8_t *seq,
+                                   size_t len,
+                                   bool with_n,
+                                   const struct Superkmer **superkmers,
+                                   size_t *count);
+
+// The 2-bit packed sequence of the last `sk_extractor_process` call (A=0, C=1, G=2,
+// T=3, 32 bases per word, first base in the most significant bits).
+//
+// # Safety
+// `ext` must come from `sk_extractor_new`, `words` and `count` be valid for writes.
+enum SkStatus sk_extractor_storage(const struct SkExtractor *ext,
+                                   const uint64_t **words,
+                                   size_t *count);
+
+// Static, NUL-terminated description of a status code.
+const char *sk_status_message(enum SkStatus status);
+
+#endif  /* RUST_SUPERKMERS_H */
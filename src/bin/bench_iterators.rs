@@ -1,3 +1,4 @@
+#[cfg(feature = "debruijn")]
 use debruijn::dna_string::DnaString;
 use std::time::Instant;
 
@@ -47,6 +48,7 @@ fn main() {
             50
         };
 
+        #[cfg(feature = "debruijn")]
         let dna_bytes = DnaString::from_acgt_bytes(&seqs[0]).to_bytes();
 
         println!("\n--- seq_len={} k={} ({} iters, {} seqs) ---", len, k, iters, pool_size);
@@ -80,21 +82,24 @@ fn main() {
         });
 
         bench("kmc2 (l=8)", &seqs, iters, filter_ref, |s| {
-            let (_, iter) =
+            let iter =
                 rust_superkmers::iteratorkmc2::SuperkmersIterator::new(s, k, 8);
             let v: Vec<_> = iter.collect();
             std::hint::black_box(v);
         });
 
+        #[cfg(feature = "debruijn")]
+        {
         let dna_bytes_ref = &dna_bytes;
         bench("syncmersmsp (l=8)", &seqs, iters, filter_ref, |_s| {
             let iter = rust_superkmers::iteratorsyncmersmsp::SuperkmersIterator::new(dna_bytes_ref, k, 8);
             let v: Vec<_> = iter.collect();
             std::hint::black_box(v);
         });
+        }
 
-        bench("msp (l=8)", &seqs, iters, filter_ref, |_s| {
-            let iter = rust_superkmers::iteratormsp::SuperkmersIterator::new(dna_bytes_ref, k, 8);
+        bench("msp (l=8)", &seqs, iters, filter_ref, |s| {
+            let iter = rust_superkmers::iteratormsp::SuperkmersIterator::forward(s, k, 8);
             let v: Vec<_> = iter.collect();
             std::hint::black_box(v);
         });
@@ -312,7 +317,38 @@ fn main() {
             });
         }
 
+        for (name, mut ext_kmc2) in [
+            ("kmc2-ext (l=8)", rust_superkmers::iteratorkmc2::SuperkmerExtractor::new(k, 8)),
+            ("kmc2-ext:mspxor (l=8)", rust_superkmers::iteratorkmc2::SuperkmerExtractor::mspxor(k, 8)),
+        ] {
+            bench(name, &seqs, iters, filter_ref, |s| {
+                let sks = ext_kmc2.process(s);
+                std::hint::black_box(sks);
+            });
+        }
+
+        for (name, mut ext_msp) in [
+            ("msp-ext (l=8)", rust_superkmers::iteratormsp::SuperkmerExtractor::forward(k, 8)),
+            ("msp-ext:mspxor (l=11)", rust_superkmers::iteratormsp::SuperkmerExtractor::mspxor(k, 11)),
+        ] {
+            bench(name, &seqs, iters, filter_ref, |s| {
+                let sks = ext_msp.process(s);
+                std::hint::black_box(sks);
+            });
+        }
+
+        for (name, mut ext_random) in [
+            ("random-ext (l=11)", rust_superkmers::iteratorrandom::SuperkmerExtractor::new(k, 11)),
+            ("random-ext:mspxor (l=11)", rust_superkmers::iteratorrandom::SuperkmerExtractor::mspxor(k, 11)),
+        ] {
+            bench(name, &seqs, iters, filter_ref, |s| {
+                let sks = ext_random.process(s);
+                std::hint::black_box(sks);
+            });
+        }
+
         // UHS SIMD batch l=8
+        #[cfg(feature = "nightly")]
         {
             let mut ext_uhs_simd = rust_superkmers::uhs_simd_l8k40max::SimdBatchExtractor::new(k, 8);
             let name = "uhs-simd-ext (l=8)";
@@ -339,6 +375,7 @@ fn main() {
         }
 
         // SIMD batch l=9
+        #[cfg(feature = "nightly")]
         {
             let mut ext_simd9 = rust_superkmers::syncmers_simd_l9k41max::SimdBatchExtractor::new(k, 9);
             let name = "simd-batch-ext (l=9)";
@@ -365,6 +402,7 @@ fn main() {
         }
 
         // SIMD batch l=8
+        #[cfg(feature = "nightly")]
         {
             let mut ext_simd_batch = rust_superkmers::syncmers_simd_l8k40max::SimdBatchExtractor::new(k, 8);
             let name = "simd-batch-ext (l=8)";
@@ -392,6 +430,7 @@ fn main() {
         }
 
         // SIMD 16x extractor (2 × 8 reads)
+        #[cfg(feature = "nightly")]
         {
             let mut ext16 = rust_superkmers::syncmers_simd_l8k40max::Simd16xExtractor::new(k, 8);
             let name = "simd-16x-ext (l=8)";
@@ -420,6 +459,7 @@ fn main() {
         }
 
         // SIMD batch pack only (no kernel)
+        #[cfg(feature = "nightly")]
         {
             let mut ext_simd_batch = rust_superkmers::syncmers_simd_l8k40max::SimdBatchExtractor::new(k, 8);
             let name = "simd-batch-pack (l=8)";
@@ -445,6 +485,7 @@ fn main() {
         }
 
         // SIMD batch kernel only (pack + kernel, no materialization)
+        #[cfg(feature = "nightly")]
         {
             let mut ext_simd_batch = rust_superkmers::syncmers_simd_l8k40max::SimdBatchExtractor::new(k, 8);
             let name = "simd-batch-kernel (l=8)";
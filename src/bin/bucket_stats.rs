@@ -1,12 +1,19 @@
 use std::collections::HashMap;
 use std::env;
-use std::fs;
-use std::io::{BufRead, BufReader};
 
 use rust_superkmers::iteratorsyncmers2;
 use rust_superkmers::iteratorkmc2;
 use rust_superkmers::iteratormsp;
 use rust_superkmers::iteratoruhs;
+use rust_superkmers::iteratorrandom;
+use rust_superkmers::genome::PackedGenome;
+use rust_superkmers::utils::{decode_packed_range, split_on_n};
+use rust_superkmers::utils::fasta::open_fasta;
+use rust_superkmers::partition::{balance_partitions, hash_partition};
+use rust_superkmers::sampling::{sample_windows, BucketSample, DEFAULT_WINDOW_LEN};
+use rust_superkmers::scoretable::ScoreTable;
+use rust_superkmers::stats::{decode_mint, BucketStats};
+use rust_superkmers::tables;
 #[cfg(feature = "simd-mini")]
 use rust_superkmers::iteratorsimdmini;
 #[cfg(feature = "simd-mini")]
@@ -15,17 +22,40 @@ use rust_superkmers::iteratorsimdmini_cminim;
 use rust_superkmers::iteratormultiminimizers;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let all_args: Vec<String> = env::args().collect();
+    let sampling: Option<(f64, u64)> = all_args.iter().find_map(|a| a.strip_prefix("--sample=")).map(|s| {
+        let mut parts = s.split(':');
+        let fraction: f64 = parts.next().and_then(|f| f.parse().ok()).filter(|&f| f > 0.0 && f <= 1.0)
+            .expect("--sample needs a fraction in (0, 1]");
+        (fraction, parts.next().map_or(42, |seed| seed.parse().expect("Invalid sampling seed")))
+    });
+    let format = all_args.iter().find_map(|a| a.strip_prefix("--format=")).unwrap_or("text").to_string();
+    if !["text", "json", "tsv"].contains(&format.as_str()) {
+        eprintln!("Unknown format: '{}' (text, json or tsv)", format);
+        std::process::exit(1);
+    }
+    let scores_path = all_args.iter().find_map(|a| a.strip_prefix("--scores=")).map(|s| s.to_string());
+    let args: Vec<String> = all_args.into_iter().filter(|a| !a.starts_with("--sample=") && !a.starts_with("--format=") && !a.starts_with("--scores=")).collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <genome.fa> [k] [l] [method[:mode]]", args[0]);
+        eprintln!("Usage: {} <genome.fa|genome.skg> [k] [l] [method[:mode]] [s] [partitions] [--sample=F[:seed]] [--format=text|json|tsv] [--scores=table.sks]", args[0]);
+        eprintln!("  genome.skg: packed genome from pack_genome, memory-mapped and processed in parallel chunks");
+        eprintln!("              (syncmer and simdmini methods only)");
         eprintln!("  k: kmer length (default 31)");
-        eprintln!("  l: minimizer length (default: 8 for syncmer/kmc2/msp/uhs, 9 for simdmini/cminim/multimini)");
+        eprintln!("  l: minimizer length (default: 8 for syncmer/kmc2/msp/uhs/random, 9 for simdmini/cminim/multimini)");
+        eprintln!("  s: syncmer s-mer length (default 2, syncmer only)");
+        eprintln!("  partitions: if set, report the balance of a greedy assignment of buckets to that many partitions");
+        eprintln!("  --sample=F[:seed]: process a seeded fraction F of the reads / {} bp windows and report", DEFAULT_WINDOW_LEN);
+        eprintln!("              P99 and Max/Mean estimates with 95% bootstrap intervals (default seed 42)");
+        eprintln!("  --format: json prints one object per run (JSON Lines), tsv a header and one row per run;");
+        eprintln!("              sampled estimates and partition balance are only printed in text format");
+        eprintln!("  --scores: cut with a score table file (see score_table); its l and mode replace l and method");
         eprintln!();
         eprintln!("Methods:");
         eprintln!("  syncmer      Closed syncmer minimizers (default)");
         eprintln!("  uhs          Universal Hitting Set (ry-alphabet patterns, Martin Frith)");
-        eprintln!("  kmc2         KMC2 disqualification-based minimizers");
-        eprintln!("  msp          Lexicographic (MSP) minimizers");
+        eprintln!("  kmc2         KMC2 disqualification-based minimizers (l=7..12)");
+        eprintln!("  msp          Lexicographic (MSP) minimizers (sticky: forward strand, as rust-debruijn)");
+        eprintln!("  random       Random-order minimizers (seeded hash of the canonical l-mer)");
         eprintln!("  simdmini     SIMD-accelerated closed syncmers (requires odd l, feature: simd-mini)");
         eprintln!("  cminim       Canonical minimizers via simd-minimizers (requires odd l, feature: simd-mini)");
         eprintln!("  multimini[:N] Multi-minimizer with N hash functions (default N=2, feature: multi-mini)");
@@ -36,7 +66,7 @@ fn main() {
         eprintln!("  msp          Leftmost wins on tie — context-independent");
         eprintln!("  mspxor       XOR-hashed canonical scores — context-independent, uniform buckets");
         eprintln!();
-        eprintln!("Supported: syncmer, uhs, simdmini support all split modes. kmc2/msp/cminim/multimini ignore mode.");
+        eprintln!("Supported: syncmer, uhs, random, kmc2, msp, simdmini support all split modes. cminim/multimini ignore mode.");
         std::process::exit(1);
     }
 
@@ -45,9 +75,10 @@ fn main() {
     let l_arg: usize = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(0); // 0 = auto
     let method = args.get(4).map(|s| s.as_str()).unwrap_or("syncmer");
     let s_param: usize = args.get(5).and_then(|s| s.parse().ok()).unwrap_or(2);
+    let num_partitions: usize = args.get(6).and_then(|s| s.parse().ok()).unwrap_or(0);
 
     let base_method = method.split(':').next().unwrap();
-    if !["syncmer", "kmc2", "msp", "simdmini", "cminim", "multimini", "uhs"].contains(&base_method) {
+    if !["syncmer", "kmc2", "msp", "simdmini", "cminim", "multimini", "uhs", "random"].contains(&base_method) {
         eprintln!("Unknown method: '{}'. Run with no arguments to see usage.", method);
         std::process::exit(1);
     }
@@ -78,17 +109,39 @@ fn main() {
         if l_arg == 0 { 8 } else { l_arg }
     };
 
+    // Packed genomes are memory-mapped instead of read into memory
+    let genome = if PackedGenome::is_packed_genome(fasta_path) {
+        if !["syncmer", "simdmini"].contains(&base_method) || s_param != 2 || scores_path.is_some() {
+            eprintln!("Packed genome input supports only syncmer and simdmini (with s=2).");
+            std::process::exit(1);
+        }
+        eprintln!("Memory-mapping packed genome {}", fasta_path);
+        Some(PackedGenome::open(fasta_path).expect("Failed to open packed genome"))
+    } else {
+        None
+    };
+
     // Read FASTA once
-    let sequences = read_fasta(fasta_path);
+    let sequences = if genome.is_some() { Vec::new() } else { read_fasta(fasta_path) };
+    let num_sequences = genome.as_ref().map_or(sequences.len(), |g| g.contigs().len());
 
-    // Generate custom mspxor scores if s != 2 and method is syncmer
-    let custom_scores: Option<Vec<rust_superkmers::iteratorsyncmers2::ScoreType>> = if base_method == "syncmer" && s_param != 2 {
-        eprintln!("Generating syncmer mspxor scores with s={}...", s_param);
-        Some(iteratorsyncmers2::generate_mspxor_syncmer_scores_with_s(l, s_param))
+    // Score table from --scores, or generated if s != 2 and method is syncmer
+    let custom_scores: Option<ScoreTable> = if let Some(path) = &scores_path {
+        let table = ScoreTable::load(path).expect("Failed to load score table");
+        eprintln!("Loaded score table '{}' (l={}, mode {:?}, checksum {:016x})", table.name, table.l, table.mode, table.checksum());
+        if table.l > k {
+            eprintln!("k must be at least the table's l={}", table.l);
+            std::process::exit(1);
+        }
+        Some(table)
+    } else if base_method == "syncmer" && s_param != 2 {
+        eprintln!("Generating syncmer scores with s={}...", s_param);
+        Some(ScoreTable::syncmer(l, s_param, split_mode, true))
     } else {
         None
     };
-    let custom_scores_ref = custom_scores.as_deref();
+    let custom_scores_ref = custom_scores.as_ref();
+    let l = custom_scores_ref.map_or(l, |t| t.l);
 
     let mode_str = match split_mode {
         rust_superkmers::SplitMode::Sticky => "sticky",
@@ -100,6 +153,8 @@ fn main() {
     for &nb_hash in &multimini_nb_hashes {
         if base_method == "multimini" {
             eprintln!("Running k={}  l={}  method=multimini  nb_hash={}", k, l, nb_hash);
+        } else if let (Some(table), Some(path)) = (custom_scores_ref, &scores_path) {
+            eprintln!("Running k={}  l={}  scores={}  mode={:?}", k, l, path, table.mode);
         } else if custom_scores_ref.is_some() {
             eprintln!("Running k={}  l={}  s={}  method={}  mode={}", k, l, s_param, base_method, mode_str);
         } else {
@@ -110,20 +165,93 @@ fn main() {
         let mut total_kmers: u64 = 0;
         let mut total_superkmers: u64 = 0;
 
+        let mut sample = sampling.map(|(fraction, _)| BucketSample::new(fraction));
+
+        if let Some(genome) = &genome {
+            match (&mut sample, sampling) {
+                (Some(sample), Some((fraction, seed))) => {
+                    let input_kmers: usize = genome.contigs().iter()
+                        .flat_map(|c| c.fragments(k))
+                        .map(|(_, len)| len.saturating_sub(k - 1))
+                        .sum();
+                    sample.set_input_kmers(input_kmers as u64);
+                    for (idx, contig) in genome.contigs().iter().enumerate() {
+                        let mut window = Vec::new();
+                        for (start, end) in sample_windows(idx as u64, contig.len, DEFAULT_WINDOW_LEN, k, fraction, seed) {
+                            let mut unit_counts = HashMap::new();
+                            for (frag_start, frag_len) in contig.fragments(k) {
+                                let (s, e) = (start.max(frag_start), end.min(frag_start + frag_len));
+                                if e >= s + k {
+                                    decode_packed_range(genome.contig_storage(idx), s, e - s, &mut window);
+                                    process_seq(&window, k, l, method, nb_hash, split_mode, &mut unit_counts, &mut total_kmers, &mut total_superkmers, custom_scores_ref);
+                                }
+                            }
+                            add_sample_unit(sample, unit_counts, &mut bucket_counts);
+                        }
+                    }
+                }
+                _ => (total_kmers, total_superkmers) = process_packed_genome(genome, k, l, base_method, split_mode, &mut bucket_counts),
+            }
+        }
+        if let (Some(sample), None) = (&mut sample, &genome) {
+            let input_kmers: usize = sequences.iter()
+                .flat_map(|seq| split_on_n(seq, k))
+                .map(|(_, frag)| frag.len() - (k - 1))
+                .sum();
+            sample.set_input_kmers(input_kmers as u64);
+        }
         for (i, seq) in sequences.iter().enumerate() {
-            process_seq(seq, k, l, method, nb_hash, split_mode, &mut bucket_counts, &mut total_kmers, &mut total_superkmers, custom_scores_ref);
+            match (&mut sample, sampling) {
+                (Some(sample), Some((fraction, seed))) => {
+                    for (start, end) in sample_windows(i as u64, seq.len(), DEFAULT_WINDOW_LEN, k, fraction, seed) {
+                        let mut unit_counts = HashMap::new();
+                        process_seq(&seq[start..end], k, l, method, nb_hash, split_mode, &mut unit_counts, &mut total_kmers, &mut total_superkmers, custom_scores_ref);
+                        add_sample_unit(sample, unit_counts, &mut bucket_counts);
+                    }
+                }
+                _ => process_seq(seq, k, l, method, nb_hash, split_mode, &mut bucket_counts, &mut total_kmers, &mut total_superkmers, custom_scores_ref),
+            }
             if (i + 1) % 10 == 0 {
                 eprintln!("  processed {} sequences, {} superkmers, {} kmers so far", i + 1, total_superkmers, total_kmers);
             }
         }
 
         eprintln!("Done. {} sequences, {} superkmers, {} total kmers, {} distinct minimizers",
-            sequences.len(), total_superkmers, total_kmers, bucket_counts.len());
+            num_sequences, total_superkmers, total_kmers, bucket_counts.len());
+        let tables: Vec<String> = tables::memory_footprint().iter().map(|t| format!("{} l={} {} KB", t.name, t.l, t.bytes / 1024)).collect();
+        eprintln!("Lookup tables: {} KB ({})", tables::total_bytes() / 1024, tables.join(", "));
+
+        let label = match (&scores_path, custom_scores_ref) {
+            (Some(_), Some(table)) => format!("table:{}", table.name),
+            _ if base_method == "multimini" => format!("multimini:{}", nb_hash),
+            _ => method.to_string(),
+        };
+        let stats = BucketStats::from_counts(&bucket_counts, total_kmers, total_superkmers, k, l, 20).with_label(&label);
+        match format.as_str() {
+            "json" => {
+                println!("{}", stats.to_json());
+                continue;
+            }
+            "tsv" => {
+                if nb_hash == multimini_nb_hashes[0] {
+                    println!("{}", BucketStats::tsv_header());
+                }
+                println!("{}", stats.to_tsv());
+                continue;
+            }
+            _ => {}
+        }
 
         if base_method == "multimini" && multimini_nb_hashes.len() > 1 {
             println!("--- multimini nb_hash={} ---", nb_hash);
         }
-        print_stats(&bucket_counts, total_kmers, total_superkmers, l);
+        print_stats(&stats);
+        if let (Some(sample), Some((fraction, seed))) = (&sample, sampling) {
+            print_sample_estimate(sample, fraction, seed);
+        }
+        if num_partitions > 0 {
+            print_partition_balance(&bucket_counts, num_partitions);
+        }
         if base_method == "multimini" && multimini_nb_hashes.len() > 1 {
             println!();
         }
@@ -132,65 +260,48 @@ fn main() {
 
 fn read_fasta(path: &str) -> Vec<Vec<u8>> {
     eprintln!("Reading {}", path);
-    let file = fs::File::open(path).expect("Failed to open FASTA file");
-    let reader = BufReader::new(file);
-    let mut sequences = Vec::new();
-    let mut current_seq = Vec::<u8>::new();
-    for line in reader.lines() {
-        let line = line.expect("Failed to read line");
-        if line.starts_with('>') {
-            if !current_seq.is_empty() {
-                sequences.push(std::mem::take(&mut current_seq));
-            }
-        } else {
-            current_seq.extend_from_slice(line.trim().as_bytes());
-        }
-    }
-    if !current_seq.is_empty() {
-        sequences.push(current_seq);
-    }
+    let records = open_fasta(path).expect("Failed to open FASTA file");
+    let sequences: Vec<Vec<u8>> = records.map(|r| r.expect("Failed to read FASTA record").seq)
+        .filter(|seq| !seq.is_empty())
+        .collect();
     eprintln!("Read {} sequences", sequences.len());
     sequences
 }
 
-fn process_seq(seq: &[u8], k: usize, l: usize, method: &str, nb_hash: usize, split_mode: rust_superkmers::SplitMode, bucket_counts: &mut HashMap<u32, u64>, total_kmers: &mut u64, total_superkmers: &mut u64, custom_scores: Option<&[rust_superkmers::iteratorsyncmers2::ScoreType]>) {
+fn process_seq(seq: &[u8], k: usize, l: usize, method: &str, nb_hash: usize, split_mode: rust_superkmers::SplitMode, bucket_counts: &mut HashMap<u32, u64>, total_kmers: &mut u64, total_superkmers: &mut u64, custom_scores: Option<&ScoreTable>) {
+    if let Some(table) = custom_scores {
+        count_superkmers(table.superkmers_with_n(seq, k).into_iter(), k, bucket_counts, total_kmers, total_superkmers);
+        return;
+    }
     let base_method = method.split(':').next().unwrap();
     match base_method {
         "syncmer" => {
-            if let Some(scores) = custom_scores {
-                // Custom s parameter: use minimizer_core directly with provided scores
-                let fragments = rust_superkmers::utils::split_on_n(seq, k);
-                let full_storage = rust_superkmers::utils::bitpack_fragment(seq);
-                let mut min_positions = Vec::new();
-                let mut scores_buf = Vec::new();
-                let mut deque_buf = Vec::new();
-                let mut superkmers = Vec::new();
-                for (offset, fragment) in &fragments {
-                    let frag_storage = rust_superkmers::utils::bitpack_fragment(fragment);
-                    rust_superkmers::minimizer_core::minimizer_positions_deque::<false, _>(
-                        &frag_storage, fragment.len(), k, l, *offset,
-                        scores, &mut min_positions, &mut scores_buf, &mut deque_buf,
-                    );
-                }
-                rust_superkmers::minimizer_core::materialize_superkmers(&min_positions, k, l, true, &mut superkmers);
-                count_superkmers(superkmers.into_iter(), k, bucket_counts, total_kmers, total_superkmers);
-            } else {
-                let iter = match split_mode {
-                    rust_superkmers::SplitMode::Classical => iteratorsyncmers2::SuperkmersIterator::classical_with_n(seq, k, l),
-                    rust_superkmers::SplitMode::Msp => iteratorsyncmers2::SuperkmersIterator::msp_with_n(seq, k, l),
-                    rust_superkmers::SplitMode::MspXor => iteratorsyncmers2::SuperkmersIterator::mspxor_with_n(seq, k, l),
-                    rust_superkmers::SplitMode::Sticky => iteratorsyncmers2::SuperkmersIterator::new_with_n(seq, k, l),
-                };
-                count_superkmers(iter, k, bucket_counts, total_kmers, total_superkmers);
-            }
+            let iter = match split_mode {
+                rust_superkmers::SplitMode::Classical => iteratorsyncmers2::SuperkmersIterator::classical_with_n(seq, k, l),
+                rust_superkmers::SplitMode::Msp => iteratorsyncmers2::SuperkmersIterator::msp_with_n(seq, k, l),
+                rust_superkmers::SplitMode::MspXor => iteratorsyncmers2::SuperkmersIterator::mspxor_with_n(seq, k, l),
+                rust_superkmers::SplitMode::Sticky => iteratorsyncmers2::SuperkmersIterator::new_with_n(seq, k, l),
+            };
+            count_superkmers(iter, k, bucket_counts, total_kmers, total_superkmers);
         }
         "kmc2" => {
-            let (_storage, iter) = iteratorkmc2::SuperkmersIterator::new_with_n(seq, k, l);
+            let iter = match split_mode {
+                rust_superkmers::SplitMode::Classical => iteratorkmc2::SuperkmersIterator::classical_with_n(seq, k, l),
+                rust_superkmers::SplitMode::Msp => iteratorkmc2::SuperkmersIterator::msp_with_n(seq, k, l),
+                rust_superkmers::SplitMode::MspXor => iteratorkmc2::SuperkmersIterator::mspxor_with_n(seq, k, l),
+                rust_superkmers::SplitMode::Sticky => iteratorkmc2::SuperkmersIterator::new_with_n(seq, k, l),
+            };
             count_superkmers(iter, k, bucket_counts, total_kmers, total_superkmers);
         }
         "msp" => {
-            let superkmers = iteratormsp::superkmers_with_n(seq, k, l);
-            count_superkmers(superkmers.into_iter(), k, bucket_counts, total_kmers, total_superkmers);
+            // Sticky keeps rust-debruijn's forward-strand MSP
+            let iter = match split_mode {
+                rust_superkmers::SplitMode::Classical => iteratormsp::SuperkmersIterator::classical_with_n(seq, k, l),
+                rust_superkmers::SplitMode::Msp => iteratormsp::SuperkmersIterator::msp_with_n(seq, k, l),
+                rust_superkmers::SplitMode::MspXor => iteratormsp::SuperkmersIterator::mspxor_with_n(seq, k, l),
+                rust_superkmers::SplitMode::Sticky => iteratormsp::SuperkmersIterator::forward_with_n(seq, k, l),
+            };
+            count_superkmers(iter, k, bucket_counts, total_kmers, total_superkmers);
         }
         #[cfg(feature = "simd-mini")]
         "simdmini" => {
@@ -219,10 +330,36 @@ fn process_seq(seq: &[u8], k: usize, l: usize, method: &str, nb_hash: usize, spl
             };
             count_superkmers(iter, k, bucket_counts, total_kmers, total_superkmers);
         }
+        "random" => {
+            let iter = match split_mode {
+                rust_superkmers::SplitMode::Classical => iteratorrandom::SuperkmersIterator::classical_with_n(seq, k, l),
+                rust_superkmers::SplitMode::Msp => iteratorrandom::SuperkmersIterator::msp_with_n(seq, k, l),
+                rust_superkmers::SplitMode::MspXor => iteratorrandom::SuperkmersIterator::mspxor_with_n(seq, k, l),
+                rust_superkmers::SplitMode::Sticky => iteratorrandom::SuperkmersIterator::new_with_n(seq, k, l),
+            };
+            count_superkmers(iter, k, bucket_counts, total_kmers, total_superkmers);
+        }
         _ => unreachable!(),
     }
 }
 
+/// Count every contig of a packed genome into `bucket_counts`; returns (total kmers, total superkmers).
+fn process_packed_genome(genome: &PackedGenome, k: usize, l: usize, base_method: &str, split_mode: rust_superkmers::SplitMode, bucket_counts: &mut HashMap<u32, u64>) -> (u64, u64) {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let (mut total_kmers, mut total_superkmers) = (0, 0);
+    for (idx, contig) in genome.contigs().iter().enumerate() {
+        let superkmers = match base_method {
+            #[cfg(feature = "simd-mini")]
+            "simdmini" => genome.contig_superkmers_simdmini(idx, k, l, split_mode, true, threads),
+            "syncmer" => genome.contig_superkmers(idx, k, l, split_mode, true, threads),
+            _ => unreachable!(),
+        };
+        count_superkmers(superkmers.into_iter(), k, bucket_counts, &mut total_kmers, &mut total_superkmers);
+        eprintln!("  {} ({} bp): {} superkmers, {} kmers so far", contig.name, contig.len, total_superkmers, total_kmers);
+    }
+    (total_kmers, total_superkmers)
+}
+
 fn count_superkmers(iter: impl Iterator<Item = rust_superkmers::Superkmer>, k: usize, bucket_counts: &mut HashMap<u32, u64>, total_kmers: &mut u64, total_superkmers: &mut u64) {
     for superkmer in iter {
         let num_kmers = superkmer.size as u64 - k as u64 + 1;
@@ -232,90 +369,73 @@ fn count_superkmers(iter: impl Iterator<Item = rust_superkmers::Superkmer>, k: u
     }
 }
 
-fn print_stats(bucket_counts: &HashMap<u32, u64>, total_kmers: u64, total_superkmers: u64, l: usize) {
-    let mut sizes: Vec<u64> = bucket_counts.values().cloned().collect();
-    sizes.sort_unstable();
-
-    let n = sizes.len();
-    if n == 0 {
+fn print_stats(stats: &BucketStats) {
+    if stats.is_empty() {
         println!("No data.");
         return;
     }
 
-    let sum: u64 = sizes.iter().sum();
-    let mean = sum as f64 / n as f64;
-    let median = sizes[n / 2];
-    let p1 = sizes[n / 100];
-    let p5 = sizes[n * 5 / 100];
-    let p25 = sizes[n * 25 / 100];
-    let p75 = sizes[n * 75 / 100];
-    let p95 = sizes[n * 95 / 100];
-    let p99 = sizes[n * 99 / 100];
-    let max = *sizes.last().unwrap();
-    let min = sizes[0];
-
     println!("=== Bucket size distribution (kmers per minimizer) ===");
-    println!("Distinct minimizers: {}", n);
-    println!("Total kmers:        {}", total_kmers);
-    println!("Total superkmers:   {}", total_superkmers);
+    println!("Distinct minimizers: {}", stats.distinct_minimizers);
+    println!("Total kmers:        {}", stats.total_kmers);
+    println!("Total superkmers:   {}", stats.total_superkmers);
+    println!("Density:            {:.4} superkmers/kmer", stats.density());
+    println!("Mean superkmer len: {:.1}", stats.mean_superkmer_len());
     println!();
-    println!("Min:    {}", min);
-    println!("P1:     {}", p1);
-    println!("P5:     {}", p5);
-    println!("P25:    {}", p25);
-    println!("Median: {}", median);
-    println!("Mean:   {:.1}", mean);
-    println!("P75:    {}", p75);
-    println!("P95:    {}", p95);
-    println!("P99:    {}", p99);
-    println!("Max:    {}", max);
-    println!("Max/Mean: {:.1}x", max as f64 / mean);
-    println!("Max/Median: {:.1}x", max as f64 / median as f64);
+    println!("Min:    {}", stats.min);
+    println!("P1:     {}", stats.p1);
+    println!("P5:     {}", stats.p5);
+    println!("P25:    {}", stats.p25);
+    println!("Median: {}", stats.median);
+    println!("Mean:   {:.1}", stats.mean);
+    println!("P75:    {}", stats.p75);
+    println!("P95:    {}", stats.p95);
+    println!("P99:    {}", stats.p99);
+    println!("Max:    {}", stats.max);
+    println!("Max/Mean: {:.1}x", stats.max_over_mean());
+    println!("Max/Median: {:.1}x", stats.max_over_median());
     println!();
 
-    // Log2 histogram
-    let mut log_bins: HashMap<u32, (u64, u64)> = HashMap::new();
-    for &size in &sizes {
-        let bin = if size == 0 { 0 } else { 64 - size.leading_zeros() };
-        let entry = log_bins.entry(bin).or_insert((0, 0));
-        entry.0 += 1;
-        entry.1 += size;
-    }
-    let mut bin_keys: Vec<u32> = log_bins.keys().cloned().collect();
-    bin_keys.sort_unstable();
-
     println!("=== Histogram (bucket size -> count of minimizers) ===");
     println!("{:<20} {:>15} {:>15} {:>10}", "Bucket size range", "# minimizers", "# kmers", "% kmers");
-    for bin in bin_keys {
-        let (count, kmer_count) = log_bins[&bin];
-        let lo = if bin == 0 { 0 } else { 1u64 << (bin - 1) };
-        let hi = (1u64 << bin) - 1;
-        let pct = 100.0 * kmer_count as f64 / total_kmers as f64;
-        println!("{:<20} {:>15} {:>15} {:>9.2}%", format!("[{}, {}]", lo, hi), count, kmer_count, pct);
+    for bin in &stats.histogram {
+        let pct = 100.0 * stats.kmer_fraction(bin);
+        println!("{:<20} {:>15} {:>15} {:>9.2}%", format!("[{}, {}]", bin.lo, bin.hi), bin.minimizers, bin.kmers, pct);
     }
 
-    // Top 20
     println!();
-    println!("=== Top 20 largest buckets ===");
-    let mut by_size: Vec<(u32, u64)> = bucket_counts.iter().map(|(&k, &v)| (k, v)).collect();
-    by_size.sort_by(|a, b| b.1.cmp(&a.1));
-    for (i, (mint, count)) in by_size.iter().take(20).enumerate() {
-        let minimizer_str = decode_kmer(*mint as usize, l);
-        println!("  {:>2}. {} (mint={:>10})  {} kmers", i + 1, minimizer_str, mint, count);
+    println!("=== Top {} largest buckets ===", stats.top.len());
+    for (i, (mint, count)) in stats.top.iter().enumerate() {
+        println!("  {:>2}. {} (mint={:>10})  {} kmers", i + 1, decode_mint(*mint, stats.l), mint, count);
     }
 }
 
-fn decode_kmer(val: usize, l: usize) -> String {
-    let mut s = String::with_capacity(l);
-    for i in (0..l).rev() {
-        let base = (val >> (2 * i)) & 3;
-        s.push(match base {
-            0 => 'A',
-            1 => 'C',
-            2 => 'G',
-            3 => 'T',
-            _ => unreachable!(),
-        });
+fn add_sample_unit(sample: &mut BucketSample, unit_counts: HashMap<u32, u64>, bucket_counts: &mut HashMap<u32, u64>) {
+    for (&mint, &count) in &unit_counts {
+        *bucket_counts.entry(mint).or_insert(0) += count;
     }
-    s
+    sample.add_unit_counts(unit_counts);
+}
+
+fn print_sample_estimate(sample: &BucketSample, fraction: f64, seed: u64) {
+    let est = sample.estimate(100, seed);
+    println!();
+    println!("=== Sampled estimates (fraction {}, seed {}, {} units, 95% bootstrap CI) ===", fraction, seed, est.units);
+    println!("P99:      {:.0} [{:.0}, {:.0}]", est.p99.estimate, est.p99.low, est.p99.high);
+    println!("Max/Mean: {:.1}x [{:.1}x, {:.1}x]", est.max_over_mean.estimate, est.max_over_mean.low, est.max_over_mean.high);
+}
+
+fn print_partition_balance(bucket_counts: &HashMap<u32, u64>, num_partitions: usize) {
+    let map = balance_partitions(bucket_counts, num_partitions);
+    let mut hash_loads = vec![0u64; num_partitions];
+    for (&mint, &count) in bucket_counts {
+        hash_loads[hash_partition(mint, num_partitions)] += count;
+    }
+    let mean = bucket_counts.values().sum::<u64>() as f64 / num_partitions as f64;
+
+    println!();
+    println!("=== Partition balance ({} partitions) ===", num_partitions);
+    println!("Greedy (LPT): max load {} kmers, max/mean {:.3}x", map.loads().iter().max().unwrap(), map.imbalance());
+    let hash_max = *hash_loads.iter().max().unwrap();
+    println!("Hashed mint:  max load {} kmers, max/mean {:.3}x", hash_max, hash_max as f64 / mean);
 }
@@ -0,0 +1,40 @@
+use std::env;
+use std::fs;
+use std::io::BufWriter;
+
+use rust_superkmers::graph::{partition_kmers, CompactedGraph};
+use rust_superkmers::utils::fasta::open_fasta;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 5 {
+        eprintln!("Usage: {} <input.fa> <k> <l> <output.gfa> [threads]", args[0]);
+        eprintln!("  Builds the compacted de Bruijn graph of the input k-mers, bucketed by");
+        eprintln!("  MspXor l-mer minimizers, and writes its unitigs as GFA 1.0. k must be odd.");
+        std::process::exit(1);
+    }
+    let k: usize = args[2].parse().expect("Invalid k");
+    let l: usize = args[3].parse().expect("Invalid l");
+    let threads = args.get(5).map(|t| t.parse().expect("Invalid thread count"))
+        .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1));
+
+    let sequences = read_fasta(&args[1]);
+    let buckets = partition_kmers(sequences.iter().map(|s| &s[..]), k, l);
+    let num_kmers: usize = buckets.values().map(|b| b.len()).sum();
+    eprintln!("{} distinct k-mers in {} buckets", num_kmers, buckets.len());
+
+    let graph = CompactedGraph::from_buckets(buckets, k, l, threads);
+    let out = fs::File::create(&args[4]).expect("Failed to create output file");
+    graph.write_gfa(BufWriter::new(out)).expect("Failed to write GFA");
+    eprintln!("Done. {} unitigs, {} links -> {}", graph.unitigs.len(), graph.links.len(), args[4]);
+}
+
+fn read_fasta(path: &str) -> Vec<Vec<u8>> {
+    eprintln!("Reading {}", path);
+    let records = open_fasta(path).expect("Failed to open FASTA file");
+    let sequences: Vec<Vec<u8>> = records.map(|r| r.expect("Failed to read FASTA record").seq)
+        .filter(|seq| !seq.is_empty())
+        .collect();
+    eprintln!("Read {} sequences", sequences.len());
+    sequences
+}
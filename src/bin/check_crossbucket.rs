@@ -0,0 +1,128 @@
+use std::env;
+
+use rust_superkmers::crossbucket::check_fasta;
+use rust_superkmers::iteratorkmc2;
+use rust_superkmers::iteratormsp;
+use rust_superkmers::iteratorrandom;
+use rust_superkmers::iteratorsyncmers2;
+use rust_superkmers::iteratoruhs;
+#[cfg(feature = "simd-mini")]
+use rust_superkmers::iteratorsimdmini;
+#[cfg(feature = "simd-mini")]
+use rust_superkmers::iteratorsimdmini_cminim;
+use rust_superkmers::{SplitMode, Superkmer};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: {} <reads.fa> [k] [l] [method[:mode]] [examples]", args[0]);
+        eprintln!("  Checks that every canonical k-mer of the input is assigned a single minimizer");
+        eprintln!("  (bucket) across all reads, and reports the cross-bucket k-mers.");
+        eprintln!("  k: kmer length (default 31, at most 63)");
+        eprintln!("  l: minimizer length (default: 8, 9 for simdmini/cminim)");
+        eprintln!("  method: syncmer, uhs, random, kmc2, msp, simdmini, cminim (default syncmer:mspxor);");
+        eprintln!("          modes as in bucket_stats (classical, msp, mspxor, default sticky)");
+        eprintln!("  examples: number of offending k-mers to print (default 5)");
+        eprintln!("  Exit status is 2 when cross-bucket k-mers are found.");
+        std::process::exit(1);
+    }
+    let k: usize = args.get(2).map_or(31, |s| s.parse().expect("Invalid k"));
+    let method = args.get(4).map(|s| s.as_str()).unwrap_or("syncmer:mspxor");
+    let base_method = method.split(':').next().unwrap();
+    let l: usize = args.get(3).map_or(if ["simdmini", "cminim"].contains(&base_method) { 9 } else { 8 }, |s| s.parse().expect("Invalid l"));
+    let max_examples: usize = args.get(5).map_or(5, |s| s.parse().expect("Invalid example count"));
+    let split_mode = match method.split(':').nth(1).unwrap_or("") {
+        "classical" => SplitMode::Classical,
+        "msp" => SplitMode::Msp,
+        "mspxor" => SplitMode::MspXor,
+        _ => SplitMode::Sticky,
+    };
+    if k > 63 {
+        eprintln!("k must be at most 63");
+        std::process::exit(1);
+    }
+
+    let mut scheme = scheme(base_method, split_mode, k, l);
+    eprintln!("Checking {} with k={} l={} method={}", args[1], k, l, method);
+    let report = check_fasta(&args[1], k, max_examples, |seq| scheme(seq)).expect("Failed to read FASTA file");
+
+    println!("{}: {} distinct canonical k-mers, {} cross-bucket ({:.4}%)",
+        method, report.distinct_kmers, report.cross_bucket_kmers, 100.0 * report.fraction());
+    for (kmer, mints) in &report.examples {
+        println!("  {} -> mints {:?}", String::from_utf8_lossy(kmer), mints);
+    }
+    if !report.is_consistent() {
+        std::process::exit(2);
+    }
+}
+
+/// Superkmer extraction of one sequence, boxed so every method fits one type.
+type Scheme = Box<dyn FnMut(&[u8]) -> Vec<Superkmer>>;
+
+/// Superkmers of a sequence (may contain N) for the given method and mode.
+fn scheme(base_method: &str, split_mode: SplitMode, k: usize, l: usize) -> Scheme {
+    match base_method {
+        "syncmer" => {
+            let mut ext = match split_mode {
+                SplitMode::Classical => iteratorsyncmers2::SuperkmerExtractor::classical(k, l),
+                SplitMode::Msp => iteratorsyncmers2::SuperkmerExtractor::msp(k, l),
+                SplitMode::MspXor => iteratorsyncmers2::SuperkmerExtractor::mspxor(k, l),
+                SplitMode::Sticky => iteratorsyncmers2::SuperkmerExtractor::new(k, l),
+            };
+            Box::new(move |seq| ext.process_with_n(seq).to_vec())
+        }
+        "uhs" => {
+            let mut ext = match split_mode {
+                SplitMode::MspXor => iteratoruhs::SuperkmerExtractor::mspxor(k, l),
+                _ => iteratoruhs::SuperkmerExtractor::new(k, l),
+            };
+            Box::new(move |seq| ext.process_with_n(seq).to_vec())
+        }
+        "random" => {
+            let mut ext = match split_mode {
+                SplitMode::Classical => iteratorrandom::SuperkmerExtractor::classical(k, l),
+                SplitMode::Msp => iteratorrandom::SuperkmerExtractor::msp(k, l),
+                SplitMode::MspXor => iteratorrandom::SuperkmerExtractor::mspxor(k, l),
+                SplitMode::Sticky => iteratorrandom::SuperkmerExtractor::new(k, l),
+            };
+            Box::new(move |seq| ext.process_with_n(seq).to_vec())
+        }
+        "kmc2" => {
+            let mut ext = match split_mode {
+                SplitMode::Classical => iteratorkmc2::SuperkmerExtractor::classical(k, l),
+                SplitMode::Msp => iteratorkmc2::SuperkmerExtractor::msp(k, l),
+                SplitMode::MspXor => iteratorkmc2::SuperkmerExtractor::mspxor(k, l),
+                SplitMode::Sticky => iteratorkmc2::SuperkmerExtractor::new(k, l),
+            };
+            Box::new(move |seq| ext.process_with_n(seq).to_vec())
+        }
+        "msp" => {
+            let mut ext = match split_mode {
+                SplitMode::Classical => iteratormsp::SuperkmerExtractor::classical(k, l),
+                SplitMode::Msp => iteratormsp::SuperkmerExtractor::msp(k, l),
+                SplitMode::MspXor => iteratormsp::SuperkmerExtractor::mspxor(k, l),
+                SplitMode::Sticky => iteratormsp::SuperkmerExtractor::forward(k, l),
+            };
+            Box::new(move |seq| ext.process_with_n(seq).to_vec())
+        }
+        #[cfg(feature = "simd-mini")]
+        "simdmini" => {
+            let mut ext = match split_mode {
+                SplitMode::Classical => iteratorsimdmini::SuperkmerExtractor::classical(k, l),
+                SplitMode::Msp => iteratorsimdmini::SuperkmerExtractor::msp(k, l),
+                SplitMode::MspXor => iteratorsimdmini::SuperkmerExtractor::mspxor(k, l),
+                SplitMode::Sticky => iteratorsimdmini::SuperkmerExtractor::new(k, l),
+            };
+            Box::new(move |seq| ext.process_with_n(seq).to_vec())
+        }
+        #[cfg(feature = "simd-mini")]
+        "cminim" => {
+            let mut ext = iteratorsimdmini_cminim::SuperkmerExtractor::new(k, l);
+            Box::new(move |seq| ext.process_with_n(seq).to_vec())
+        }
+        _ => {
+            eprintln!("Unknown or unavailable method: '{}'. Run with no arguments to see usage.", base_method);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use std::env;
+
+use rust_superkmers::genome::{pack_fasta, PackedGenome};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        eprintln!("Usage: {} <genome.fa> <genome.skg>", args[0]);
+        eprintln!("  Converts a FASTA file to a memory-mappable 2-bit packed genome");
+        eprintln!("  (contig index + N-runs), usable as input to bucket_stats.");
+        std::process::exit(1);
+    }
+
+    eprintln!("Packing {} -> {}", args[1], args[2]);
+    pack_fasta(&args[1], &args[2]).expect("Failed to pack genome");
+
+    let genome = PackedGenome::open(&args[2]).expect("Failed to reopen packed genome");
+    let n_bases: usize = genome.contigs().iter().map(|c| c.n_runs.iter().map(|r| r.1).sum::<usize>()).sum();
+    eprintln!("Done. {} contigs, {} bases ({} N)", genome.contigs().len(), genome.total_len(), n_bases);
+}
@@ -0,0 +1,76 @@
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+
+use rust_superkmers::iteratorrandom::DEFAULT_SEED;
+use rust_superkmers::scoretable::{read_rank_array, read_ranked_lmers, ScoreTable};
+use rust_superkmers::SplitMode;
+
+fn usage(prog: &str) -> ! {
+    eprintln!("Usage: {} export <syncmer|uhs|random|kmc2|msp>[:mode] <l> <out.sks> [s|seed] [--non-canonical]", prog);
+    eprintln!("       {} import-ranked <lmers.txt> <mode> <out.sks> [--non-canonical]", prog);
+    eprintln!("       {} import-ranks <ranks.bin> <l> <mode> <out.sks> [--non-canonical]", prog);
+    eprintln!("       {} info <table.sks>", prog);
+    eprintln!("  export: save a built-in score table (s: syncmer s-mer length, default 2; seed: random order seed)");
+    eprintln!("  import-ranked: table from a ranked l-mer list, one l-mer per line, best first");
+    eprintln!("  import-ranks: table from a dense array of little-endian u64 ranks, one per l-mer");
+    eprintln!("  mode: sticky, classical, msp or mspxor. Tables are usable with bucket_stats --scores=.");
+    std::process::exit(1);
+}
+
+fn parse_mode(s: &str) -> SplitMode {
+    match s {
+        "" | "sticky" => SplitMode::Sticky,
+        "classical" => SplitMode::Classical,
+        "msp" => SplitMode::Msp,
+        "mspxor" => SplitMode::MspXor,
+        _ => {
+            eprintln!("Unknown mode: '{}'", s);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    let all_args: Vec<String> = env::args().collect();
+    let canonical = !all_args.iter().any(|a| a == "--non-canonical");
+    let args: Vec<&str> = all_args.iter().map(|s| s.as_str()).filter(|a| *a != "--non-canonical").collect();
+    let parse_l = |s: &str| -> usize { s.parse().expect("Invalid l") };
+
+    let (table, out) = match args.get(1..) {
+        Some(["export", scheme, l, out, rest @ ..]) => {
+            let (base, mode) = scheme.split_once(':').unwrap_or((scheme, ""));
+            let (l, mode) = (parse_l(l), parse_mode(mode));
+            let table = match base {
+                "syncmer" => ScoreTable::syncmer(l, rest.first().map_or(2, |s| s.parse().expect("Invalid s")), mode, canonical),
+                "uhs" => ScoreTable::uhs(l, mode, canonical),
+                "random" => ScoreTable::random(l, mode, canonical, rest.first().map_or(DEFAULT_SEED, |s| s.parse().expect("Invalid seed"))),
+                "kmc2" => ScoreTable::kmc2(l, mode, canonical),
+                "msp" => ScoreTable::lexicographic(l, mode, canonical),
+                _ => usage(args[0]),
+            };
+            (table, *out)
+        }
+        Some(["import-ranked", path, mode, out]) => {
+            let (l, ranks) = read_ranked_lmers(BufReader::new(File::open(path).expect("Failed to open ranked list")))
+                .expect("Failed to read ranked list");
+            (ScoreTable::from_ranks("ranked", l, parse_mode(mode), canonical, &ranks).expect("Invalid ranks"), *out)
+        }
+        Some(["import-ranks", path, l, mode, out]) => {
+            let l = parse_l(l);
+            let ranks = read_rank_array(BufReader::new(File::open(path).expect("Failed to open rank array")), l)
+                .expect("Failed to read rank array");
+            (ScoreTable::from_ranks("ranked", l, parse_mode(mode), canonical, &ranks).expect("Invalid ranks"), *out)
+        }
+        Some(["info", path]) => {
+            let table = ScoreTable::load(path).expect("Failed to load score table");
+            let distinct = table.scores.iter().collect::<std::collections::HashSet<_>>().len();
+            println!("name={} l={} s={} mode={:?} canonical={} scores={} distinct={} checksum={:016x}",
+                table.name, table.l, table.s, table.mode, table.canonical, table.scores.len(), distinct, table.checksum());
+            return;
+        }
+        _ => usage(args[0]),
+    };
+    table.save(out).expect("Failed to write score table");
+    eprintln!("Wrote {} (name={} l={} mode={:?} canonical={} checksum {:016x})", out, table.name, table.l, table.mode, table.canonical, table.checksum());
+}
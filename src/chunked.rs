@@ -0,0 +1,129 @@
+//! Chunked processing of one long sequence with exact seam stitching.
+//!
+//! `chunk_ranges` cuts a sequence into ranges that overlap by k-1 bases, so
+//! every k-mer lies in exactly one range. Each range is extracted independently
+//! (e.g. on its own thread) with an extractor's `process_range`, which reports
+//! positions in whole-sequence coordinates. `stitch_superkmers` then concatenates
+//! the chunk outputs, re-joining the superkmer that each seam cut in two.
+//!
+//! A superkmer is cut by a seam when the last k-mer of one chunk and the first
+//! k-mer of the next have the same minimizer occurrence. Both halves then carry
+//! the same absolute minimizer position (`start + mpos`) and are merged back.
+//!
+//! Seam guarantees per `SplitMode`:
+//! - `Classical`, `Msp`, `MspXor`: the minimizer of a k-mer depends only on the
+//!   k-mer itself, so the stitched result is identical to a single pass.
+//! - `Sticky`: the minimizer of a k-mer depends on where the superkmer started.
+//!   Each chunk starts from a fresh window scan, so the first few superkmers after
+//!   a seam can have different boundaries (and a different but equal-score
+//!   minimizer) than a single pass, until both runs pick the same minimizer again.
+//!   The stitched output still tiles every k-mer exactly once.
+//!
+//! ```
+//! use rust_superkmers::chunked::{chunk_ranges, stitch_superkmers};
+//! use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+//!
+//! let seq = b"AACTGCACTGCACTGCACTGCACACTGCACTGCACTGCACTGCACACTGCACTGCACTGACTGCACTGCACTGCACTGCACTGCCTGC";
+//! let (k, l) = (21, 8);
+//! let parts: Vec<Vec<_>> = std::thread::scope(|s| {
+//!     let handles: Vec<_> = chunk_ranges(seq.len(), k, 3).into_iter().map(|(start, end)| {
+//!         s.spawn(move || SuperkmerExtractor::mspxor(k, l).process_range(seq, start, end).to_vec())
+//!     }).collect();
+//!     handles.into_iter().map(|h| h.join().unwrap()).collect()
+//! });
+//! let stitched = stitch_superkmers(&parts, k);
+//! assert_eq!(stitched, SuperkmerExtractor::mspxor(k, l).process(seq));
+//! ```
+use crate::Superkmer;
+
+/// `process_range` and `process_range_with_n` for an extractor with `process`,
+/// `process_with_n` and a `superkmers` output buffer.
+macro_rules! process_range_methods {
+    () => {
+        /// Process bases `[start, end)` of `seq` (no N characters) as an independent chunk.
+        /// Superkmer positions are reported in `seq` coordinates. Use with
+        /// `chunked::chunk_ranges` / `chunked::stitch_superkmers` to split a long sequence.
+        pub fn process_range(&mut self, seq: &[u8], start: usize, end: usize) -> &[$crate::Superkmer] {
+            self.process(&seq[start..end]);
+            for sk in self.superkmers.iter_mut() {
+                sk.start += start;
+            }
+            &self.superkmers
+        }
+
+        /// Same as `process_range` for a range that may contain N/n characters.
+        pub fn process_range_with_n(&mut self, seq: &[u8], start: usize, end: usize) -> &[$crate::Superkmer] {
+            self.process_with_n(&seq[start..end]);
+            for sk in self.superkmers.iter_mut() {
+                sk.start += start;
+            }
+            &self.superkmers
+        }
+    };
+}
+pub(crate) use process_range_methods;
+
+/// Split `[0, seq_len)` into at most `num_chunks` ranges `(start, end)` that overlap
+/// by k-1 bases. Each range holds at least one k-mer and every k-mer of the sequence
+/// lies in exactly one range. Returns no ranges if `seq_len < k`.
+pub fn chunk_ranges(seq_len: usize, k: usize, num_chunks: usize) -> Vec<(usize, usize)> {
+    if seq_len < k {
+        return Vec::new();
+    }
+    let num_kmers = seq_len - k + 1;
+    let per_chunk = num_kmers.div_ceil(num_chunks.max(1));
+    chunk_ranges_by_kmers(0, seq_len, k, per_chunk)
+}
+
+/// Split `[start, end)` into consecutive ranges of `kmers_per_chunk` k-mers
+/// (the last one may be shorter), overlapping by k-1 bases.
+pub fn chunk_ranges_by_kmers(start: usize, end: usize, k: usize, kmers_per_chunk: usize) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    if end < start + k {
+        return ranges;
+    }
+    let num_kmers = end - start - k + 1;
+    let mut first = 0;
+    while first < num_kmers {
+        let n = std::cmp::min(kmers_per_chunk.max(1), num_kmers - first);
+        ranges.push((start + first, start + first + n + k - 1));
+        first += n;
+    }
+    ranges
+}
+
+/// Merge two superkmers cut apart by a chunk seam: `next` starts at the k-mer right
+/// after the last k-mer of `prev`, and both share the same absolute minimizer position.
+/// Returns true if `next` was merged into `prev`.
+pub fn merge_seam(prev: &mut Superkmer, next: &Superkmer, k: usize) -> bool {
+    let contiguous = next.start + k == prev.start + prev.size as usize + 1;
+    let same_minimizer = prev.start + prev.mpos as usize == next.start + next.mpos as usize
+        && prev.mint == next.mint;
+    if contiguous && same_minimizer {
+        prev.size = (next.start + next.size as usize - prev.start) as u16;
+        true
+    } else {
+        false
+    }
+}
+
+/// Append the superkmers of the next chunk to `out`, merging across the seam.
+/// Chunks must be appended in sequence order.
+pub fn stitch_into(out: &mut Vec<Superkmer>, next: &[Superkmer], k: usize) {
+    let mut rest = next;
+    if let (Some(prev), Some(first)) = (out.last_mut(), next.first()) {
+        if merge_seam(prev, first, k) {
+            rest = &next[1..];
+        }
+    }
+    out.extend_from_slice(rest);
+}
+
+/// Concatenate per-chunk superkmers (in sequence order), merging across seams.
+pub fn stitch_superkmers(chunks: &[Vec<Superkmer>], k: usize) -> Vec<Superkmer> {
+    let mut out = Vec::with_capacity(chunks.iter().map(|c| c.len()).sum());
+    for chunk in chunks {
+        stitch_into(&mut out, chunk, k);
+    }
+    out
+}
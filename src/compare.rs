@@ -0,0 +1,167 @@
+//! Differential testing of two schemes expected to produce identical superkmers
+//! (e.g. `iteratorsyncmers2` and `iteratorsyncmersmsp`, or a SIMD batch extractor
+//! and the scalar `mspxor` extractor).
+//!
+//! Schemes are functions from a sequence to its superkmers; results are compared
+//! after sorting by `start`, on every field. A scheme that does not fill some field
+//! (e.g. `mint_is_rc`) can be normalized in its closure.
+//!
+//! `compare_random` is a property-based mode: it generates sequences with Ns,
+//! lowercase runs and lengths around k, and shrinks the first failing input.
+//!
+//! ```
+//! use rust_superkmers::compare::compare_random;
+//! use rust_superkmers::iteratorsyncmers2::{SuperkmerExtractor, SuperkmersIterator};
+//! let mut ext = SuperkmerExtractor::mspxor(21, 8);
+//! let result = compare_random(
+//!     |s| ext.process_with_n(s).to_vec(),
+//!     |s| SuperkmersIterator::mspxor_with_n(s, 21, 8).collect(),
+//!     21, 8, 50, 1,
+//! );
+//! assert_eq!(result, Ok(50));
+//! ```
+use std::fmt;
+
+use crate::stats::decode_mint;
+use crate::Superkmer;
+
+/// Bases of context shown around a divergence.
+const CONTEXT: usize = 10;
+
+/// First difference found between two schemes.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Divergence {
+    /// Index of the input sequence.
+    pub input: usize,
+    /// The input (shrunk in `compare_random`).
+    pub seq: Vec<u8>,
+    /// Index of the first differing superkmer (after sorting by start).
+    pub index: usize,
+    /// The superkmers at `index` of each scheme; `None` when one ran out.
+    pub a: Option<Superkmer>,
+    pub b: Option<Superkmer>,
+    pub l: usize,
+}
+
+impl Divergence {
+    /// Range of `seq` covering both superkmers, with some context.
+    pub fn window(&self) -> (usize, usize) {
+        let spans = [self.a, self.b].into_iter().flatten().map(|sk| (sk.start, sk.start + sk.size as usize));
+        let (lo, hi) = spans.fold((usize::MAX, 0), |(lo, hi), (s, e)| (lo.min(s), hi.max(e)));
+        if lo > hi {
+            return (0, self.seq.len());
+        }
+        (lo.saturating_sub(CONTEXT), (hi + CONTEXT).min(self.seq.len()))
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter<'_>, name: &str, sk: Option<Superkmer>) -> fmt::Result {
+        match sk {
+            None => writeln!(f, "  {}: (no superkmer)", name),
+            Some(sk) => {
+                let pos = sk.start + sk.mpos as usize;
+                let lmer = self.seq.get(pos..pos + self.l).map_or(String::new(), |s| String::from_utf8_lossy(s).into_owned());
+                writeln!(f, "  {}: start={} size={} mpos={} mint={} ({}) mint_is_rc={} l-mer at {}: {}",
+                    name, sk.start, sk.size, sk.mpos, sk.mint, decode_mint(sk.mint, self.l), sk.mint_is_rc, pos, lmer)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "schemes diverge on input {} (length {}) at superkmer {}", self.input, self.seq.len(), self.index)?;
+        self.describe(f, "a", self.a)?;
+        self.describe(f, "b", self.b)?;
+        let (lo, hi) = self.window();
+        write!(f, "  seq[{}..{}]: {}", lo, hi, String::from_utf8_lossy(&self.seq[lo..hi]))
+    }
+}
+
+fn sorted<F: FnMut(&[u8]) -> Vec<Superkmer>>(scheme: &mut F, seq: &[u8]) -> Vec<Superkmer> {
+    let mut sks = scheme(seq);
+    sks.sort();
+    sks
+}
+
+/// First divergence of `a` and `b` on `seq`, if any.
+fn diverge<A, B>(a: &mut A, b: &mut B, seq: &[u8], input: usize, l: usize) -> Option<Divergence>
+where
+    A: FnMut(&[u8]) -> Vec<Superkmer>,
+    B: FnMut(&[u8]) -> Vec<Superkmer>,
+{
+    let (ra, rb) = (sorted(a, seq), sorted(b, seq));
+    let index = (0..ra.len().max(rb.len())).find(|&i| ra.get(i) != rb.get(i))?;
+    Some(Divergence { input, seq: seq.to_vec(), index, a: ra.get(index).copied(), b: rb.get(index).copied(), l })
+}
+
+/// Run both schemes on every input. Returns the number of inputs compared, or the
+/// first divergence.
+pub fn compare_schemes<'a, A, B, I>(mut a: A, mut b: B, inputs: I, l: usize) -> Result<usize, Divergence>
+where
+    A: FnMut(&[u8]) -> Vec<Superkmer>,
+    B: FnMut(&[u8]) -> Vec<Superkmer>,
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let mut count = 0;
+    for (input, seq) in inputs.into_iter().enumerate() {
+        if let Some(d) = diverge(&mut a, &mut b, seq, input, l) {
+            return Err(d);
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Random test input number `case`: lengths around k and up to a few hundred bases,
+/// with some Ns and lowercase runs.
+pub fn random_input(case: u64, seed: u64, k: usize) -> Vec<u8> {
+    let mut x = seed ^ case.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    let mut next = |n: u64| {
+        x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (x >> 33) % n
+    };
+    let len = match next(4) {
+        0 => (k + next(5) as usize).saturating_sub(2),
+        1 => k + next(64) as usize,
+        _ => next(400) as usize,
+    };
+    let mut seq: Vec<u8> = (0..len).map(|_| b"ACGT"[next(4) as usize]).collect();
+    if len > 0 {
+        for _ in 0..next(3) {
+            seq[next(len as u64) as usize] = b'N';
+        }
+        if next(3) == 0 {
+            let start = next(len as u64) as usize;
+            let end = (start + next(2 * k as u64) as usize).min(len);
+            seq[start..end].make_ascii_lowercase();
+        }
+    }
+    seq
+}
+
+/// Property-based comparison on `cases` random inputs (see `random_input`). On failure
+/// the input is shrunk by trimming both ends while the schemes still diverge.
+pub fn compare_random<A, B>(mut a: A, mut b: B, k: usize, l: usize, cases: u64, seed: u64) -> Result<usize, Divergence>
+where
+    A: FnMut(&[u8]) -> Vec<Superkmer>,
+    B: FnMut(&[u8]) -> Vec<Superkmer>,
+{
+    for case in 0..cases {
+        let seq = random_input(case, seed, k);
+        if let Some(mut d) = diverge(&mut a, &mut b, &seq, case as usize, l) {
+            let mut step = d.seq.len() / 2;
+            while step > 0 {
+                let n = d.seq.len();
+                let smaller = [&d.seq[step..], &d.seq[..n - step]].into_iter()
+                    .find_map(|s| diverge(&mut a, &mut b, s, case as usize, l));
+                match smaller {
+                    Some(s) => d = s,
+                    None => step /= 2,
+                }
+                step = step.min(d.seq.len() / 2);
+            }
+            return Err(d);
+        }
+    }
+    Ok(cases as usize)
+}
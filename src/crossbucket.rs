@@ -0,0 +1,124 @@
+//! Cross-bucket consistency check: does every canonical k-mer land in a single bucket?
+//!
+//! With a context-independent canonical scheme (Msp, MspXor) a k-mer's `mint`
+//! depends only on the k-mer, so a k-mer seen in several reads (on either strand)
+//! always gets the same `mint`. Context-dependent schemes (Sticky) break this; such
+//! k-mers are counted in several buckets and "cross-bucket" k-mers are reported.
+//!
+//! K-mers are stored as packed canonical `graph::Kmer` keys (k <= 63), with a single
+//! `mint` per k-mer; the extra mints of offending k-mers are kept aside.
+//!
+//! ```
+//! use rust_superkmers::crossbucket::CrossBucketChecker;
+//! use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+//! let seq = b"AACTGCACTGCACTGCACTGCACACTGCACTGCACTGCACTGCACACTGCACTGCACTGACTGCACTGCACTGCACTGCACTGCCTGC";
+//! let mut ext = SuperkmerExtractor::mspxor(21, 8);
+//! let mut checker = CrossBucketChecker::new(21);
+//! checker.add_sequence(seq, &mut |s| ext.process_with_n(s).to_vec());
+//! checker.add_sequence(&seq[17..70], &mut |s| ext.process_with_n(s).to_vec());
+//! let report = checker.report(5);
+//! assert_eq!(report.cross_bucket_kmers, 0);
+//! ```
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use crate::graph::{decode_kmer, Kmer};
+use crate::utils::encode_base;
+use crate::utils::fasta::open_fasta;
+use crate::Superkmer;
+
+/// Result of a cross-bucket check.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CrossBucketReport {
+    pub distinct_kmers: usize,
+    pub cross_bucket_kmers: usize,
+    /// Some offending canonical k-mers with all their mints, smallest k-mers first.
+    pub examples: Vec<(Vec<u8>, Vec<u32>)>,
+}
+
+impl CrossBucketReport {
+    /// Share of distinct canonical k-mers found in more than one bucket.
+    pub fn fraction(&self) -> f64 {
+        if self.distinct_kmers == 0 { 0.0 } else { self.cross_bucket_kmers as f64 / self.distinct_kmers as f64 }
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.cross_bucket_kmers == 0
+    }
+}
+
+/// Accumulates canonical k-mer -> mint over any number of sequences.
+pub struct CrossBucketChecker {
+    k: usize,
+    mints: HashMap<Kmer, u32>,
+    extra_mints: HashMap<Kmer, Vec<u32>>,
+}
+
+impl CrossBucketChecker {
+    pub fn new(k: usize) -> Self {
+        assert!((1..=63).contains(&k), "cross-bucket check needs k <= 63");
+        CrossBucketChecker { k, mints: HashMap::new(), extra_mints: HashMap::new() }
+    }
+
+    /// Cut `seq` with `scheme` and record the mint of each of its k-mers.
+    pub fn add_sequence<F: FnMut(&[u8]) -> Vec<Superkmer>>(&mut self, seq: &[u8], scheme: &mut F) {
+        if seq.len() >= self.k {
+            let sks = scheme(seq);
+            self.add_superkmers(seq, &sks);
+        }
+    }
+
+    /// Record the mint of each k-mer of superkmers `sks` of `seq`.
+    pub fn add_superkmers(&mut self, seq: &[u8], sks: &[Superkmer]) {
+        let k = self.k;
+        let mask: Kmer = (1 << (2 * k)) - 1;
+        for sk in sks {
+            let (mut fwd, mut rc): (Kmer, Kmer) = (0, 0);
+            for (i, &b) in seq[sk.start..sk.start + sk.size as usize].iter().enumerate() {
+                let code = encode_base(b) as Kmer;
+                fwd = ((fwd << 2) | code) & mask;
+                rc = (rc >> 2) | ((3 - code) << (2 * (k - 1)));
+                if i + 1 >= k {
+                    self.record(fwd.min(rc), sk.mint);
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, kmer: Kmer, mint: u32) {
+        match self.mints.get(&kmer) {
+            None => {
+                self.mints.insert(kmer, mint);
+            }
+            Some(&first) if first != mint => {
+                let extra = self.extra_mints.entry(kmer).or_default();
+                if !extra.contains(&mint) {
+                    extra.push(mint);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Counts so far, with up to `max_examples` offending k-mers.
+    pub fn report(&self, max_examples: usize) -> CrossBucketReport {
+        let mut offenders: Vec<&Kmer> = self.extra_mints.keys().collect();
+        offenders.sort_unstable();
+        let examples = offenders.into_iter().take(max_examples).map(|kmer| {
+            let mut mints = vec![self.mints[kmer]];
+            mints.extend_from_slice(&self.extra_mints[kmer]);
+            (decode_kmer(*kmer, self.k), mints)
+        }).collect();
+        CrossBucketReport { distinct_kmers: self.mints.len(), cross_bucket_kmers: self.extra_mints.len(), examples }
+    }
+}
+
+/// Check `scheme` over every record of a FASTA file.
+pub fn check_fasta<P: AsRef<Path>, F: FnMut(&[u8]) -> Vec<Superkmer>>(path: P, k: usize, max_examples: usize, mut scheme: F) -> io::Result<CrossBucketReport> {
+    let mut checker = CrossBucketChecker::new(k);
+    for record in open_fasta(path)? {
+        checker.add_sequence(&record?.seq, &mut scheme);
+    }
+    Ok(checker.report(max_examples))
+}
@@ -0,0 +1,133 @@
+//! Superkmer deduplication within a bucket.
+//!
+//! Input superkmers must be in minimizer-canonical orientation
+//! (`orientation::canonical_superkmer`), so copies from both strands compare equal.
+//!
+//! - `dedup_exact` collapses identical superkmers and counts them.
+//! - `merge_overlapping` joins superkmers with the same `mint` whose minimizers
+//!   line up and whose bases agree over an overlap of at least k-1 bases. Such a
+//!   join adds no k-mer that was not already in one of the inputs, and every k-mer
+//!   of the result still contains the shared minimizer, so the output is a smaller
+//!   set of maximal superkmers with exactly the same k-mers.
+//!
+//! Counts are per superkmer, not per k-mer, so `merge_overlapping` does not carry
+//! them: count on the output of `dedup_exact`, merge when only the k-mer set matters.
+//!
+//! ```
+//! use rust_superkmers::dedup::{dedup_exact, merge_overlapping};
+//! use rust_superkmers::orientation::canonical_superkmer;
+//! use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+//!
+//! let genome = b"AACTGCACTGCACTGCACTGCACACTGCACTGCACTGCACTGCACACTGCACTGCACTGACTGCACTGCACTGCACTGCACTGCCTGC";
+//! let mut ext = SuperkmerExtractor::mspxor(21, 8);
+//! let mut bucket = Vec::new();
+//! for read in [&genome[0..50], &genome[0..50], &genome[20..80]] {
+//!     bucket.extend(ext.process(read).iter().map(|sk| canonical_superkmer(read, sk, 8)));
+//! }
+//! let counted = dedup_exact(bucket.clone());
+//! assert_eq!(counted.iter().map(|(_, c)| *c as usize).sum::<usize>(), bucket.len());
+//! assert!(merge_overlapping(bucket, 21).len() <= counted.len());
+//! ```
+use crate::minimizer_core::get_base;
+use crate::orientation::CanonicalSuperkmer;
+use crate::utils::bitpack_fragment;
+
+/// Collapse identical superkmers, returning each distinct one with its multiplicity,
+/// sorted by (`mint`, `size`, `mpos`, bases).
+pub fn dedup_exact(mut sks: Vec<CanonicalSuperkmer>) -> Vec<(CanonicalSuperkmer, u32)> {
+    sks.sort_unstable();
+    let mut out: Vec<(CanonicalSuperkmer, u32)> = Vec::with_capacity(sks.len());
+    for sk in sks {
+        match out.last_mut() {
+            Some((last, count)) if *last == sk => *count += 1,
+            _ => out.push((sk, 1)),
+        }
+    }
+    out
+}
+
+/// A superkmer being grown, as ASCII bases with the minimizer at `seq[left..]`.
+struct Span {
+    left: usize,
+    seq: Vec<u8>,
+}
+
+impl Span {
+    fn right(&self) -> usize {
+        self.seq.len() - self.left
+    }
+
+    /// Extend `self` with `other` if, aligned on the minimizer, they overlap by at
+    /// least `min_overlap` bases and agree on all of them.
+    fn try_merge(&mut self, other: &Span, min_overlap: usize) -> bool {
+        let left = self.left.min(other.left);
+        let right = self.right().min(other.right());
+        if left + right < min_overlap
+            || self.seq[self.left - left..self.left + right] != other.seq[other.left - left..other.left + right]
+        {
+            return false;
+        }
+        if other.left > self.left {
+            let mut seq = other.seq[..other.left - self.left].to_vec();
+            seq.extend_from_slice(&self.seq);
+            self.seq = seq;
+            self.left = other.left;
+        }
+        if other.right() > self.right() {
+            let from = other.left + self.right();
+            self.seq.extend_from_slice(&other.seq[from..]);
+        }
+        true
+    }
+}
+
+fn merge_group(mint: u32, group: &[CanonicalSuperkmer], k: usize, out: &mut Vec<CanonicalSuperkmer>) {
+    const BASES: [u8; 4] = [b'A', b'C', b'G', b'T'];
+    let mut spans: Vec<Span> = group.iter().map(|sk| Span {
+        left: sk.mpos as usize,
+        seq: (0..sk.size as usize).map(|i| BASES[get_base(&sk.bases, i)]).collect(),
+    }).collect();
+
+    // Sweep from the leftmost span start. A span's start is fixed by its first member
+    // and only its right end grows, so a merge never brings two kept spans within
+    // overlap range: each span is tried once against the spans still reaching it.
+    spans.sort_unstable_by(|a, b| b.left.cmp(&a.left).then_with(|| b.seq.len().cmp(&a.seq.len())));
+    let mut active: Vec<Span> = Vec::new();
+    let mut done: Vec<Span> = Vec::new();
+    for span in spans {
+        // Later spans start further right, so these can no longer overlap by k-1
+        let mut i = 0;
+        while i < active.len() {
+            if span.left + active[i].right() < k - 1 {
+                done.push(active.swap_remove(i));
+            } else {
+                i += 1;
+            }
+        }
+        if !active.iter_mut().any(|m| m.try_merge(&span, k - 1)) {
+            active.push(span);
+        }
+    }
+    done.extend(active);
+
+    out.extend(done.into_iter().map(|s| CanonicalSuperkmer {
+        mint,
+        size: s.seq.len() as u16,
+        mpos: s.left as u16,
+        bases: bitpack_fragment(&s.seq),
+    }));
+}
+
+/// Merge same-`mint` superkmers that overlap by at least k-1 bases (aligned on the
+/// minimizer) into maximal superkmers. Duplicates and contained superkmers are absorbed.
+/// The output holds exactly the k-mers of the input, sorted like `dedup_exact`.
+pub fn merge_overlapping(mut sks: Vec<CanonicalSuperkmer>, k: usize) -> Vec<CanonicalSuperkmer> {
+    sks.sort_unstable();
+    sks.dedup();
+    let mut out = Vec::with_capacity(sks.len());
+    for group in sks.chunk_by(|a, b| a.mint == b.mint) {
+        merge_group(group[0].mint, group, k, &mut out);
+    }
+    out.sort_unstable();
+    out
+}
@@ -0,0 +1,240 @@
+//! C API for the superkmer extractors, built into the `cdylib` and `staticlib` targets.
+//! The header is `include/rust_superkmers.h`, generated by cbindgen:
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/rust_superkmers.h
+//! ```
+//!
+//! An extractor is created from a scheme spec such as `"syncmer:mspxor"`, `"uhs:mspxor"`,
+//! `"random:msp"`, `"msp:mspxor"` or `"kmc2"` (modes and their meaning as in `bucket_stats`; append `:nc` for
+//! forward-strand minimizers). Every function returns an `SkStatus`; panics are caught
+//! at the boundary and reported as `SK_PANIC`. Arrays handed out by an extractor stay
+//! valid until its next `sk_extractor_process` or `sk_extractor_free`.
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use crate::{iteratorkmc2, iteratormsp, iteratorrandom, iteratorsyncmers2, iteratoruhs, SplitMode, Superkmer};
+
+/// Status code returned by every C API function.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SkStatus {
+    SkOk = 0,
+    /// A required pointer argument was NULL.
+    SkNullPointer = 1,
+    /// The scheme spec is not valid UTF-8 or names an unknown scheme or mode.
+    SkInvalidScheme = 2,
+    /// k or l is not supported by the scheme.
+    SkInvalidParameters = 3,
+    /// The library panicked; the extractor should be freed.
+    SkPanic = 4,
+}
+
+enum Scheme {
+    Syncmer(iteratorsyncmers2::SuperkmerExtractor),
+    Uhs(iteratoruhs::SuperkmerExtractor),
+    Random(iteratorrandom::SuperkmerExtractor),
+    Kmc2(iteratorkmc2::SuperkmerExtractor),
+    Lexicographic(iteratormsp::SuperkmerExtractor),
+}
+
+/// Opaque extractor handle.
+pub struct SkExtractor {
+    scheme: Scheme,
+}
+
+impl SkExtractor {
+    /// Parse a scheme spec and check (k, l) against what the scheme supports.
+    fn new(spec: &str, k: usize, l: usize) -> Result<Self, SkStatus> {
+        let mut parts = spec.split(':');
+        let base = parts.next().unwrap_or("");
+        let (mut mode, mut canonical) = (SplitMode::Sticky, true);
+        for part in parts {
+            match part {
+                "sticky" => mode = SplitMode::Sticky,
+                "classical" => mode = SplitMode::Classical,
+                "msp" => mode = SplitMode::Msp,
+                "mspxor" => mode = SplitMode::MspXor,
+                "nc" => canonical = false,
+                _ => return Err(SkStatus::SkInvalidScheme),
+            }
+        }
+        let supported_l: &[usize] = match base {
+            "syncmer" => &[8, 9],
+            "uhs" => &[7, 8, 9, 11],
+            "kmc2" => &[7, 8, 9, 10, 11, 12],
+            "random" => &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+            "msp" => &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+            _ => return Err(SkStatus::SkInvalidScheme),
+        };
+        if !supported_l.contains(&l) || k < l || k > u16::MAX as usize {
+            return Err(SkStatus::SkInvalidParameters);
+        }
+        let scheme = match base {
+            "syncmer" => Scheme::Syncmer(match (mode, canonical) {
+                (SplitMode::Sticky, true) => iteratorsyncmers2::SuperkmerExtractor::new(k, l),
+                (SplitMode::Sticky, false) => iteratorsyncmers2::SuperkmerExtractor::non_canonical(k, l),
+                (SplitMode::Classical, true) => iteratorsyncmers2::SuperkmerExtractor::classical(k, l),
+                (SplitMode::Classical, false) => iteratorsyncmers2::SuperkmerExtractor::classical_non_canonical(k, l),
+                (SplitMode::Msp, true) => iteratorsyncmers2::SuperkmerExtractor::msp(k, l),
+                (SplitMode::Msp, false) => iteratorsyncmers2::SuperkmerExtractor::msp_non_canonical(k, l),
+                (SplitMode::MspXor, true) => iteratorsyncmers2::SuperkmerExtractor::mspxor(k, l),
+                (SplitMode::MspXor, false) => iteratorsyncmers2::SuperkmerExtractor::mspxor_non_canonical(k, l),
+            }),
+            "random" => Scheme::Random(match (mode, canonical) {
+                (SplitMode::Sticky, true) => iteratorrandom::SuperkmerExtractor::new(k, l),
+                (SplitMode::Sticky, false) => iteratorrandom::SuperkmerExtractor::non_canonical(k, l),
+                (SplitMode::Classical, true) => iteratorrandom::SuperkmerExtractor::classical(k, l),
+                (SplitMode::Classical, false) => iteratorrandom::SuperkmerExtractor::classical_non_canonical(k, l),
+                (SplitMode::Msp, true) => iteratorrandom::SuperkmerExtractor::msp(k, l),
+                (SplitMode::Msp, false) => iteratorrandom::SuperkmerExtractor::msp_non_canonical(k, l),
+                (SplitMode::MspXor, true) => iteratorrandom::SuperkmerExtractor::mspxor(k, l),
+                (SplitMode::MspXor, false) => iteratorrandom::SuperkmerExtractor::mspxor_non_canonical(k, l),
+            }),
+            "kmc2" => Scheme::Kmc2(match (mode, canonical) {
+                (SplitMode::Sticky, true) => iteratorkmc2::SuperkmerExtractor::new(k, l),
+                (SplitMode::Sticky, false) => iteratorkmc2::SuperkmerExtractor::non_canonical(k, l),
+                (SplitMode::Classical, true) => iteratorkmc2::SuperkmerExtractor::classical(k, l),
+                (SplitMode::Classical, false) => iteratorkmc2::SuperkmerExtractor::classical_non_canonical(k, l),
+                (SplitMode::Msp, true) => iteratorkmc2::SuperkmerExtractor::msp(k, l),
+                (SplitMode::Msp, false) => iteratorkmc2::SuperkmerExtractor::msp_non_canonical(k, l),
+                (SplitMode::MspXor, true) => iteratorkmc2::SuperkmerExtractor::mspxor(k, l),
+                (SplitMode::MspXor, false) => iteratorkmc2::SuperkmerExtractor::mspxor_non_canonical(k, l),
+            }),
+            "msp" => Scheme::Lexicographic(match (mode, canonical) {
+                (SplitMode::Sticky, true) => iteratormsp::SuperkmerExtractor::sticky(k, l),
+                (SplitMode::Sticky, false) => iteratormsp::SuperkmerExtractor::non_canonical(k, l),
+                (SplitMode::Classical, true) => iteratormsp::SuperkmerExtractor::classical(k, l),
+                (SplitMode::Classical, false) => iteratormsp::SuperkmerExtractor::classical_non_canonical(k, l),
+                (SplitMode::Msp, true) => iteratormsp::SuperkmerExtractor::msp(k, l),
+                (SplitMode::Msp, false) => iteratormsp::SuperkmerExtractor::msp_non_canonical(k, l),
+                (SplitMode::MspXor, true) => iteratormsp::SuperkmerExtractor::mspxor(k, l),
+                (SplitMode::MspXor, false) => iteratormsp::SuperkmerExtractor::mspxor_non_canonical(k, l),
+            }),
+            // UHS has a sticky and an MspXor variant only
+            "uhs" => Scheme::Uhs(match (mode, canonical) {
+                (SplitMode::Sticky, true) => iteratoruhs::SuperkmerExtractor::new(k, l),
+                (SplitMode::Sticky, false) => iteratoruhs::SuperkmerExtractor::non_canonical(k, l),
+                (SplitMode::MspXor, true) => iteratoruhs::SuperkmerExtractor::mspxor(k, l),
+                (SplitMode::MspXor, false) => iteratoruhs::SuperkmerExtractor::mspxor_non_canonical(k, l),
+                _ => return Err(SkStatus::SkInvalidScheme),
+            }),
+            _ => unreachable!("base checked above"),
+        };
+        Ok(SkExtractor { scheme })
+    }
+
+    fn process(&mut self, seq: &[u8], with_n: bool) -> &[Superkmer] {
+        match &mut self.scheme {
+            Scheme::Syncmer(ext) => if with_n { ext.process_with_n(seq) } else { ext.process(seq) },
+            Scheme::Uhs(ext) => if with_n { ext.process_with_n(seq) } else { ext.process(seq) },
+            Scheme::Random(ext) => if with_n { ext.process_with_n(seq) } else { ext.process(seq) },
+            Scheme::Kmc2(ext) => if with_n { ext.process_with_n(seq) } else { ext.process(seq) },
+            Scheme::Lexicographic(ext) => if with_n { ext.process_with_n(seq) } else { ext.process(seq) },
+        }
+    }
+
+    fn storage(&self) -> &[u64] {
+        match &self.scheme {
+            Scheme::Syncmer(ext) => ext.storage(),
+            Scheme::Uhs(ext) => ext.storage(),
+            Scheme::Random(ext) => ext.storage(),
+            Scheme::Kmc2(ext) => ext.storage(),
+            Scheme::Lexicographic(ext) => ext.storage(),
+        }
+    }
+}
+
+/// Run `f`, turning a panic into `SK_PANIC`.
+fn guard(f: impl FnOnce() -> SkStatus) -> SkStatus {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(SkStatus::SkPanic)
+}
+
+/// Create an extractor for `scheme` (NUL-terminated spec) with k-mer length `k` and
+/// minimizer length `l`. On success `*out` owns the extractor; free it with
+/// `sk_extractor_free`. On error `*out` is set to NULL.
+///
+/// # Safety
+/// `scheme` must be NULL or a valid C string, `out` NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sk_extractor_new(scheme: *const c_char, k: usize, l: usize, out: *mut *mut SkExtractor) -> SkStatus {
+    if scheme.is_null() || out.is_null() {
+        return SkStatus::SkNullPointer;
+    }
+    *out = ptr::null_mut();
+    guard(|| {
+        let Ok(spec) = CStr::from_ptr(scheme).to_str() else { return SkStatus::SkInvalidScheme };
+        match SkExtractor::new(spec, k, l) {
+            Ok(ext) => {
+                *out = Box::into_raw(Box::new(ext));
+                SkStatus::SkOk
+            }
+            Err(status) => status,
+        }
+    })
+}
+
+/// Free an extractor. NULL is ignored.
+///
+/// # Safety
+/// `ext` must be NULL or come from `sk_extractor_new`, and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn sk_extractor_free(ext: *mut SkExtractor) {
+    if !ext.is_null() {
+        drop(Box::from_raw(ext));
+    }
+}
+
+/// Cut `len` bases at `seq` into superkmers. With `with_n`, k-mers containing
+/// N/n are skipped; otherwise the sequence must be ACGT only. `*superkmers` and `*count`
+/// receive the extractor's result array.
+///
+/// # Safety
+/// `ext` must come from `sk_extractor_new`, `seq` be valid for `len` bytes (or NULL
+/// with `len` 0), and `superkmers` and `count` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sk_extractor_process(
+    ext: *mut SkExtractor, seq: *const u8, len: usize, with_n: bool,
+    superkmers: *mut *const Superkmer, count: *mut usize,
+) -> SkStatus {
+    if ext.is_null() || (seq.is_null() && len > 0) || superkmers.is_null() || count.is_null() {
+        return SkStatus::SkNullPointer;
+    }
+    let seq = if len == 0 { &[][..] } else { std::slice::from_raw_parts(seq, len) };
+    guard(|| {
+        let sks = (*ext).process(seq, with_n);
+        *superkmers = sks.as_ptr();
+        *count = sks.len();
+        SkStatus::SkOk
+    })
+}
+
+/// The 2-bit packed sequence of the last `sk_extractor_process` call (A=0, C=1, G=2,
+/// T=3, 32 bases per word, first base in the most significant bits).
+///
+/// # Safety
+/// `ext` must come from `sk_extractor_new`, `words` and `count` be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sk_extractor_storage(ext: *const SkExtractor, words: *mut *const u64, count: *mut usize) -> SkStatus {
+    if ext.is_null() || words.is_null() || count.is_null() {
+        return SkStatus::SkNullPointer;
+    }
+    let storage = (*ext).storage();
+    *words = storage.as_ptr();
+    *count = storage.len();
+    SkStatus::SkOk
+}
+
+/// Static, NUL-terminated description of a status code.
+#[no_mangle]
+pub extern "C" fn sk_status_message(status: SkStatus) -> *const c_char {
+    let msg: &'static CStr = match status {
+        SkStatus::SkOk => c"ok",
+        SkStatus::SkNullPointer => c"null pointer argument",
+        SkStatus::SkInvalidScheme => c"invalid scheme spec",
+        SkStatus::SkInvalidParameters => c"k or l not supported by the scheme",
+        SkStatus::SkPanic => c"internal error",
+    };
+    msg.as_ptr()
+}
@@ -0,0 +1,340 @@
+//! Memory-mapped, pre-packed 2-bit genome for reference-scale extraction.
+//!
+//! `pack_fasta` converts a FASTA file once into a flat file of 2-bit packed
+//! contigs (same MSB-first layout as `bitpack_fragment`) plus an index of
+//! contig offsets and N-runs. `PackedGenome::open` memory-maps that file, so
+//! a 3 Gbp reference costs ~750 MB of page cache instead of 3 GB of ASCII,
+//! and nothing is re-packed at extraction time.
+//!
+//! Extraction splits every N-free fragment into chunks that overlap by k-1
+//! bases (every k-mer belongs to exactly one chunk), runs them on a pool of
+//! scoped threads, and stitches the seams with `chunked::stitch_superkmers`.
+//! For context-independent modes (`Classical`, `Msp`, `MspXor`) the result is
+//! identical to a single pass over the contig; see `chunked` for `Sticky`.
+//!
+//! File layout (little-endian):
+//! ```text
+//! magic "SKPGENOM" | version u32 | num_contigs u32 | index_offset u64
+//! data: u64 words, each contig starting on a word boundary
+//! index: per contig: name_len u32, name, len u64, word_offset u64,
+//!        num_n_runs u32, (start u64, len u64) * num_n_runs
+//! ```
+//!
+//! ```ignore
+//! use rust_superkmers::genome::{pack_fasta, PackedGenome};
+//! pack_fasta("hg38.fa", "hg38.skg")?;
+//! let genome = PackedGenome::open("hg38.skg")?;
+//! for idx in 0..genome.contigs().len() {
+//!     let sks = genome.contig_superkmers(idx, 31, 8, SplitMode::MspXor, true, 16);
+//! }
+//! ```
+use crate::{Superkmer, SplitMode};
+use crate::chunked::{chunk_ranges_by_kmers, stitch_superkmers};
+use crate::utils::{bitpack_fragment, copy_packed_range};
+use crate::utils::fasta::{open_fasta, FastaRecord};
+use crate::utils::io::{capped, invalid};
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"SKPGENOM";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 24;
+
+/// Number of k-mers per parallel chunk. Large enough that the k-1 overlap and
+/// seam merging are negligible, small enough to balance threads on one contig.
+pub const DEFAULT_CHUNK_KMERS: usize = 1 << 20;
+
+/// Index entry for one contig of a packed genome.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContigInfo {
+    pub name: String,
+    /// Length in bases, N-runs included.
+    pub len: usize,
+    /// Offset of the contig's first word in the data section, in u64 words.
+    pub word_offset: usize,
+    /// Runs of N/n as (start, length), sorted. N bases are stored as A.
+    pub n_runs: Vec<(usize, usize)>,
+}
+
+impl ContigInfo {
+    /// N-free fragments of at least `min_len` bases, as (offset, length).
+    /// Same semantics as `utils::split_on_n`.
+    pub fn fragments(&self, min_len: usize) -> Vec<(usize, usize)> {
+        let mut fragments = Vec::new();
+        let mut start = 0;
+        for &(run_start, run_len) in &self.n_runs {
+            if run_start - start >= min_len {
+                fragments.push((start, run_start - start));
+            }
+            start = run_start + run_len;
+        }
+        if self.len - start >= min_len {
+            fragments.push((start, self.len - start));
+        }
+        fragments
+    }
+}
+
+/// Record runs of N/n in `seq` as (start, length).
+fn find_n_runs(seq: &[u8]) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < seq.len() {
+        if seq[i] == b'N' || seq[i] == b'n' {
+            let start = i;
+            while i < seq.len() && (seq[i] == b'N' || seq[i] == b'n') {
+                i += 1;
+            }
+            runs.push((start, i - start));
+        } else {
+            i += 1;
+        }
+    }
+    runs
+}
+
+/// Convert a FASTA file into a packed genome file readable by `PackedGenome::open`.
+/// Only one contig is held in memory at a time.
+pub fn pack_fasta<P: AsRef<Path>, Q: AsRef<Path>>(fasta_path: P, out_path: Q) -> io::Result<()> {
+    let records = open_fasta(fasta_path)?;
+    let mut out = BufWriter::new(File::create(out_path)?);
+    out.write_all(MAGIC)?;
+    out.write_all(&VERSION.to_le_bytes())?;
+    out.write_all(&[0u8; 12])?; // num_contigs and index_offset, patched below
+
+    let mut contigs: Vec<ContigInfo> = Vec::new();
+    let mut word_offset = 0usize;
+    for record in records {
+        let FastaRecord { name, seq } = record?;
+        let words = bitpack_fragment(&seq);
+        for w in &words {
+            out.write_all(&w.to_le_bytes())?;
+        }
+        contigs.push(ContigInfo { name, len: seq.len(), word_offset, n_runs: find_n_runs(&seq) });
+        word_offset += words.len();
+    }
+
+    let index_offset = HEADER_LEN + word_offset * 8;
+    for c in &contigs {
+        out.write_all(&(c.name.len() as u32).to_le_bytes())?;
+        out.write_all(c.name.as_bytes())?;
+        out.write_all(&(c.len as u64).to_le_bytes())?;
+        out.write_all(&(c.word_offset as u64).to_le_bytes())?;
+        out.write_all(&(c.n_runs.len() as u32).to_le_bytes())?;
+        for &(start, len) in &c.n_runs {
+            out.write_all(&(start as u64).to_le_bytes())?;
+            out.write_all(&(len as u64).to_le_bytes())?;
+        }
+    }
+
+    let mut file = out.into_inner().map_err(|e| e.into_error())?;
+    file.seek(SeekFrom::Start(12))?;
+    file.write_all(&(contigs.len() as u32).to_le_bytes())?;
+    file.write_all(&(index_offset as u64).to_le_bytes())?;
+    Ok(())
+}
+
+/// Little-endian cursor over the index section.
+struct IndexReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> IndexReader<'a> {
+    fn take(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if self.pos + n > self.bytes.len() {
+            return Err(invalid("truncated genome index"));
+        }
+        let s = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(s)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<usize> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()) as usize)
+    }
+}
+
+/// A memory-mapped packed genome.
+pub struct PackedGenome {
+    mmap: Mmap,
+    contigs: Vec<ContigInfo>,
+    num_words: usize,
+}
+
+impl PackedGenome {
+    /// Check whether `path` starts with the packed genome magic.
+    pub fn is_packed_genome<P: AsRef<Path>>(path: P) -> bool {
+        let mut magic = [0u8; 8];
+        match File::open(path) {
+            Ok(mut f) => io::Read::read_exact(&mut f, &mut magic).is_ok() && &magic == MAGIC,
+            Err(_) => false,
+        }
+    }
+
+    /// Memory-map a file written by `pack_fasta` and parse its index.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        if cfg!(target_endian = "big") {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "packed genomes are mapped as native u64 words; little-endian only"));
+        }
+        let file = File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+        if mmap.len() < HEADER_LEN || &mmap[..8] != MAGIC {
+            return Err(invalid("not a packed genome file"));
+        }
+        let mut header = IndexReader { bytes: &mmap[..HEADER_LEN], pos: 8 };
+        let version = header.u32()?;
+        if version != VERSION {
+            return Err(invalid("unsupported packed genome version"));
+        }
+        let num_contigs = header.u32()? as usize;
+        let index_offset = header.u64()?;
+        if index_offset < HEADER_LEN || index_offset > mmap.len() || !(index_offset - HEADER_LEN).is_multiple_of(8) {
+            return Err(invalid("corrupt packed genome header"));
+        }
+        let num_words = (index_offset - HEADER_LEN) / 8;
+
+        let mut index = IndexReader { bytes: &mmap[index_offset..], pos: 0 };
+        let mut contigs = Vec::with_capacity(capped(num_contigs));
+        for _ in 0..num_contigs {
+            let name_len = index.u32()? as usize;
+            let name = String::from_utf8_lossy(index.take(name_len)?).into_owned();
+            let len = index.u64()?;
+            let word_offset = index.u64()?;
+            let num_runs = index.u32()? as usize;
+            let mut n_runs = Vec::with_capacity(capped(num_runs));
+            let mut prev_end = 0;
+            for _ in 0..num_runs {
+                let (start, run_len) = (index.u64()?, index.u64()?);
+                // Sorted, disjoint and inside the contig, or `fragments` would underflow
+                match start.checked_add(run_len) {
+                    Some(end) if start >= prev_end && end <= len => prev_end = end,
+                    _ => return Err(invalid("corrupt N-run in genome index")),
+                }
+                n_runs.push((start, run_len));
+            }
+            if word_offset.checked_add(len.div_ceil(32)).is_none_or(|end| end > num_words) {
+                return Err(invalid("contig extends past data section"));
+            }
+            contigs.push(ContigInfo { name, len, word_offset, n_runs });
+        }
+        Ok(PackedGenome { mmap, contigs, num_words })
+    }
+
+    pub fn contigs(&self) -> &[ContigInfo] {
+        &self.contigs
+    }
+
+    /// Total number of bases over all contigs (N-runs included).
+    pub fn total_len(&self) -> usize {
+        self.contigs.iter().map(|c| c.len).sum()
+    }
+
+    /// The whole data section as u64 words.
+    fn words(&self) -> &[u64] {
+        // HEADER_LEN is a multiple of 8 and mmaps are page-aligned, so the cast is aligned.
+        unsafe {
+            std::slice::from_raw_parts(self.mmap[HEADER_LEN..].as_ptr() as *const u64, self.num_words)
+        }
+    }
+
+    /// 2-bit packed storage of contig `idx`, base 0 at the MSB of word 0.
+    pub fn contig_storage(&self, idx: usize) -> &[u64] {
+        let c = &self.contigs[idx];
+        &self.words()[c.word_offset..c.word_offset + c.len.div_ceil(32)]
+    }
+
+    /// Extract superkmers of contig `idx` with the `iteratorsyncmers2` scoring,
+    /// using `threads` worker threads. Positions are contig coordinates.
+    pub fn contig_superkmers(&self, idx: usize, k: usize, l: usize, mode: SplitMode, canonical: bool, threads: usize) -> Vec<Superkmer> {
+        use crate::iteratorsyncmers2::SuperkmerExtractor;
+        let new_extractor = || -> SuperkmerExtractor {
+            match (mode, canonical) {
+                (SplitMode::Sticky, true) => SuperkmerExtractor::new(k, l),
+                (SplitMode::Sticky, false) => SuperkmerExtractor::non_canonical(k, l),
+                (SplitMode::Classical, true) => SuperkmerExtractor::classical(k, l),
+                (SplitMode::Classical, false) => SuperkmerExtractor::classical_non_canonical(k, l),
+                (SplitMode::Msp, true) => SuperkmerExtractor::msp(k, l),
+                (SplitMode::Msp, false) => SuperkmerExtractor::msp_non_canonical(k, l),
+                (SplitMode::MspXor, true) => SuperkmerExtractor::mspxor(k, l),
+                (SplitMode::MspXor, false) => SuperkmerExtractor::mspxor_non_canonical(k, l),
+            }
+        };
+        self.chunked_superkmers(idx, k, threads, DEFAULT_CHUNK_KMERS, new_extractor, |ext, chunk, len, out| {
+            out.extend_from_slice(ext.process_packed(chunk, len));
+        })
+    }
+
+    /// Same as `contig_superkmers` with the `iteratorsimdmini` extractor (odd l).
+    /// Chunks are decoded back to ASCII since simd-minimizers consumes ASCII.
+    #[cfg(feature = "simd-mini")]
+    pub fn contig_superkmers_simdmini(&self, idx: usize, k: usize, l: usize, mode: SplitMode, canonical: bool, threads: usize) -> Vec<Superkmer> {
+        use crate::iteratorsimdmini::SuperkmerExtractor;
+        let new_extractor = || -> (SuperkmerExtractor, Vec<u8>) {
+            let ext = match (mode, canonical) {
+                (SplitMode::Sticky, true) => SuperkmerExtractor::new(k, l),
+                (SplitMode::Sticky, false) => SuperkmerExtractor::non_canonical(k, l),
+                (SplitMode::Classical, true) => SuperkmerExtractor::classical(k, l),
+                (SplitMode::Classical, false) => SuperkmerExtractor::classical_non_canonical(k, l),
+                (SplitMode::Msp, true) => SuperkmerExtractor::msp(k, l),
+                (SplitMode::Msp, false) => SuperkmerExtractor::msp_non_canonical(k, l),
+                (SplitMode::MspXor, true) => SuperkmerExtractor::mspxor(k, l),
+                (SplitMode::MspXor, false) => SuperkmerExtractor::mspxor_non_canonical(k, l),
+            };
+            (ext, Vec::new())
+        };
+        self.chunked_superkmers(idx, k, threads, DEFAULT_CHUNK_KMERS, new_extractor, |(ext, ascii), chunk, len, out| {
+            crate::utils::decode_packed_range(chunk, 0, len, ascii);
+            out.extend_from_slice(ext.process(ascii));
+        })
+    }
+
+    /// Split the N-free fragments of contig `idx` into chunks of `chunk_kmers` k-mers
+    /// (k-1 bases of overlap), run `run` on each chunk in parallel, and stitch the results.
+    /// `run` receives chunk-aligned packed storage and appends chunk-relative superkmers.
+    pub fn chunked_superkmers<E, N, F>(&self, idx: usize, k: usize, threads: usize, chunk_kmers: usize, new_extractor: N, run: F) -> Vec<Superkmer>
+    where
+        N: Fn() -> E + Sync,
+        F: Fn(&mut E, &[u64], usize, &mut Vec<Superkmer>) + Sync,
+    {
+        let storage = self.contig_storage(idx);
+        let chunks: Vec<(usize, usize)> = self.contigs[idx].fragments(k).into_iter()
+            .flat_map(|(offset, len)| chunk_ranges_by_kmers(offset, offset + len, k, chunk_kmers))
+            .collect();
+
+        let threads = threads.max(1).min(chunks.len().max(1));
+        let mut results: Vec<Vec<Superkmer>> = vec![Vec::new(); chunks.len()];
+        std::thread::scope(|scope| {
+            let mut slots: Vec<Vec<(usize, &mut Vec<Superkmer>)>> = (0..threads).map(|_| Vec::new()).collect();
+            for (i, slot) in results.iter_mut().enumerate() {
+                slots[i % threads].push((i, slot));
+            }
+            for worker in slots {
+                let chunks = &chunks;
+                let new_extractor = &new_extractor;
+                let run = &run;
+                scope.spawn(move || {
+                    let mut ext = new_extractor();
+                    let mut buf = Vec::new();
+                    for (i, out) in worker {
+                        let (start, end) = chunks[i];
+                        let len = end - start;
+                        copy_packed_range(storage, start, len, &mut buf);
+                        run(&mut ext, &buf, len, out);
+                        for sk in out.iter_mut() {
+                            sk.start += start;
+                        }
+                    }
+                });
+            }
+        });
+
+        stitch_superkmers(&results, k)
+    }
+}
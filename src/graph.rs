@@ -0,0 +1,335 @@
+//! Compacted de Bruijn graph construction from MspXor buckets, BCALM2-style.
+//!
+//! 1. `partition_kmers` splits the canonical k-mers of the input into buckets keyed
+//!    by `mint`, using `SuperkmerExtractor::mspxor`. MspXor is context-independent,
+//!    so every occurrence of a k-mer lands in the same bucket and buckets are disjoint.
+//! 2. Each bucket is compacted on its own (in parallel): maximal paths of
+//!    compactable edges whose k-mers all belong to the bucket become unitig fragments.
+//!    An edge x -> y is compactable when y is the only successor of x and x the only
+//!    predecessor of y. A neighbour's bucket is its MspXor minimizer, so degrees are
+//!    looked up in the neighbouring buckets only (binary search), with no index over
+//!    all k-mers. The buckets themselves are all held in memory.
+//! 3. Fragments are glued across buckets along the compactable edges that leave
+//!    their ends, giving the unitigs of the whole graph.
+//!
+//! k must be odd (no k-mer is its own reverse complement) and at most 63.
+//!
+//! ```
+//! use rust_superkmers::graph::CompactedGraph;
+//! let seq = b"AACTGCACTGCACTGCACTGCACACTGCACTGCACTGCACTGCACACTGCACTGCACTGACTGCACTGCACTGCACTGCACTGCCTGC";
+//! let graph = CompactedGraph::from_sequences([&seq[..]], 21, 8, 2);
+//! let mut gfa = Vec::new();
+//! graph.write_gfa(&mut gfa).unwrap();
+//! assert!(gfa.starts_with(b"H\tVN:Z:1.0\n"));
+//! ```
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{self, Write};
+
+use crate::iteratorsyncmers2::SuperkmerExtractor;
+use crate::utils::encode_base;
+
+/// A k-mer, 2-bit packed (A=0, C=1, G=2, T=3) in the low 2k bits, first base most significant.
+pub type Kmer = u128;
+
+const BASES: [u8; 4] = [b'A', b'C', b'G', b'T'];
+
+pub fn revcomp_kmer(x: Kmer, k: usize) -> Kmer {
+    let mut rc = 0;
+    let mut v = x;
+    for _ in 0..k {
+        rc = (rc << 2) | (3 - (v & 3));
+        v >>= 2;
+    }
+    rc
+}
+
+pub fn canonical_kmer(x: Kmer, k: usize) -> Kmer {
+    x.min(revcomp_kmer(x, k))
+}
+
+pub fn encode_kmer(seq: &[u8]) -> Kmer {
+    seq.iter().fold(0, |acc, &b| (acc << 2) | encode_base(b) as Kmer)
+}
+
+pub fn decode_kmer(x: Kmer, k: usize) -> Vec<u8> {
+    (0..k).rev().map(|i| BASES[((x >> (2 * i)) & 3) as usize]).collect()
+}
+
+fn revcomp_seq(seq: &[u8]) -> Vec<u8> {
+    seq.iter().rev().map(|&b| match b {
+        b'A' => b'T',
+        b'C' => b'G',
+        b'G' => b'C',
+        _ => b'A',
+    }).collect()
+}
+
+/// Canonical k-mers of `seqs`, bucketed by MspXor minimizer (`mint`).
+/// Each bucket is sorted and deduplicated. Ns split the sequences.
+pub fn partition_kmers<'a, I: IntoIterator<Item = &'a [u8]>>(seqs: I, k: usize, l: usize) -> HashMap<u32, Vec<Kmer>> {
+    assert!(k % 2 == 1 && k <= 63, "graph construction needs an odd k <= 63");
+    let mask: Kmer = (1 << (2 * k)) - 1;
+    let mut extractor = SuperkmerExtractor::mspxor(k, l);
+    let mut buckets: HashMap<u32, Vec<Kmer>> = HashMap::new();
+    for seq in seqs {
+        for sk in extractor.process_with_n(seq) {
+            let bucket = buckets.entry(sk.mint).or_default();
+            let bases = &seq[sk.start..sk.start + sk.size as usize];
+            let mut kmer = encode_kmer(&bases[..k - 1]);
+            for &b in &bases[k - 1..] {
+                kmer = ((kmer << 2) | encode_base(b) as Kmer) & mask;
+                bucket.push(canonical_kmer(kmer, k));
+            }
+        }
+    }
+    for kmers in buckets.values_mut() {
+        kmers.sort_unstable();
+        kmers.dedup();
+    }
+    buckets
+}
+
+/// The graph as its buckets, sorted by mint, each a sorted list of canonical k-mers.
+struct Dbg<'a> {
+    k: usize,
+    mask: Kmer,
+    extractor: SuperkmerExtractor,
+    buckets: &'a [(u32, Vec<Kmer>)],
+}
+
+impl Dbg<'_> {
+    /// Bucket of `x` if it is in the graph: the bucket of its minimizer, if it holds `x`.
+    fn bucket(&self, x: Kmer) -> Option<u32> {
+        let canon = canonical_kmer(x, self.k);
+        let mut bases = [0u8; 63];
+        for (i, b) in bases[..self.k].iter_mut().enumerate() {
+            *b = BASES[((canon >> (2 * (self.k - 1 - i))) & 3) as usize];
+        }
+        let (mint, _, _) = self.extractor.minimizer_of(&bases[..self.k])?;
+        let b = self.buckets.binary_search_by_key(&mint, |b| b.0).ok()?;
+        self.buckets[b].1.binary_search(&canon).ok().map(|_| mint)
+    }
+
+    fn successors(&self, x: Kmer) -> impl Iterator<Item = Kmer> + '_ {
+        (0..4).map(move |b| ((x << 2) | b) & self.mask).filter(|&y| self.bucket(y).is_some())
+    }
+
+    fn num_predecessors(&self, x: Kmer) -> usize {
+        (0..4).filter(|&b| self.bucket((x >> 2) | (b << (2 * (self.k - 1)))).is_some()).count()
+    }
+
+    /// The successor y of `x` if x -> y is compactable.
+    fn next_compactable(&self, x: Kmer) -> Option<Kmer> {
+        let mut succ = self.successors(x);
+        let y = succ.next()?;
+        if succ.next().is_some() || self.num_predecessors(y) != 1 || canonical_kmer(y, self.k) == canonical_kmer(x, self.k) {
+            return None;
+        }
+        Some(y)
+    }
+
+    /// The predecessor w of `x` if w -> x is compactable.
+    fn prev_compactable(&self, x: Kmer) -> Option<Kmer> {
+        self.next_compactable(revcomp_kmer(x, self.k)).map(|y| revcomp_kmer(y, self.k))
+    }
+}
+
+/// A path of k-mers in one orientation: its sequence and its first and last k-mers.
+struct Path {
+    seq: Vec<u8>,
+    first: Kmer,
+    last: Kmer,
+}
+
+impl Path {
+    /// First k-mer when read forward (`fwd`) or reverse-complemented.
+    fn first(&self, fwd: bool, k: usize) -> Kmer {
+        if fwd { self.first } else { revcomp_kmer(self.last, k) }
+    }
+
+    fn last(&self, fwd: bool, k: usize) -> Kmer {
+        if fwd { self.last } else { revcomp_kmer(self.first, k) }
+    }
+}
+
+/// Compact the k-mers of one bucket into maximal paths that stay inside the bucket.
+fn compact_bucket(dbg: &Dbg, mint: u32, kmers: &[Kmer]) -> Vec<Path> {
+    let k = dbg.k;
+    let mut visited: HashSet<Kmer> = HashSet::with_capacity(kmers.len());
+    let mut paths = Vec::new();
+    for &start in kmers {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut step = |y: Kmer| dbg.bucket(y) == Some(mint) && visited.insert(canonical_kmer(y, k));
+
+        let mut last = start;
+        let mut right = Vec::new();
+        while let Some(y) = dbg.next_compactable(last).filter(|&y| step(y)) {
+            right.push(BASES[(y & 3) as usize]);
+            last = y;
+        }
+        let mut first = start;
+        let mut left = Vec::new();
+        while let Some(w) = dbg.prev_compactable(first).filter(|&w| step(w)) {
+            left.push(BASES[(w >> (2 * (k - 1))) as usize]);
+            first = w;
+        }
+
+        left.reverse();
+        left.extend(decode_kmer(start, k));
+        left.extend(right);
+        paths.push(Path { seq: left, first, last });
+    }
+    paths
+}
+
+/// Glue bucket-local paths along the compactable edges between their ends.
+fn glue(dbg: &Dbg, fragments: Vec<Path>) -> Vec<Path> {
+    let k = dbg.k;
+    let mut ends: HashMap<Kmer, Vec<usize>> = HashMap::new();
+    for (i, f) in fragments.iter().enumerate() {
+        ends.entry(canonical_kmer(f.first, k)).or_default().push(i);
+        if canonical_kmer(f.last, k) != canonical_kmer(f.first, k) {
+            ends.entry(canonical_kmer(f.last, k)).or_default().push(i);
+        }
+    }
+    // The fragment (and orientation) whose first k-mer (`at_start`) or last k-mer is `x`.
+    let find = |x: Kmer, at_start: bool| -> Option<(usize, bool)> {
+        ends.get(&canonical_kmer(x, k))?.iter().find_map(|&i| {
+            [true, false].into_iter().find(|&fwd| {
+                let end = if at_start { fragments[i].first(fwd, k) } else { fragments[i].last(fwd, k) };
+                end == x
+            }).map(|fwd| (i, fwd))
+        })
+    };
+
+    let mut used = vec![false; fragments.len()];
+    let mut unitigs = Vec::new();
+    for i in 0..fragments.len() {
+        if used[i] {
+            continue;
+        }
+        used[i] = true;
+        let mut chain = VecDeque::from([(i, true)]);
+        loop {
+            let &(j, fwd) = chain.back().unwrap();
+            match dbg.next_compactable(fragments[j].last(fwd, k)).and_then(|y| find(y, true)) {
+                Some((next, dir)) if !used[next] => {
+                    used[next] = true;
+                    chain.push_back((next, dir));
+                }
+                _ => break,
+            }
+        }
+        loop {
+            let &(j, fwd) = chain.front().unwrap();
+            match dbg.prev_compactable(fragments[j].first(fwd, k)).and_then(|w| find(w, false)) {
+                Some((prev, dir)) if !used[prev] => {
+                    used[prev] = true;
+                    chain.push_front((prev, dir));
+                }
+                _ => break,
+            }
+        }
+
+        let &(j, fwd) = chain.front().unwrap();
+        let first = fragments[j].first(fwd, k);
+        let &(j, fwd) = chain.back().unwrap();
+        let last = fragments[j].last(fwd, k);
+        let mut seq = Vec::new();
+        for &(j, fwd) in &chain {
+            let part = if fwd { fragments[j].seq.clone() } else { revcomp_seq(&fragments[j].seq) };
+            seq.extend_from_slice(if seq.is_empty() { &part } else { &part[k - 1..] });
+        }
+        unitigs.push(Path { seq, first, last });
+    }
+    unitigs
+}
+
+/// A (k-1)-overlap between two unitigs, GFA-style: `from` in orientation `from_fwd`
+/// is followed by `to` in orientation `to_fwd`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Link {
+    pub from: usize,
+    pub from_fwd: bool,
+    pub to: usize,
+    pub to_fwd: bool,
+}
+
+/// Compacted de Bruijn graph: unitig sequences and the links between them.
+pub struct CompactedGraph {
+    pub k: usize,
+    pub unitigs: Vec<Vec<u8>>,
+    pub links: Vec<Link>,
+}
+
+impl CompactedGraph {
+    /// Build the graph of the k-mers of `seqs`, bucketed by MspXor l-mer minimizers.
+    pub fn from_sequences<'a, I: IntoIterator<Item = &'a [u8]>>(seqs: I, k: usize, l: usize, threads: usize) -> Self {
+        Self::from_buckets(partition_kmers(seqs, k, l), k, l, threads)
+    }
+
+    /// Build the graph from `partition_kmers(.., k, l)` output, compacting buckets on
+    /// `threads` threads.
+    pub fn from_buckets(buckets: HashMap<u32, Vec<Kmer>>, k: usize, l: usize, threads: usize) -> Self {
+        let mut buckets: Vec<(u32, Vec<Kmer>)> = buckets.into_iter().collect();
+        buckets.sort_unstable_by_key(|b| b.0);
+        let dbg = Dbg { k, mask: (1 << (2 * k)) - 1, extractor: SuperkmerExtractor::mspxor(k, l), buckets: &buckets };
+
+        let threads = threads.max(1);
+        let mut per_bucket: Vec<(usize, Vec<Path>)> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..threads).map(|t| {
+                let (dbg, buckets) = (&dbg, &buckets);
+                s.spawn(move || {
+                    (t..buckets.len()).step_by(threads)
+                        .map(|b| (b, compact_bucket(dbg, buckets[b].0, &buckets[b].1)))
+                        .collect::<Vec<_>>()
+                })
+            }).collect();
+            handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+        });
+        per_bucket.sort_unstable_by_key(|p| p.0);
+        let unitigs = glue(&dbg, per_bucket.into_iter().flat_map(|p| p.1).collect());
+
+        let mut starts: HashMap<Kmer, Vec<(usize, bool)>> = HashMap::new();
+        for (i, u) in unitigs.iter().enumerate() {
+            for fwd in [true, false] {
+                starts.entry(u.first(fwd, k)).or_default().push((i, fwd));
+            }
+        }
+        let mut links = Vec::new();
+        for (from, u) in unitigs.iter().enumerate() {
+            for from_fwd in [true, false] {
+                for y in dbg.successors(u.last(from_fwd, k)) {
+                    for &(to, to_fwd) in starts.get(&y).into_iter().flatten() {
+                        // Each link is found from both ends; keep one of the two spellings
+                        if (from, from_fwd) <= (to, !to_fwd) {
+                            links.push(Link { from, from_fwd, to, to_fwd });
+                        }
+                    }
+                }
+            }
+        }
+        links.sort_unstable();
+        links.dedup();
+
+        CompactedGraph { k, unitigs: unitigs.into_iter().map(|u| u.seq).collect(), links }
+    }
+
+    /// Write the graph as GFA 1.0: one `S` line per unitig (ids are indices into
+    /// `unitigs`) and one `L` line per link, with a (k-1)-base overlap.
+    pub fn write_gfa<W: Write>(&self, mut out: W) -> io::Result<()> {
+        let sign = |fwd: bool| if fwd { '+' } else { '-' };
+        writeln!(out, "H\tVN:Z:1.0")?;
+        for (i, seq) in self.unitigs.iter().enumerate() {
+            write!(out, "S\t{}\t", i)?;
+            out.write_all(seq)?;
+            writeln!(out, "\tLN:i:{}", seq.len())?;
+        }
+        for link in &self.links {
+            writeln!(out, "L\t{}\t{}\t{}\t{}\t{}M", link.from, sign(link.from_fwd), link.to, sign(link.to_fwd), self.k - 1)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+//! Minimizer-space index for read-to-reference seeding.
+//!
+//! The reference is cut into canonical MspXor superkmers, and every superkmer is
+//! stored under its `mint`. A query is cut the same way; each query superkmer whose
+//! `mint` is in the index yields one `Hit` per reference entry. The minimizer is
+//! anchored at `start + mpos` on both sides, and `mint_is_rc` says on which strand
+//! it was read, so a hit gives both the strand and the offset of the alignment.
+//!
+//! ```
+//! use rust_superkmers::index::MinimizerIndex;
+//! let reference = b"AACTGCACTGCACTGCACTGCACACTGCACTGCACTGCACTGCACACTGCACTGCACTGACTGCACTGCACTGCACTGCACTGCCTGC";
+//! let mut index = MinimizerIndex::new(21, 8);
+//! index.add_contig("ref", reference);
+//! let hits = index.query(&reference[30..80]);
+//! assert!(hits.iter().any(|h| h.forward && h.offset == 30));
+//! ```
+use std::collections::HashMap;
+
+use crate::genome::PackedGenome;
+use crate::iteratorsyncmers2::SuperkmerExtractor;
+use crate::SplitMode;
+
+/// One reference superkmer stored under its `mint`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RefEntry {
+    pub contig: u32,
+    pub start: usize,
+    pub size: u16,
+    pub mpos: u16,
+    pub mint_is_rc: bool,
+}
+
+/// A query superkmer sharing its minimizer with a reference superkmer.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Hit {
+    pub contig: u32,
+    /// Query and reference minimizer on the same strand.
+    pub forward: bool,
+    /// Reference position of the first query base (of the reverse-complemented
+    /// query for reverse hits). Colinear hits share the same offset.
+    pub offset: i64,
+    /// Minimizer position in the query.
+    pub query_pos: usize,
+    /// Minimizer position in the reference contig.
+    pub ref_pos: usize,
+    pub mint: u32,
+}
+
+/// Reference superkmers grouped by minimizer.
+pub struct MinimizerIndex {
+    k: usize,
+    l: usize,
+    contig_names: Vec<String>,
+    entries: HashMap<u32, Vec<RefEntry>>,
+}
+
+impl MinimizerIndex {
+    /// Empty index for canonical MspXor superkmers of k-mers with l-mer minimizers.
+    pub fn new(k: usize, l: usize) -> Self {
+        MinimizerIndex { k, l, contig_names: Vec::new(), entries: HashMap::new() }
+    }
+
+    /// Index every contig of a packed genome, extracting each on `threads` threads.
+    pub fn from_packed_genome(genome: &PackedGenome, k: usize, l: usize, threads: usize) -> Self {
+        let mut index = Self::new(k, l);
+        for (idx, contig) in genome.contigs().iter().enumerate() {
+            let sks = genome.contig_superkmers(idx, k, l, SplitMode::MspXor, true, threads);
+            index.add_superkmers(&contig.name, &sks);
+        }
+        index
+    }
+
+    /// An extractor producing the superkmers this index is keyed on.
+    pub fn extractor(&self) -> SuperkmerExtractor {
+        SuperkmerExtractor::mspxor(self.k, self.l)
+    }
+
+    /// Add a contig (may contain N). Returns its id.
+    pub fn add_contig(&mut self, name: &str, seq: &[u8]) -> u32 {
+        let mut ext = self.extractor();
+        self.add_superkmers(name, ext.process_with_n(seq))
+    }
+
+    fn add_superkmers(&mut self, name: &str, sks: &[crate::Superkmer]) -> u32 {
+        let contig = self.contig_names.len() as u32;
+        self.contig_names.push(name.to_string());
+        for sk in sks {
+            self.entries.entry(sk.mint).or_default().push(RefEntry {
+                contig,
+                start: sk.start,
+                size: sk.size,
+                mpos: sk.mpos,
+                mint_is_rc: sk.mint_is_rc,
+            });
+        }
+        contig
+    }
+
+    pub fn contig_names(&self) -> &[String] {
+        &self.contig_names
+    }
+
+    /// Reference superkmers with minimizer `mint`, in insertion order.
+    pub fn lookup(&self, mint: u32) -> &[RefEntry] {
+        self.entries.get(&mint).map_or(&[], |v| &v[..])
+    }
+
+    /// Number of distinct minimizers.
+    pub fn num_minimizers(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Number of stored reference superkmers.
+    pub fn num_entries(&self) -> usize {
+        self.entries.values().map(|v| v.len()).sum()
+    }
+
+    /// Hits of `seq` (may contain N), sorted by (contig, strand, offset, query position).
+    pub fn query(&self, seq: &[u8]) -> Vec<Hit> {
+        let mut hits = Vec::new();
+        self.query_with(&mut self.extractor(), seq, &mut hits);
+        hits
+    }
+
+    /// Same as `query`, reusing an extractor from `extractor()` and the `hits` buffer.
+    pub fn query_with(&self, ext: &mut SuperkmerExtractor, seq: &[u8], hits: &mut Vec<Hit>) {
+        hits.clear();
+        for sk in ext.process_with_n(seq) {
+            let query_pos = sk.start + sk.mpos as usize;
+            for entry in self.lookup(sk.mint) {
+                let ref_pos = entry.start + entry.mpos as usize;
+                let forward = entry.mint_is_rc == sk.mint_is_rc;
+                let offset = if forward {
+                    ref_pos as i64 - query_pos as i64
+                } else {
+                    // Minimizer at query_pos maps to seq.len() - query_pos - l in rc(seq)
+                    (ref_pos + query_pos + self.l) as i64 - seq.len() as i64
+                };
+                hits.push(Hit { contig: entry.contig, forward, offset, query_pos, ref_pos, mint: sk.mint });
+            }
+        }
+        hits.sort_unstable();
+    }
+}
@@ -3,42 +3,68 @@
 // with KMC2 disqualification rules (no signatures starting with A
 // or containing AA/AC) to improve bucket balance.
 //
-// Same MSP sliding-window approach as iteratorsyncmers2, just a different
-// scoring function backed by a precomputed lookup table.
+// Same sliding-window core as iteratorsyncmers2, just a different
+// scoring function backed by a precomputed lookup table. All split modes
+// are available; MspXor orders the signatures within each class by
+// canonical_value ^ constant instead of lexicographically.
+//
+// KMC2 signatures carry no orientation: canonical extractors always report
+// `mint_is_rc = false`.
 
-use crate::Superkmer;
+use crate::{Superkmer, SplitMode};
+use crate::minimizer_core::{canonical_lmer, kmer_minimizer, minimizer_positions_deque, minimizer_positions_sticky, materialize_superkmers, mint_of, with_packed, WindowBuffers};
 use crate::utils::bitpack_fragment;
-use lazy_static::lazy_static;
-
-const K8: usize = 8;
-
-lazy_static! {
-    /// For each possible 8-mer value, stores the KMC2 score.
-    /// Score = canonical_value for non-disqualified signatures,
-    ///       = (1 << 16) | canonical_value for disqualified ones.
-    /// This ensures non-disqualified signatures always sort before disqualified.
-    /// The canonical value can be recovered as score & 0xFFFF.
-    static ref KMC2_SCORES_8: Box<[usize; 1 << (2 * K8)]> = {
-        let mut table = Box::new([0usize; 1 << (2 * K8)]);
-        for val in 0..(1usize << (2 * K8)) {
-            let rc = revcomp_lmer(val, K8);
-            let canonical = std::cmp::min(val, rc);
-            let disqualified = kmc2_is_disqualified(canonical, K8);
-            table[val] = if disqualified { (1 << (2 * K8)) | canonical } else { canonical };
-        }
-        table
-    };
+use crate::tables::LazyTables;
+
+/// Signature lengths KMC2 tables are generated for.
+pub const KMC2_L: std::ops::RangeInclusive<usize> = 7..=12;
+
+/// XOR tiebreaker for MspXor, as in the syncmer and UHS schemes.
+const XOR_CONSTANT: usize = 0xACE5_ACE5;
+
+/// KMC2 score of each l-mer:
+/// Score = canonical_value for non-disqualified signatures,
+///       = (1 << 2l) | canonical_value for disqualified ones.
+/// This ensures non-disqualified signatures always sort before disqualified.
+/// The canonical value can be recovered as score & ((1 << 2l) - 1).
+fn generate_kmc2_scores(l: usize) -> Vec<u32> {
+    generate_kmc2_scores_with(l, 0)
 }
 
-#[inline]
-fn revcomp_lmer(val: usize, l: usize) -> usize {
-    let mut rc = 0usize;
-    let mut v = val;
-    for _ in 0..l {
-        rc = (rc << 2) | (3 - (v & 3));
-        v >>= 2;
+/// MspXor variant: canonical_value ^ constant within each class.
+fn generate_kmc2_mspxor_scores(l: usize) -> Vec<u32> {
+    generate_kmc2_scores_with(l, XOR_CONSTANT & ((1 << (2 * l)) - 1))
+}
+
+fn generate_kmc2_scores_with(l: usize, xor: usize) -> Vec<u32> {
+    (0..1usize << (2 * l)).map(|val| {
+        let canonical = canonical_lmer(val, l).0 as usize;
+        let disqualified = kmc2_is_disqualified(canonical, l);
+        ((disqualified as usize) << (2 * l) | (canonical ^ xor)) as u32
+    }).collect()
+}
+
+static KMC2_SCORES: LazyTables<u32> = LazyTables::new("kmc2", generate_kmc2_scores);
+static KMC2_MSPXOR_SCORES: LazyTables<u32> = LazyTables::new("kmc2-mspxor", generate_kmc2_mspxor_scores);
+
+/// KMC2 scores (lexicographic within each class). l=7..12.
+pub fn kmc2_scores(l: usize) -> &'static [u32] {
+    assert!(KMC2_L.contains(&l), "KMC2 signatures are only tabulated for l in {:?}", KMC2_L);
+    KMC2_SCORES.get(l)
+}
+
+/// KMC2 scores with the XOR tiebreaker within each class. l=7..12.
+pub fn kmc2_mspxor_scores(l: usize) -> &'static [u32] {
+    assert!(KMC2_L.contains(&l), "KMC2 signatures are only tabulated for l in {:?}", KMC2_L);
+    KMC2_MSPXOR_SCORES.get(l)
+}
+
+/// Scores used by `mode`.
+pub fn kmc2_mode_scores(l: usize, mode: SplitMode) -> &'static [u32] {
+    match mode {
+        SplitMode::MspXor => kmc2_mspxor_scores(l),
+        SplitMode::Sticky | SplitMode::Classical | SplitMode::Msp => kmc2_scores(l),
     }
-    rc
 }
 
 /// KMC2 disqualification: a canonical l-mer is disqualified if it
@@ -62,134 +88,97 @@ fn kmc2_is_disqualified(canonical_val: usize, l: usize) -> bool {
     false
 }
 
-/// Extract an l-mer value from packed storage, MSB-first encoding.
-/// Each u64 in `data` holds 32 bases, with base 0 at bits 63-62 (MSB).
-fn get_kmer_value(data: &[u64], base_pos: usize, l: usize) -> usize {
-    let word = base_pos / 32;
-    let offset = base_pos % 32;
-    let bit_len = l * 2;
-
-    if offset + l <= 32 {
-        let shift = 64 - (offset + l) * 2;
-        ((data[word] >> shift) & ((1u64 << bit_len) - 1)) as usize
-    } else {
-        let bases_in_first = 32 - offset;
-        let bases_in_second = l - bases_in_first;
-        let first_bits = (data[word] & ((1u64 << (bases_in_first * 2)) - 1)) as usize;
-        let shift = 64 - bases_in_second * 2;
-        let second_bits = ((data[word + 1] >> shift) & ((1u64 << (bases_in_second * 2)) - 1)) as usize;
-        (first_bits << (bases_in_second * 2)) | second_bits
+/// Sliding window with the given mode.
+fn kmc2_positions_into(
+    storage: &[u64], frag_len: usize, k: usize, l: usize, offset: usize, mode: SplitMode,
+    (min_positions, scores_buf, deque): WindowBuffers,
+) {
+    let scores = kmc2_mode_scores(l, mode);
+    match mode {
+        SplitMode::Sticky => minimizer_positions_sticky(storage, frag_len, k, l, offset, scores, min_positions),
+        SplitMode::Classical => minimizer_positions_deque::<true, _>(storage, frag_len, k, l, offset, scores, min_positions, scores_buf, deque),
+        SplitMode::Msp | SplitMode::MspXor => minimizer_positions_deque::<false, _>(storage, frag_len, k, l, offset, scores, min_positions, scores_buf, deque),
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-struct MinPos {
-    val: usize,
-    pos: usize,
-    kmer: usize,
-}
-
-impl Ord for MinPos {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let val_cmp = self.val.cmp(&other.val);
-        if val_cmp != std::cmp::Ordering::Equal {
-            return val_cmp;
-        }
-        // Tie: higher pos (rightmost) is preferred (sorts as "less")
-        match self.pos.cmp(&other.pos) {
-            std::cmp::Ordering::Equal => std::cmp::Ordering::Equal,
-            std::cmp::Ordering::Less => std::cmp::Ordering::Greater,
-            std::cmp::Ordering::Greater => std::cmp::Ordering::Less,
-        }
-    }
+/// Reported mint of a minimizer l-mer: canonical signatures lose their orientation.
+#[inline]
+fn signature_of(lmer: usize, l: usize, canonical: bool) -> (u32, bool) {
+    (mint_of(lmer, l, canonical).0, false)
 }
 
-impl PartialOrd for MinPos {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+/// Minimizer of a single k-mer (ACGT bases) as (mint, mpos, mint_is_rc), `mpos` being
+/// its offset in the k-mer. The mint always agrees with the superkmer that
+/// `SuperkmersIterator::new` assigns to that k-mer in any read; when the minimizing canonical
+/// l-mer occurs twice in the k-mer, `mpos` (rightmost occurrence) may point to another
+/// occurrence than the iterator's. `mint_is_rc` is always false. l=7..12.
+pub fn minimizer_of(kmer: &[u8], l: usize) -> (u32, u16, bool) {
+    with_packed(kmer, |storage| minimizer_of_packed(storage, 0, kmer.len(), l))
 }
 
-fn msp_minimizer_positions(storage: &[u64], frag_len: usize, k: usize, l: usize, offset: usize) -> Vec<(usize, usize, usize, usize)> {
-    let l_mask = (1usize << (2 * l)) - 1;
-
-    let mp = |pos: usize| -> MinPos {
-        let lmer = get_kmer_value(storage, pos, l);
-        let score = KMC2_SCORES_8[lmer];
-        let canonical = score & l_mask;
-        MinPos { val: score, pos, kmer: canonical }
-    };
-
-    let find_min = |start: usize, stop: usize| -> MinPos {
-        let mut min_pos = mp(start);
-        for pos in (start + 1)..=stop {
-            let current = mp(pos);
-            min_pos = std::cmp::min(min_pos, current);
-        }
-        min_pos
-    };
-
-    let frag_end = offset + frag_len;
-    let mut min_positions: Vec<(usize, usize, usize, usize)> = Vec::new();
-
-    if frag_len >= k {
-        let mut min_pos = find_min(0, k - l);
-        min_positions.push((offset, min_pos.pos + offset, min_pos.kmer, frag_end));
-
-        for i in 1..(frag_len - k + 1) {
-            let end_pos = mp(i + k - l);
-
-            if i > min_pos.pos {
-                min_pos = find_min(i, i + k - l);
-                min_positions.push((i + offset, min_pos.pos + offset, min_pos.kmer, frag_end));
-            } else if end_pos.val < min_pos.val {
-                min_pos = end_pos;
-                min_positions.push((i + offset, min_pos.pos + offset, min_pos.kmer, frag_end));
-            }
-        }
-    }
-
-    min_positions
+/// `minimizer_of` for the k-mer at base `pos` of a 2-bit packed sequence.
+pub fn minimizer_of_packed(storage: &[u64], pos: usize, k: usize, l: usize) -> (u32, u16, bool) {
+    let (offset, lmer) = kmer_minimizer::<true, _>(storage, pos, k, l, kmc2_scores(l));
+    let (mint, mint_is_rc) = signature_of(lmer, l, true);
+    (mint, offset as u16, mint_is_rc)
 }
 
 pub struct SuperkmersIterator {
     min_positions: Vec<(usize, usize, usize, usize)>,
+    storage: Vec<u64>,
     p: usize,
     k: usize,
+    l: usize,
+    canonical: bool,
 }
 
-impl SuperkmersIterator {
-    pub fn new(seq_str: &[u8], k: usize, l: usize) -> (Vec<u64>, Self) {
-        let storage = bitpack_fragment(seq_str);
-        let min_positions = msp_minimizer_positions(&storage, seq_str.len(), k, l, 0);
+macro_rules! iter_constructors {
+    ($($name:ident, $name_n:ident, $canonical:expr, $mode:expr;)*) => {
+        $(
+            pub fn $name(seq_str: &[u8], k: usize, l: usize) -> Self {
+                Self::new_inner(seq_str, k, l, $canonical, $mode)
+            }
+            pub fn $name_n(seq_str: &[u8], k: usize, l: usize) -> Self {
+                Self::new_with_n_inner(seq_str, k, l, $canonical, $mode)
+            }
+        )*
+    };
+}
 
-        (storage, SuperkmersIterator {
-            min_positions,
-            p: 0,
-            k,
-        })
+impl SuperkmersIterator {
+    iter_constructors! {
+        new,                            new_with_n,                            true,  SplitMode::Sticky;
+        non_canonical,                  non_canonical_with_n,                  false, SplitMode::Sticky;
+        classical,                      classical_with_n,                      true,  SplitMode::Classical;
+        classical_non_canonical,        classical_non_canonical_with_n,        false, SplitMode::Classical;
+        msp,                            msp_with_n,                            true,  SplitMode::Msp;
+        msp_non_canonical,              msp_non_canonical_with_n,              false, SplitMode::Msp;
+        mspxor,                         mspxor_with_n,                         true,  SplitMode::MspXor;
+        mspxor_non_canonical,           mspxor_non_canonical_with_n,           false, SplitMode::MspXor;
     }
 
-    pub fn new_with_n(seq_str: &[u8], k: usize, l: usize) -> (Vec<u64>, Self) {
-        let fragments = crate::utils::split_on_n(seq_str, k);
-        let full_storage = bitpack_fragment(seq_str);
+    pub fn storage(&self) -> &[u64] {
+        &self.storage
+    }
 
-        let mut all_min_positions: Vec<(usize, usize, usize, usize)> = Vec::new();
+    fn new_inner(seq_str: &[u8], k: usize, l: usize, canonical: bool, mode: SplitMode) -> Self {
+        let storage = bitpack_fragment(seq_str);
+        let mut min_positions = Vec::new();
+        let (mut scores_buf, mut deque) = (Vec::new(), Vec::new());
+        kmc2_positions_into(&storage, seq_str.len(), k, l, 0, mode, (&mut min_positions, &mut scores_buf, &mut deque));
+        SuperkmersIterator { min_positions, storage, p: 0, k, l, canonical }
+    }
 
+    fn new_with_n_inner(seq_str: &[u8], k: usize, l: usize, canonical: bool, mode: SplitMode) -> Self {
+        let mut min_positions = Vec::new();
+        let (mut scores_buf, mut deque) = (Vec::new(), Vec::new());
         let mut frag_storage = Vec::new();
-        for (offset, fragment) in &fragments {
-            let frag_words = (fragment.len() + 31) / 32;
-            frag_storage.resize(frag_words, 0);
+        for (offset, fragment) in crate::utils::split_on_n(seq_str, k) {
+            frag_storage.resize(fragment.len().div_ceil(32), 0);
             crate::utils::bitpack_fragment_into(fragment, &mut frag_storage);
-            let positions = msp_minimizer_positions(&frag_storage, fragment.len(), k, l, *offset);
-            all_min_positions.extend(positions);
+            kmc2_positions_into(&frag_storage, fragment.len(), k, l, offset, mode, (&mut min_positions, &mut scores_buf, &mut deque));
         }
-
-        (full_storage, SuperkmersIterator {
-            min_positions: all_min_positions,
-            p: 0,
-            k,
-        })
+        SuperkmersIterator { min_positions, storage: bitpack_fragment(seq_str), p: 0, k, l, canonical }
     }
 }
 
@@ -200,28 +189,129 @@ impl Iterator for SuperkmersIterator {
         if self.p >= self.min_positions.len() {
             return None;
         }
-
         let (start_pos, min_abs_pos, min_kmer, frag_end) = self.min_positions[self.p];
-
-        let size = if self.p < self.min_positions.len() - 1 {
-            let (next_pos, _, _, next_frag_end) = self.min_positions[self.p + 1];
-            if next_frag_end == frag_end {
-                next_pos + self.k - 1 - start_pos
-            } else {
-                frag_end - start_pos
-            }
-        } else {
-            frag_end - start_pos
+        let size = match self.min_positions.get(self.p + 1) {
+            Some(&(next_pos, _, _, next_frag_end)) if next_frag_end == frag_end => next_pos + self.k - 1 - start_pos,
+            _ => frag_end - start_pos,
         };
-
         self.p += 1;
-
+        let (mint, mint_is_rc) = signature_of(min_kmer, self.l, self.canonical);
         Some(Superkmer {
             start: start_pos,
-            mint: min_kmer as u32,
+            mint,
             size: size as u16,
             mpos: (min_abs_pos - start_pos) as u16,
-            mint_is_rc: false,
+            mint_is_rc,
         })
     }
 }
+
+/// Reusable superkmer extractor that avoids per-read allocations.
+pub struct SuperkmerExtractor {
+    superkmers: Vec<Superkmer>,
+    min_positions: Vec<(usize, usize, usize, usize)>,
+    storage: Vec<u64>,
+    frag_storage: Vec<u64>,
+    scores_buf: Vec<usize>,
+    deque: Vec<usize>,
+    k: usize,
+    l: usize,
+    canonical: bool,
+    mode: SplitMode,
+}
+
+macro_rules! extractor_constructors {
+    ($($name:ident, $canonical:expr, $mode:expr;)*) => {
+        $(
+            pub fn $name(k: usize, l: usize) -> Self {
+                Self::new_inner(k, l, $canonical, $mode)
+            }
+        )*
+    };
+}
+
+impl SuperkmerExtractor {
+    extractor_constructors! {
+        new,                       true,  SplitMode::Sticky;
+        non_canonical,             false, SplitMode::Sticky;
+        classical,                 true,  SplitMode::Classical;
+        classical_non_canonical,   false, SplitMode::Classical;
+        msp,                       true,  SplitMode::Msp;
+        msp_non_canonical,         false, SplitMode::Msp;
+        mspxor,                    true,  SplitMode::MspXor;
+        mspxor_non_canonical,      false, SplitMode::MspXor;
+    }
+
+    fn new_inner(k: usize, l: usize, canonical: bool, mode: SplitMode) -> Self {
+        // Generate the table up front rather than on the first read
+        kmc2_mode_scores(l, mode);
+        SuperkmerExtractor {
+            superkmers: Vec::new(),
+            min_positions: Vec::new(),
+            storage: Vec::new(),
+            frag_storage: Vec::new(),
+            scores_buf: Vec::new(),
+            deque: Vec::new(),
+            k, l, canonical, mode,
+        }
+    }
+
+    /// Convert min_positions, dropping the orientation of canonical signatures.
+    fn materialize(&mut self) {
+        materialize_superkmers(&self.min_positions, self.k, self.l, self.canonical, &mut self.superkmers);
+        for sk in self.superkmers.iter_mut() {
+            sk.mint_is_rc = false;
+        }
+    }
+
+    /// Process a sequence with no N characters. Returns the superkmers slice.
+    pub fn process(&mut self, seq: &[u8]) -> &[Superkmer] {
+        self.superkmers.clear();
+        self.min_positions.clear();
+        self.storage.resize(seq.len().div_ceil(32), 0);
+        crate::utils::bitpack_fragment_into(seq, &mut self.storage);
+        kmc2_positions_into(&self.storage, seq.len(), self.k, self.l, 0, self.mode, (&mut self.min_positions, &mut self.scores_buf, &mut self.deque));
+        self.materialize();
+        &self.superkmers
+    }
+
+    /// Process a sequence that may contain N/n characters. Returns the superkmers slice.
+    pub fn process_with_n(&mut self, seq: &[u8]) -> &[Superkmer] {
+        self.superkmers.clear();
+        self.min_positions.clear();
+        self.storage.resize(seq.len().div_ceil(32), 0);
+        crate::utils::bitpack_fragment_into(seq, &mut self.storage);
+        for (offset, fragment) in crate::utils::split_on_n(seq, self.k) {
+            self.frag_storage.resize(fragment.len().div_ceil(32), 0);
+            crate::utils::bitpack_fragment_into(fragment, &mut self.frag_storage);
+            kmc2_positions_into(&self.frag_storage, fragment.len(), self.k, self.l, offset, self.mode, (&mut self.min_positions, &mut self.scores_buf, &mut self.deque));
+        }
+        self.materialize();
+        &self.superkmers
+    }
+
+    crate::chunked::process_range_methods!();
+
+    /// Minimizer of a single k-mer as (mint, mpos, mint_is_rc), as in
+    /// `iteratorsyncmers2::SuperkmerExtractor::minimizer_of`. `None` for sticky extractors.
+    pub fn minimizer_of(&self, kmer: &[u8]) -> Option<(u32, u16, bool)> {
+        assert_eq!(kmer.len(), self.k, "minimizer_of expects a k-mer");
+        with_packed(kmer, |storage| self.minimizer_of_packed(storage, 0))
+    }
+
+    /// `minimizer_of` for the k-mer at base `pos` of a 2-bit packed sequence.
+    pub fn minimizer_of_packed(&self, storage: &[u64], pos: usize) -> Option<(u32, u16, bool)> {
+        let scores = kmc2_mode_scores(self.l, self.mode);
+        let (offset, lmer) = match self.mode {
+            SplitMode::Sticky => return None,
+            SplitMode::Classical => kmer_minimizer::<true, _>(storage, pos, self.k, self.l, scores),
+            SplitMode::Msp | SplitMode::MspXor => kmer_minimizer::<false, _>(storage, pos, self.k, self.l, scores),
+        };
+        let (mint, mint_is_rc) = signature_of(lmer, self.l, self.canonical);
+        Some((mint, offset as u16, mint_is_rc))
+    }
+
+    pub fn storage(&self) -> &[u64] {
+        &self.storage
+    }
+}
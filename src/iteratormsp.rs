@@ -1,89 +1,263 @@
-use crate::Superkmer;
-use debruijn::dna_string::DnaString;
-use debruijn::kmer::{Kmer8, Kmer10, Kmer12};
-use debruijn::Kmer;
-use debruijn::msp::Scanner;
+//! Lexicographic minimizers, the order of MSP (Li et al.) and rust-debruijn's `Scanner`.
+//!
+//! Scores are computed from the l-mer (`minimizer_core::Lexicographic`) instead of
+//! looked up, so any l up to `tables::MAX_L` works and nothing is tabulated. The
+//! poly-A l-mer is demoted to last, as in rust-debruijn. Like the other schemes the
+//! order is on canonical l-mers; MspXor XORs them with the usual constant first.
+//! The `forward` constructors rank forward l-mers with sticky splitting, which is
+//! what rust-debruijn's `Scanner` computes.
+//!
+//! `SuperkmersIterator::new` keeps its contract from when this module wrapped
+//! rust-debruijn: it takes `DnaString::to_bytes()` 2-bit codes and returns forward
+//! superkmers. The canonical sticky constructors, `new` in the other schemes, are
+//! `sticky` / `sticky_with_n` here.
+//!
+//! ```
+//! use rust_superkmers::iteratormsp::SuperkmerExtractor;
+//! let seq = b"AACTGCACTGCACTGCACTGCACACTGCACTGCACTGCACTGCAC";
+//! let mut ext = SuperkmerExtractor::msp(31, 10);
+//! for sk in ext.process(seq) { /* canonical mint */ }
+//! ```
+use crate::{Superkmer, SplitMode};
+use crate::minimizer_core::{kmer_minimizer, minimizer_positions_deque, minimizer_positions_sticky, materialize_superkmers, mint_of, with_packed, Lexicographic, WindowBuffers};
+use crate::tables::MAX_L;
+use crate::utils::bitpack_fragment;
 
-/// Extract superkmers from ASCII sequence, splitting on N/n characters.
-pub fn superkmers_with_n(seq: &[u8], k: usize, l: usize) -> Vec<Superkmer> {
-    let fragments = crate::utils::split_on_n(seq, k);
-    let mut all = Vec::new();
-    for (offset, fragment) in fragments {
-        let dnastring = DnaString::from_acgt_bytes(fragment).to_bytes();
-        let iter = SuperkmersIterator::new(&dnastring, k, l);
-        for mut sk in iter {
-            sk.start += offset;
-            all.push(sk);
-        }
-    }
-    all
-}
+/// XORed into canonical l-mers for MspXor, as in the syncmer and UHS tiebreakers.
+const XOR_CONSTANT: u32 = 0xACE5_ACE5;
 
-pub struct SuperkmersIterator<'a> {
-    iter: Box<dyn Iterator<Item = Superkmer> + 'a>,
+/// Order used by `mode`: canonical lexicographic, XORed for MspXor.
+pub fn lexicographic_order(l: usize, mode: SplitMode) -> Lexicographic {
+    assert!((1..=MAX_L).contains(&l), "lexicographic minimizers support l in 1..={}", MAX_L);
+    let xor = if mode == SplitMode::MspXor { XOR_CONSTANT & ((1 << (2 * l)) - 1) } else { 0 };
+    Lexicographic { l, canonical: true, xor }
 }
 
-fn score8(p: &Kmer8) -> usize {
-    if p.to_u64() == 0 { std::usize::MAX } else { p.to_u64() as usize }
+/// Order of the `forward` constructors: forward l-mers, poly-A last.
+pub fn forward_order(l: usize) -> Lexicographic {
+    Lexicographic { canonical: false, ..lexicographic_order(l, SplitMode::Sticky) }
 }
 
-fn score10(p: &Kmer10) -> usize {
-    if p.to_u64() == 0 { std::usize::MAX } else { p.to_u64() as usize }
+/// Extract superkmers from ASCII sequence, splitting on N/n characters, with forward
+/// l-mers and sticky splitting (rust-debruijn's MSP).
+pub fn superkmers_with_n(seq: &[u8], k: usize, l: usize) -> Vec<Superkmer> {
+    SuperkmersIterator::forward_with_n(seq, k, l).collect()
 }
 
-fn score12(p: &Kmer12) -> usize {
-    if p.to_u64() == 0 { std::usize::MAX } else { p.to_u64() as usize }
+/// Sliding window with the given mode, over l-mers of `order.l` bases.
+fn msp_positions_into(
+    storage: &[u64], frag_len: usize, k: usize, offset: usize, mode: SplitMode, order: &Lexicographic,
+    (min_positions, scores_buf, deque): WindowBuffers,
+) {
+    let l = order.l;
+    match mode {
+        SplitMode::Sticky => minimizer_positions_sticky(storage, frag_len, k, l, offset, order, min_positions),
+        SplitMode::Classical => minimizer_positions_deque::<true, _>(storage, frag_len, k, l, offset, order, min_positions, scores_buf, deque),
+        SplitMode::Msp | SplitMode::MspXor => minimizer_positions_deque::<false, _>(storage, frag_len, k, l, offset, order, min_positions, scores_buf, deque),
+    }
 }
 
+pub struct SuperkmersIterator {
+    min_positions: Vec<(usize, usize, usize, usize)>,
+    storage: Vec<u64>,
+    p: usize,
+    k: usize,
+    l: usize,
+    canonical: bool,
+}
 
- /* wrapper around rust-debruijn msp funtions
-  */
-impl<'a> SuperkmersIterator<'a> {
-    pub fn new(dnastring: &'a [u8], k: usize, l: usize) -> Self {
-        let dnastring = &DnaString::from_bytes(dnastring);
-        let superkmer_iter: Box<dyn Iterator<Item = Superkmer>> = match l {
-            8 => {
-                let scanner8 = Scanner::new(dnastring, score8, k);
-                Box::new(scanner8.scan().into_iter().map(|msp| Superkmer {
-                    start: msp.start as usize,
-                    mint: msp.minimizer.to_u64() as u32,
-                    size: msp.len as u16,
-                    mpos: (msp.minimizer_pos - msp.start) as u16,
-                    mint_is_rc: false,
-                }))
+macro_rules! iter_constructors {
+    ($($name:ident, $name_n:ident, $canonical:expr, $mode:expr;)*) => {
+        $(
+            pub fn $name(seq_str: &[u8], k: usize, l: usize) -> Self {
+                Self::new_inner(seq_str, k, l, $canonical, $mode, lexicographic_order(l, $mode))
             }
-            10 => {
-                let scanner10 = Scanner::new(dnastring, score10, k);
-                Box::new(scanner10.scan().into_iter().map(|msp| Superkmer {
-                    start: msp.start as usize,
-                    mint: msp.minimizer.to_u64() as u32,
-                    size: msp.len as u16,
-                    mpos: (msp.minimizer_pos - msp.start) as u16,
-                    mint_is_rc: false,
-                }))
+            pub fn $name_n(seq_str: &[u8], k: usize, l: usize) -> Self {
+                Self::new_with_n_inner(seq_str, k, l, $canonical, $mode, lexicographic_order(l, $mode))
             }
-            12 => {
-                let scanner12 = Scanner::new(dnastring, score12, k);
-                Box::new(scanner12.scan().into_iter().map(|msp| Superkmer {
-                    start: msp.start as usize,
-                    mint: msp.minimizer.to_u64() as u32,
-                    size: msp.len as u16,
-                    mpos: (msp.minimizer_pos - msp.start) as u16,
-                    mint_is_rc: false,
-                }))
-            }
-            _ => panic!("Unsupported l size for MSP iteration"),
-        };
+        )*
+    };
+}
 
-        SuperkmersIterator { iter: superkmer_iter }
+impl SuperkmersIterator {
+    iter_constructors! {
+        sticky,                         sticky_with_n,                         true,  SplitMode::Sticky;
+        non_canonical,                  non_canonical_with_n,                  false, SplitMode::Sticky;
+        classical,                      classical_with_n,                      true,  SplitMode::Classical;
+        classical_non_canonical,        classical_non_canonical_with_n,        false, SplitMode::Classical;
+        msp,                            msp_with_n,                            true,  SplitMode::Msp;
+        msp_non_canonical,              msp_non_canonical_with_n,              false, SplitMode::Msp;
+        mspxor,                         mspxor_with_n,                         true,  SplitMode::MspXor;
+        mspxor_non_canonical,           mspxor_non_canonical_with_n,           false, SplitMode::MspXor;
+    }
+
+    /// `forward` on 2-bit codes (A=0, C=1, G=2, T=3, one per byte), as produced by
+    /// rust-debruijn's `DnaString::to_bytes()`.
+    pub fn new(codes: &[u8], k: usize, l: usize) -> Self {
+        let seq: Vec<u8> = codes.iter().map(|&c| b"ACGT"[c as usize & 3]).collect();
+        Self::forward(&seq, k, l)
+    }
+
+    /// Forward l-mers, sticky splitting: the superkmers of rust-debruijn's `Scanner`.
+    pub fn forward(seq_str: &[u8], k: usize, l: usize) -> Self {
+        Self::new_inner(seq_str, k, l, false, SplitMode::Sticky, forward_order(l))
+    }
+
+    pub fn forward_with_n(seq_str: &[u8], k: usize, l: usize) -> Self {
+        Self::new_with_n_inner(seq_str, k, l, false, SplitMode::Sticky, forward_order(l))
+    }
+
+    pub fn storage(&self) -> &[u64] {
+        &self.storage
+    }
+
+    fn new_inner(seq_str: &[u8], k: usize, l: usize, canonical: bool, mode: SplitMode, order: Lexicographic) -> Self {
+        let storage = bitpack_fragment(seq_str);
+        let mut min_positions = Vec::new();
+        let (mut scores_buf, mut deque) = (Vec::new(), Vec::new());
+        msp_positions_into(&storage, seq_str.len(), k, 0, mode, &order, (&mut min_positions, &mut scores_buf, &mut deque));
+        SuperkmersIterator { min_positions, storage, p: 0, k, l, canonical }
+    }
+
+    fn new_with_n_inner(seq_str: &[u8], k: usize, l: usize, canonical: bool, mode: SplitMode, order: Lexicographic) -> Self {
+        let mut min_positions = Vec::new();
+        let (mut scores_buf, mut deque) = (Vec::new(), Vec::new());
+        let mut frag_storage = Vec::new();
+        for (offset, fragment) in crate::utils::split_on_n(seq_str, k) {
+            frag_storage.resize(fragment.len().div_ceil(32), 0);
+            crate::utils::bitpack_fragment_into(fragment, &mut frag_storage);
+            msp_positions_into(&frag_storage, fragment.len(), k, offset, mode, &order, (&mut min_positions, &mut scores_buf, &mut deque));
+        }
+        SuperkmersIterator { min_positions, storage: bitpack_fragment(seq_str), p: 0, k, l, canonical }
     }
 }
 
-impl<'a> Iterator for SuperkmersIterator<'a> {
+impl Iterator for SuperkmersIterator {
     type Item = Superkmer;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
+        if self.p >= self.min_positions.len() {
+            return None;
+        }
+        let (start_pos, min_abs_pos, min_kmer, frag_end) = self.min_positions[self.p];
+        let size = match self.min_positions.get(self.p + 1) {
+            Some(&(next_pos, _, _, next_frag_end)) if next_frag_end == frag_end => next_pos + self.k - 1 - start_pos,
+            _ => frag_end - start_pos,
+        };
+        self.p += 1;
+        let (mint, mint_is_rc) = mint_of(min_kmer, self.l, self.canonical);
+        Some(Superkmer {
+            start: start_pos,
+            mint,
+            size: size as u16,
+            mpos: (min_abs_pos - start_pos) as u16,
+            mint_is_rc,
+        })
     }
 }
 
+/// Reusable superkmer extractor that avoids per-read allocations.
+pub struct SuperkmerExtractor {
+    superkmers: Vec<Superkmer>,
+    min_positions: Vec<(usize, usize, usize, usize)>,
+    storage: Vec<u64>,
+    frag_storage: Vec<u64>,
+    scores_buf: Vec<usize>,
+    deque: Vec<usize>,
+    order: Lexicographic,
+    k: usize,
+    l: usize,
+    canonical: bool,
+    mode: SplitMode,
+}
+
+macro_rules! extractor_constructors {
+    ($($name:ident, $canonical:expr, $mode:expr;)*) => {
+        $(
+            pub fn $name(k: usize, l: usize) -> Self {
+                Self::new_inner(k, l, $canonical, $mode, lexicographic_order(l, $mode))
+            }
+        )*
+    };
+}
+
+impl SuperkmerExtractor {
+    extractor_constructors! {
+        sticky,                    true,  SplitMode::Sticky;
+        non_canonical,             false, SplitMode::Sticky;
+        classical,                 true,  SplitMode::Classical;
+        classical_non_canonical,   false, SplitMode::Classical;
+        msp,                       true,  SplitMode::Msp;
+        msp_non_canonical,         false, SplitMode::Msp;
+        mspxor,                    true,  SplitMode::MspXor;
+        mspxor_non_canonical,      false, SplitMode::MspXor;
+    }
+
+    /// Forward l-mers, sticky splitting: the superkmers of rust-debruijn's `Scanner`.
+    pub fn forward(k: usize, l: usize) -> Self {
+        Self::new_inner(k, l, false, SplitMode::Sticky, forward_order(l))
+    }
+
+    fn new_inner(k: usize, l: usize, canonical: bool, mode: SplitMode, order: Lexicographic) -> Self {
+        SuperkmerExtractor {
+            superkmers: Vec::new(),
+            min_positions: Vec::new(),
+            storage: Vec::new(),
+            frag_storage: Vec::new(),
+            scores_buf: Vec::new(),
+            deque: Vec::new(),
+            order,
+            k, l, canonical, mode,
+        }
+    }
+
+    /// Process a sequence with no N characters. Returns the superkmers slice.
+    pub fn process(&mut self, seq: &[u8]) -> &[Superkmer] {
+        self.superkmers.clear();
+        self.min_positions.clear();
+        self.storage.resize(seq.len().div_ceil(32), 0);
+        crate::utils::bitpack_fragment_into(seq, &mut self.storage);
+        msp_positions_into(&self.storage, seq.len(), self.k, 0, self.mode, &self.order, (&mut self.min_positions, &mut self.scores_buf, &mut self.deque));
+        materialize_superkmers(&self.min_positions, self.k, self.l, self.canonical, &mut self.superkmers);
+        &self.superkmers
+    }
+
+    /// Process a sequence that may contain N/n characters. Returns the superkmers slice.
+    pub fn process_with_n(&mut self, seq: &[u8]) -> &[Superkmer] {
+        self.superkmers.clear();
+        self.min_positions.clear();
+        self.storage.resize(seq.len().div_ceil(32), 0);
+        crate::utils::bitpack_fragment_into(seq, &mut self.storage);
+        for (offset, fragment) in crate::utils::split_on_n(seq, self.k) {
+            self.frag_storage.resize(fragment.len().div_ceil(32), 0);
+            crate::utils::bitpack_fragment_into(fragment, &mut self.frag_storage);
+            msp_positions_into(&self.frag_storage, fragment.len(), self.k, offset, self.mode, &self.order, (&mut self.min_positions, &mut self.scores_buf, &mut self.deque));
+        }
+        materialize_superkmers(&self.min_positions, self.k, self.l, self.canonical, &mut self.superkmers);
+        &self.superkmers
+    }
+
+    crate::chunked::process_range_methods!();
+
+    /// Minimizer of a single k-mer as (mint, mpos, mint_is_rc), as in
+    /// `iteratoruhs::SuperkmerExtractor::minimizer_of`. `None` for sticky extractors.
+    pub fn minimizer_of(&self, kmer: &[u8]) -> Option<(u32, u16, bool)> {
+        assert_eq!(kmer.len(), self.k, "minimizer_of expects a k-mer");
+        with_packed(kmer, |storage| self.minimizer_of_packed(storage, 0))
+    }
+
+    /// `minimizer_of` for the k-mer at base `pos` of a 2-bit packed sequence.
+    pub fn minimizer_of_packed(&self, storage: &[u64], pos: usize) -> Option<(u32, u16, bool)> {
+        let (offset, lmer) = match self.mode {
+            SplitMode::Sticky => return None,
+            SplitMode::Classical => kmer_minimizer::<true, _>(storage, pos, self.k, self.l, &self.order),
+            SplitMode::Msp | SplitMode::MspXor => kmer_minimizer::<false, _>(storage, pos, self.k, self.l, &self.order),
+        };
+        let (mint, mint_is_rc) = mint_of(lmer, self.l, self.canonical);
+        Some((mint, offset as u16, mint_is_rc))
+    }
+
+    pub fn storage(&self) -> &[u64] {
+        &self.storage
+    }
+}
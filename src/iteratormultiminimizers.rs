@@ -33,14 +33,7 @@ fn canonical_lmer_index(ascii: &[u8], pos: usize, l: usize) -> (usize, bool) {
         let base = ASCII_TO_2BIT[ascii[pos + i] as usize] as usize;
         fwd = (fwd << 2) | base;
     }
-    let canonical_table = match l {
-        8 => &*crate::CANONICAL_8,
-        9 => &*crate::CANONICAL_9,
-        10 => &*crate::CANONICAL_10,
-        12 => &*crate::CANONICAL_12,
-        _ => panic!("Unsupported l={} for canonical lookup", l),
-    };
-    let (canonical, is_rc) = canonical_table[fwd];
+    let (canonical, is_rc) = crate::tables::canonical_lmer(fwd, l);
     (canonical as usize, is_rc)
 }
 
@@ -0,0 +1,290 @@
+//! Random-order minimizers: l-mers ranked by a seeded hash of their canonical value.
+//!
+//! The hash is a bijection on 32-bit values, so two l-mers only tie when one is the
+//! reverse complement of the other. Scores are tabulated per l (4 bytes per l-mer)
+//! and the sliding window is the generic one from `minimizer_core`, so all split
+//! modes are available. Msp and MspXor are both plain random orders here; MspXor
+//! hashes with the seed XORed by the constant the other schemes use, giving a second,
+//! independent order.
+//!
+//! ```
+//! use rust_superkmers::iteratorrandom::SuperkmerExtractor;
+//! let seq = b"AACTGCACTGCACTGCACTGCACACTGCACTGCACTGCACTGCAC";
+//! let mut ext = SuperkmerExtractor::mspxor(31, 11).with_seed(7);
+//! for sk in ext.process(seq) { /* canonical mint */ }
+//! ```
+use std::borrow::Cow;
+
+use crate::{Superkmer, SplitMode};
+use crate::minimizer_core::{canonical_lmer, kmer_minimizer, minimizer_positions_deque, minimizer_positions_sticky, materialize_superkmers, mint_of, with_packed};
+use crate::utils::bitpack_fragment;
+use crate::tables::LazyTables;
+
+/// Seed of the order used by the `SuperkmersIterator` constructors and by default in
+/// `SuperkmerExtractor`.
+pub const DEFAULT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// XORed into the seed for MspXor, as in the syncmer and UHS tiebreakers.
+const XOR_CONSTANT: u64 = 0xACE5_ACE5;
+
+/// Seeded hash of a canonical l-mer (murmur3 finalizer), a bijection on `u32`.
+#[inline(always)]
+pub fn random_score(canon: u32, seed: u64) -> u32 {
+    let mut h = canon ^ seed as u32 ^ (seed >> 32) as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+/// Score of every l-mer: the hash of its canonical l-mer.
+pub fn generate_random_scores(l: usize, seed: u64) -> Vec<u32> {
+    (0..1usize << (2 * l)).map(|fwd| random_score(canonical_lmer(fwd, l).0, seed)).collect()
+}
+
+fn default_scores(l: usize) -> Vec<u32> {
+    generate_random_scores(l, DEFAULT_SEED)
+}
+
+fn default_mspxor_scores(l: usize) -> Vec<u32> {
+    generate_random_scores(l, DEFAULT_SEED ^ XOR_CONSTANT)
+}
+
+static RANDOM_SCORES: LazyTables<u32> = LazyTables::new("random", default_scores);
+static RANDOM_MSPXOR_SCORES: LazyTables<u32> = LazyTables::new("random-mspxor", default_mspxor_scores);
+
+/// Scores that `mode` uses with `seed`; the default seed's tables are shared.
+pub fn random_mode_scores(l: usize, mode: SplitMode, seed: u64) -> Cow<'static, [u32]> {
+    match (mode, seed == DEFAULT_SEED) {
+        (SplitMode::MspXor, true) => Cow::Borrowed(RANDOM_MSPXOR_SCORES.get(l)),
+        (SplitMode::MspXor, false) => Cow::Owned(generate_random_scores(l, seed ^ XOR_CONSTANT)),
+        (_, true) => Cow::Borrowed(RANDOM_SCORES.get(l)),
+        (_, false) => Cow::Owned(generate_random_scores(l, seed)),
+    }
+}
+
+/// Window parameters shared by every fragment of one extraction.
+struct Window<'a> {
+    k: usize,
+    l: usize,
+    mode: SplitMode,
+    scores: &'a [u32],
+}
+
+/// Sliding window with the given mode.
+fn random_positions_into(
+    window: &Window, storage: &[u64], frag_len: usize, offset: usize,
+    min_positions: &mut Vec<(usize, usize, usize, usize)>, scores_buf: &mut Vec<usize>, deque: &mut Vec<usize>,
+) {
+    let &Window { k, l, mode, scores } = window;
+    match mode {
+        SplitMode::Sticky => minimizer_positions_sticky(storage, frag_len, k, l, offset, scores, min_positions),
+        SplitMode::Classical => minimizer_positions_deque::<true, _>(storage, frag_len, k, l, offset, scores, min_positions, scores_buf, deque),
+        SplitMode::Msp | SplitMode::MspXor => minimizer_positions_deque::<false, _>(storage, frag_len, k, l, offset, scores, min_positions, scores_buf, deque),
+    }
+}
+
+pub struct SuperkmersIterator {
+    min_positions: Vec<(usize, usize, usize, usize)>,
+    storage: Vec<u64>,
+    p: usize,
+    k: usize,
+    l: usize,
+    canonical: bool,
+}
+
+macro_rules! iter_constructors {
+    ($($name:ident, $name_n:ident, $canonical:expr, $mode:expr;)*) => {
+        $(
+            pub fn $name(seq_str: &[u8], k: usize, l: usize) -> Self {
+                Self::new_inner(seq_str, k, l, $canonical, $mode)
+            }
+            pub fn $name_n(seq_str: &[u8], k: usize, l: usize) -> Self {
+                Self::new_with_n_inner(seq_str, k, l, $canonical, $mode)
+            }
+        )*
+    };
+}
+
+impl SuperkmersIterator {
+    iter_constructors! {
+        new,                            new_with_n,                            true,  SplitMode::Sticky;
+        non_canonical,                  non_canonical_with_n,                  false, SplitMode::Sticky;
+        classical,                      classical_with_n,                      true,  SplitMode::Classical;
+        classical_non_canonical,        classical_non_canonical_with_n,        false, SplitMode::Classical;
+        msp,                            msp_with_n,                            true,  SplitMode::Msp;
+        msp_non_canonical,              msp_non_canonical_with_n,              false, SplitMode::Msp;
+        mspxor,                         mspxor_with_n,                         true,  SplitMode::MspXor;
+        mspxor_non_canonical,           mspxor_non_canonical_with_n,           false, SplitMode::MspXor;
+    }
+
+    pub fn storage(&self) -> &[u64] {
+        &self.storage
+    }
+
+    fn new_inner(seq_str: &[u8], k: usize, l: usize, canonical: bool, mode: SplitMode) -> Self {
+        let storage = bitpack_fragment(seq_str);
+        let scores = random_mode_scores(l, mode, DEFAULT_SEED);
+        let mut min_positions = Vec::new();
+        let (mut scores_buf, mut deque) = (Vec::new(), Vec::new());
+        let window = Window { k, l, mode, scores: &scores };
+        random_positions_into(&window, &storage, seq_str.len(), 0, &mut min_positions, &mut scores_buf, &mut deque);
+        SuperkmersIterator { min_positions, storage, p: 0, k, l, canonical }
+    }
+
+    fn new_with_n_inner(seq_str: &[u8], k: usize, l: usize, canonical: bool, mode: SplitMode) -> Self {
+        let scores = random_mode_scores(l, mode, DEFAULT_SEED);
+        let mut min_positions = Vec::new();
+        let (mut scores_buf, mut deque) = (Vec::new(), Vec::new());
+        let mut frag_storage = Vec::new();
+        let window = Window { k, l, mode, scores: &scores };
+        for (offset, fragment) in crate::utils::split_on_n(seq_str, k) {
+            frag_storage.resize(fragment.len().div_ceil(32), 0);
+            crate::utils::bitpack_fragment_into(fragment, &mut frag_storage);
+            random_positions_into(&window, &frag_storage, fragment.len(), offset, &mut min_positions, &mut scores_buf, &mut deque);
+        }
+        SuperkmersIterator { min_positions, storage: bitpack_fragment(seq_str), p: 0, k, l, canonical }
+    }
+}
+
+impl Iterator for SuperkmersIterator {
+    type Item = Superkmer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.p >= self.min_positions.len() {
+            return None;
+        }
+        let (start_pos, min_abs_pos, min_kmer, frag_end) = self.min_positions[self.p];
+        let size = match self.min_positions.get(self.p + 1) {
+            Some(&(next_pos, _, _, next_frag_end)) if next_frag_end == frag_end => next_pos + self.k - 1 - start_pos,
+            _ => frag_end - start_pos,
+        };
+        self.p += 1;
+        let (mint, mint_is_rc) = mint_of(min_kmer, self.l, self.canonical);
+        Some(Superkmer {
+            start: start_pos,
+            mint,
+            size: size as u16,
+            mpos: (min_abs_pos - start_pos) as u16,
+            mint_is_rc,
+        })
+    }
+}
+
+/// Reusable superkmer extractor that avoids per-read allocations.
+pub struct SuperkmerExtractor {
+    superkmers: Vec<Superkmer>,
+    min_positions: Vec<(usize, usize, usize, usize)>,
+    storage: Vec<u64>,
+    frag_storage: Vec<u64>,
+    scores_buf: Vec<usize>,
+    deque: Vec<usize>,
+    scores: Cow<'static, [u32]>,
+    seed: u64,
+    k: usize,
+    l: usize,
+    canonical: bool,
+    mode: SplitMode,
+}
+
+macro_rules! extractor_constructors {
+    ($($name:ident, $canonical:expr, $mode:expr;)*) => {
+        $(
+            pub fn $name(k: usize, l: usize) -> Self {
+                Self::new_inner(k, l, $canonical, $mode)
+            }
+        )*
+    };
+}
+
+impl SuperkmerExtractor {
+    extractor_constructors! {
+        new,                       true,  SplitMode::Sticky;
+        non_canonical,             false, SplitMode::Sticky;
+        classical,                 true,  SplitMode::Classical;
+        classical_non_canonical,   false, SplitMode::Classical;
+        msp,                       true,  SplitMode::Msp;
+        msp_non_canonical,         false, SplitMode::Msp;
+        mspxor,                    true,  SplitMode::MspXor;
+        mspxor_non_canonical,      false, SplitMode::MspXor;
+    }
+
+    fn new_inner(k: usize, l: usize, canonical: bool, mode: SplitMode) -> Self {
+        SuperkmerExtractor {
+            superkmers: Vec::new(),
+            min_positions: Vec::new(),
+            storage: Vec::new(),
+            frag_storage: Vec::new(),
+            scores_buf: Vec::new(),
+            deque: Vec::new(),
+            scores: random_mode_scores(l, mode, DEFAULT_SEED),
+            seed: DEFAULT_SEED,
+            k, l, canonical, mode,
+        }
+    }
+
+    /// Use the order of another seed. Seeds other than `DEFAULT_SEED` get their own
+    /// score table, generated here.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.scores = random_mode_scores(self.l, self.mode, seed);
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Process a sequence with no N characters. Returns the superkmers slice.
+    pub fn process(&mut self, seq: &[u8]) -> &[Superkmer] {
+        self.superkmers.clear();
+        self.min_positions.clear();
+        self.storage.resize(seq.len().div_ceil(32), 0);
+        crate::utils::bitpack_fragment_into(seq, &mut self.storage);
+        let window = Window { k: self.k, l: self.l, mode: self.mode, scores: &self.scores };
+        random_positions_into(&window, &self.storage, seq.len(), 0, &mut self.min_positions, &mut self.scores_buf, &mut self.deque);
+        materialize_superkmers(&self.min_positions, self.k, self.l, self.canonical, &mut self.superkmers);
+        &self.superkmers
+    }
+
+    /// Process a sequence that may contain N/n characters. Returns the superkmers slice.
+    pub fn process_with_n(&mut self, seq: &[u8]) -> &[Superkmer] {
+        self.superkmers.clear();
+        self.min_positions.clear();
+        self.storage.resize(seq.len().div_ceil(32), 0);
+        crate::utils::bitpack_fragment_into(seq, &mut self.storage);
+        let window = Window { k: self.k, l: self.l, mode: self.mode, scores: &self.scores };
+        for (offset, fragment) in crate::utils::split_on_n(seq, self.k) {
+            self.frag_storage.resize(fragment.len().div_ceil(32), 0);
+            crate::utils::bitpack_fragment_into(fragment, &mut self.frag_storage);
+            random_positions_into(&window, &self.frag_storage, fragment.len(), offset, &mut self.min_positions, &mut self.scores_buf, &mut self.deque);
+        }
+        materialize_superkmers(&self.min_positions, self.k, self.l, self.canonical, &mut self.superkmers);
+        &self.superkmers
+    }
+
+    crate::chunked::process_range_methods!();
+
+    /// Minimizer of a single k-mer as (mint, mpos, mint_is_rc), as in
+    /// `iteratoruhs::SuperkmerExtractor::minimizer_of`. `None` for sticky extractors.
+    pub fn minimizer_of(&self, kmer: &[u8]) -> Option<(u32, u16, bool)> {
+        assert_eq!(kmer.len(), self.k, "minimizer_of expects a k-mer");
+        with_packed(kmer, |storage| self.minimizer_of_packed(storage, 0))
+    }
+
+    /// `minimizer_of` for the k-mer at base `pos` of a 2-bit packed sequence.
+    pub fn minimizer_of_packed(&self, storage: &[u64], pos: usize) -> Option<(u32, u16, bool)> {
+        let (offset, lmer) = match self.mode {
+            SplitMode::Sticky => return None,
+            SplitMode::Classical => kmer_minimizer::<true, _>(storage, pos, self.k, self.l, &*self.scores),
+            SplitMode::Msp | SplitMode::MspXor => kmer_minimizer::<false, _>(storage, pos, self.k, self.l, &*self.scores),
+        };
+        let (mint, mint_is_rc) = mint_of(lmer, self.l, self.canonical);
+        Some((mint, offset as u16, mint_is_rc))
+    }
+
+    pub fn storage(&self) -> &[u64] {
+        &self.storage
+    }
+}
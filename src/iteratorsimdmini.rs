@@ -535,6 +535,8 @@ impl SuperkmerExtractor {
         &self.superkmers
     }
 
+    crate::chunked::process_range_methods!();
+
     /// Access the 2-bit packed representation of the last processed sequence.
     pub fn storage(&self) -> &[u64] {
         &self.storage
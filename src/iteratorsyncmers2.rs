@@ -13,8 +13,8 @@
 //! for sk in iter { /* forward-strand mint */ }
 //! ```
 use crate::{Superkmer, SplitMode};
-use crate::minimizer_core::{canonical_table, minimizer_positions_deque, materialize_superkmers};
-use lazy_static::lazy_static;
+use crate::minimizer_core::{canonical_lmer, kmer_minimizer, minimizer_positions_deque, materialize_superkmers, mint_of, with_packed};
+use crate::tables::LazyTables;
 
 // Re-export for public API compatibility (bench_iterators uses these)
 pub use crate::minimizer_core::{get_kmer_value, get_base};
@@ -25,43 +25,33 @@ const S: usize = 2; //syncmer's s parameter
 /// (valid for l <= 7 where canon_val fits in 15 bits).
 pub type ScoreType = u32;
 
-lazy_static! {
-static ref SYNCMER_SCORES_8: Vec<ScoreType> = generate_syncmer_scores::<8>();
-static ref SYNCMER_SCORES_9: Vec<ScoreType> = generate_syncmer_scores::<9>();
-static ref MSP_SYNCMER_SCORES_8: Vec<ScoreType> = generate_msp_syncmer_scores::<8>();
-static ref MSP_SYNCMER_SCORES_9: Vec<ScoreType> = generate_msp_syncmer_scores::<9>();
-static ref MSPXOR_SYNCMER_SCORES_8: Vec<ScoreType> = generate_mspxor_syncmer_scores::<8>();
-static ref MSPXOR_SYNCMER_SCORES_9: Vec<ScoreType> = generate_mspxor_syncmer_scores::<9>();
-}
+static SYNCMER_SCORES: LazyTables<ScoreType> = LazyTables::new("syncmer", generate_syncmer_scores);
+static MSP_SYNCMER_SCORES: LazyTables<ScoreType> = LazyTables::new("syncmer-msp", generate_msp_syncmer_scores);
+static MSPXOR_SYNCMER_SCORES: LazyTables<ScoreType> = LazyTables::new("syncmer-mspxor", generate_mspxor_syncmer_scores);
 
 pub(crate) fn syncmer_scores(l: usize) -> &'static [ScoreType] {
     match l {
-        8 => &SYNCMER_SCORES_8[..],
-        9 => &SYNCMER_SCORES_9[..],
+        8 | 9 => SYNCMER_SCORES.get(l),
         _ => panic!("Unsupported l={} for syncmer scores", l),
     }
 }
 
 pub(crate) fn msp_syncmer_scores(l: usize) -> &'static [ScoreType] {
     match l {
-        8 => &MSP_SYNCMER_SCORES_8[..],
-        9 => &MSP_SYNCMER_SCORES_9[..],
+        8 | 9 => MSP_SYNCMER_SCORES.get(l),
         _ => panic!("Unsupported l={} for MSP syncmer scores", l),
     }
 }
 
 pub fn mspxor_syncmer_scores(l: usize) -> &'static [ScoreType] {
     match l {
-        8 => &MSPXOR_SYNCMER_SCORES_8[..],
-        9 => &MSPXOR_SYNCMER_SCORES_9[..],
+        8 | 9 => MSPXOR_SYNCMER_SCORES.get(l),
         _ => panic!("Unsupported l={} for MSP-xor syncmer scores", l),
     }
 }
 
-
-
-fn generate_syncmer_scores<const K: usize>() -> Vec<ScoreType> {
-    generate_syncmer_scores_with_s(K, S)
+fn generate_syncmer_scores(l: usize) -> Vec<ScoreType> {
+    generate_syncmer_scores_with_s(l, S)
 }
 
 /// Generate syncmer scores for arbitrary (l, s) parameters.
@@ -81,7 +71,7 @@ pub fn generate_syncmer_scores_with_s(l: usize, s: usize) -> Vec<ScoreType> {
             };
         }
         let syncmer = crate::syncmers::find_syncmers(l, s, &[0, l - s], None, &kmer_bytes);
-        scores[kmer_int] = syncmer.is_empty() as ScoreType;
+        scores[kmer_int] = (syncmer.is_empty() || crate::tables::is_palindrome(kmer_int, l)) as ScoreType;
     }
     scores[0] = 1; // Demote all-A l-mer
     scores[num_lmers - 1] = 1; // Demote all-T l-mer
@@ -107,41 +97,46 @@ pub fn compress_score(priority: ScoreType, tiebreaker: usize) -> ScoreType {
 /// Syncmers sort before non-syncmers; within each group, lower canonical value wins.
 /// Scores are unique per canonical l-mer, so ties only occur between forward/RC
 /// pairs (same bucket), making the sticky loop context-independent.
-fn generate_msp_syncmer_scores<const K: usize>() -> Vec<ScoreType> {
-    let base = generate_syncmer_scores::<K>();
-    let canon_table = canonical_table(K);
-    let mut scores = vec![0 as ScoreType; 1 << (2 * K)];
-    for fwd in 0..(1 << (2 * K)) {
-        let (canon_val, _) = canon_table[fwd];
-        scores[fwd] = compress_score(base[canon_val as usize], canon_val as usize);
-    }
-    scores
+fn generate_msp_syncmer_scores(l: usize) -> Vec<ScoreType> {
+    msp_from_base(syncmer_scores(l), l)
 }
 
 /// MSP-xor scores: composite (syncmer_priority, canonical_value ^ XOR_CONSTANT).
 /// Like MSP but XORs a constant to break A-rich lexicographic bias.
-fn generate_mspxor_syncmer_scores<const K: usize>() -> Vec<ScoreType> {
-    let base = generate_syncmer_scores::<K>();
-    let canon_table = canonical_table(K);
-    let mut scores = vec![0 as ScoreType; 1 << (2 * K)];
-    for fwd in 0..(1 << (2 * K)) {
-        let (canon_val, _) = canon_table[fwd];
-        scores[fwd] = compress_score(base[canon_val as usize], canon_val as usize ^ XOR_CONSTANT);
-    }
-    scores
+fn generate_mspxor_syncmer_scores(l: usize) -> Vec<ScoreType> {
+    mspxor_from_base(syncmer_scores(l), l)
 }
 
 /// Generate mspxor scores with arbitrary (l, s) parameters.
 pub fn generate_mspxor_syncmer_scores_with_s(l: usize, s: usize) -> Vec<ScoreType> {
-    let base = generate_syncmer_scores_with_s(l, s);
-    let canon_table = canonical_table(l);
-    let num_lmers = 1 << (2 * l);
-    let mut scores = vec![0 as ScoreType; num_lmers];
-    for fwd in 0..num_lmers {
-        let (canon_val, _) = canon_table[fwd];
-        scores[fwd] = compress_score(base[canon_val as usize], canon_val as usize ^ XOR_CONSTANT);
+    mspxor_from_base(&generate_syncmer_scores_with_s(l, s), l)
+}
+
+/// Scores that `mode` uses for closed syncmers with parameters (l, s), e.g. to save
+/// them with `scoretable::ScoreTable`.
+pub fn syncmer_mode_scores(l: usize, s: usize, mode: SplitMode) -> Vec<ScoreType> {
+    match (mode, s, l) {
+        (SplitMode::Sticky | SplitMode::Classical, 2, 8 | 9) => syncmer_scores(l).to_vec(),
+        (SplitMode::Msp, 2, 8 | 9) => msp_syncmer_scores(l).to_vec(),
+        (SplitMode::MspXor, 2, 8 | 9) => mspxor_syncmer_scores(l).to_vec(),
+        (SplitMode::Sticky | SplitMode::Classical, _, _) => generate_syncmer_scores_with_s(l, s),
+        (SplitMode::Msp, _, _) => msp_from_base(&generate_syncmer_scores_with_s(l, s), l),
+        (SplitMode::MspXor, _, _) => generate_mspxor_syncmer_scores_with_s(l, s),
     }
-    scores
+}
+
+fn msp_from_base(base: &[ScoreType], l: usize) -> Vec<ScoreType> {
+    (0..1usize << (2 * l)).map(|fwd| {
+        let (canon_val, _) = canonical_lmer(fwd, l);
+        compress_score(base[canon_val as usize], canon_val as usize)
+    }).collect()
+}
+
+fn mspxor_from_base(base: &[ScoreType], l: usize) -> Vec<ScoreType> {
+    (0..1usize << (2 * l)).map(|fwd| {
+        let (canon_val, _) = canonical_lmer(fwd, l);
+        compress_score(base[canon_val as usize], canon_val as usize ^ XOR_CONSTANT)
+    }).collect()
 }
 
 
@@ -200,6 +195,17 @@ fn msp_syncmer_positions_into(storage: &[u64], frag_len: usize, k: usize, l: usi
     }
 }
 
+/// Minimizer of the k-mer at `pos` as (offset in the k-mer, l-mer), for the
+/// context-independent modes of `msp_syncmer_positions_into`.
+fn msp_syncmer_kmer_minimizer(storage: &[u64], pos: usize, k: usize, l: usize, mode: SplitMode) -> Option<(usize, usize)> {
+    match mode {
+        SplitMode::Sticky => None,
+        SplitMode::Classical => Some(kmer_minimizer::<true, _>(storage, pos, k, l, syncmer_scores(l))),
+        SplitMode::Msp => Some(kmer_minimizer::<false, _>(storage, pos, k, l, msp_syncmer_scores(l))),
+        SplitMode::MspXor => Some(kmer_minimizer::<false, _>(storage, pos, k, l, mspxor_syncmer_scores(l))),
+    }
+}
+
 /// Sticky mode: single-pass with rescan on falloff. Rescans are rare because
 /// equal-score l-mers (all syncmers have score 0) never replace the current minimizer.
 #[inline(always)]
@@ -357,11 +363,7 @@ impl Iterator for SuperkmersIterator {
 
         self.p += 1;
 
-        let (mint, mint_is_rc) = if self.canonical {
-            canonical_table(self.l)[min_kmer]
-        } else {
-            (min_kmer as u32, false)
-        };
+        let (mint, mint_is_rc) = mint_of(min_kmer, self.l, self.canonical);
         Some(Superkmer {
             start: start_pos,
             mint,
@@ -455,9 +457,123 @@ impl SuperkmerExtractor {
         &self.superkmers
     }
 
+    crate::chunked::process_range_methods!();
+
+    /// Process an already 2-bit packed, N-free sequence of `len` bases
+    /// (MSB-first, as produced by `bitpack_fragment`). Skips packing entirely,
+    /// so `storage()` is left untouched. Returns the superkmers slice.
+    pub fn process_packed(&mut self, storage: &[u64], len: usize) -> &[Superkmer] {
+        self.superkmers.clear();
+        self.min_positions.clear();
+        msp_syncmer_positions_into(storage, len, self.k, self.l, 0, self.mode, &mut self.min_positions, &mut self.scores_buf, &mut self.deque);
+        materialize_superkmers(&self.min_positions, self.k, self.l, self.canonical, &mut self.superkmers);
+        &self.superkmers
+    }
+
+    /// Minimizer of a single k-mer (`k` ACGT bases) as (mint, mpos, mint_is_rc), `mpos`
+    /// being its offset in the k-mer. Agrees with the superkmer the extractor assigns to
+    /// that k-mer in any read: same mint and mint_is_rc, and the superkmer's
+    /// `start + mpos` is the k-mer position plus `mpos`. `None` for `SplitMode::Sticky`,
+    /// whose minimizer depends on the preceding k-mers.
+    pub fn minimizer_of(&self, kmer: &[u8]) -> Option<(u32, u16, bool)> {
+        assert_eq!(kmer.len(), self.k, "minimizer_of expects a k-mer");
+        with_packed(kmer, |storage| self.minimizer_of_packed(storage, 0))
+    }
+
+    /// `minimizer_of` for the k-mer at base `pos` of a 2-bit packed sequence.
+    pub fn minimizer_of_packed(&self, storage: &[u64], pos: usize) -> Option<(u32, u16, bool)> {
+        let (offset, lmer) = msp_syncmer_kmer_minimizer(storage, pos, self.k, self.l, self.mode)?;
+        let (mint, mint_is_rc) = mint_of(lmer, self.l, self.canonical);
+        Some((mint, offset as u16, mint_is_rc))
+    }
 
     /// Access the 2-bit packed representation of the last processed sequence.
     pub fn storage(&self) -> &[u64] {
         &self.storage
     }
 }
+
+/// Push-style extractor for sequences that arrive in pieces (nanopore streams,
+/// very long contigs read in buffers). `feed()` returns the superkmers that are
+/// complete so far; `finish()` flushes the rest. The concatenation of all returned
+/// slices equals `SuperkmerExtractor::process_with_n` on the concatenated input
+/// (and `process` when it has no N), for every `SplitMode`.
+///
+/// Between calls only the tail starting at the last, still-growing superkmer is
+/// kept (at most one superkmer plus k-1 bases). Every superkmer start is a point
+/// where all modes, Sticky included, pick their minimizer from the current window
+/// alone, so re-scanning from there reproduces the one-shot window state.
+pub struct StreamingExtractor {
+    inner: SuperkmerExtractor,
+    buf: Vec<u8>,
+    buf_offset: usize,
+    ready: Vec<Superkmer>,
+    k: usize,
+}
+
+impl StreamingExtractor {
+    extractor_constructors! {
+        new,                       true,  SplitMode::Sticky;
+        non_canonical,             false, SplitMode::Sticky;
+        classical,                 true,  SplitMode::Classical;
+        classical_non_canonical,   false, SplitMode::Classical;
+        msp,                       true,  SplitMode::Msp;
+        msp_non_canonical,         false, SplitMode::Msp;
+        mspxor,                    true,  SplitMode::MspXor;
+        mspxor_non_canonical,      false, SplitMode::MspXor;
+    }
+
+    fn new_inner_full(k: usize, l: usize, canonical: bool, mode: SplitMode) -> Self {
+        StreamingExtractor {
+            inner: SuperkmerExtractor::new_inner_full(k, l, canonical, mode),
+            buf: Vec::new(),
+            buf_offset: 0,
+            ready: Vec::new(),
+            k,
+        }
+    }
+
+    /// Append `data` to the stream. Returns the superkmers completed by this call,
+    /// with positions relative to the start of the stream.
+    pub fn feed(&mut self, data: &[u8]) -> &[Superkmer] {
+        self.ready.clear();
+        self.buf.extend_from_slice(data);
+        let superkmers = self.inner.process_with_n(&self.buf);
+        let keep_from = match superkmers.last() {
+            // The last superkmer may still grow unless it is followed by a k-mer
+            // containing N (then nothing in the buffer can extend it).
+            Some(last) if last.start + last.size as usize == self.buf.len() => {
+                self.ready.extend_from_slice(&superkmers[..superkmers.len() - 1]);
+                last.start
+            }
+            _ => {
+                self.ready.extend_from_slice(superkmers);
+                self.buf.len().saturating_sub(self.k - 1)
+            }
+        };
+        for sk in self.ready.iter_mut() {
+            sk.start += self.buf_offset;
+        }
+        self.buf.drain(..keep_from);
+        self.buf_offset += keep_from;
+        &self.ready
+    }
+
+    /// End the stream: returns the remaining superkmers and resets the extractor
+    /// so it can be reused for another stream.
+    pub fn finish(&mut self) -> &[Superkmer] {
+        self.ready.clear();
+        self.ready.extend_from_slice(self.inner.process_with_n(&self.buf));
+        for sk in self.ready.iter_mut() {
+            sk.start += self.buf_offset;
+        }
+        self.buf.clear();
+        self.buf_offset = 0;
+        &self.ready
+    }
+
+    /// Total number of bases fed since the stream started.
+    pub fn position(&self) -> usize {
+        self.buf_offset + self.buf.len()
+    }
+}
@@ -71,19 +71,19 @@ fn generate_syncmers<const K: usize>() -> Vec<bool> {
 
 fn score8(p: &Kmer8) -> usize {
     let kmer = p.to_u64() as usize;
-    if kmer == 0 || kmer == (1 << (2 * K8)) - 1 { return 1; } // Demote all-A and all-T l-mers
+    if kmer == 0 || kmer == (1 << (2 * K8)) - 1 || crate::tables::is_palindrome(kmer, K8) { return 1; } // Demote homopolymers and palindromes
     !SYNCMERS_8[kmer] as usize
 }
 
 fn score10(p: &Kmer10) -> usize {
     let kmer = p.to_u64() as usize;
-    if kmer == 0 || kmer == (1 << (2 * K10)) - 1 { return 1; } // Demote all-A and all-T l-mers
+    if kmer == 0 || kmer == (1 << (2 * K10)) - 1 || crate::tables::is_palindrome(kmer, K10) { return 1; } // Demote homopolymers and palindromes
     !SYNCMERS_10[kmer] as usize
 }
 
 fn score12(p: &Kmer12) -> usize {
     let kmer = p.to_u64() as usize;
-    if kmer == 0 || kmer == (1 << (2 * K12)) - 1 { return 1; } // Demote all-A and all-T l-mers
+    if kmer == 0 || kmer == (1 << (2 * K12)) - 1 || crate::tables::is_palindrome(kmer, K12) { return 1; } // Demote homopolymers and palindromes
     !SYNCMERS_12[kmer] as usize
 }
 
@@ -91,13 +91,7 @@ fn score12(p: &Kmer12) -> usize {
 #[inline]
 fn make_superkmer(msp_start: usize, msp_len: u16, msp_minimizer_pos: usize, mint_fwd: u64, l: usize, canonical: bool) -> Superkmer {
     let (mint, mint_is_rc) = if canonical {
-        let table = match l {
-            8 => &*crate::CANONICAL_8,
-            10 => &*crate::CANONICAL_10,
-            12 => &*crate::CANONICAL_12,
-            _ => panic!("Unsupported l={}", l),
-        };
-        table[mint_fwd as usize]
+        crate::tables::canonical_lmer(mint_fwd as usize, l)
     } else {
         (mint_fwd as u32, false)
     };
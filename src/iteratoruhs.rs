@@ -15,9 +15,9 @@
 //! for sk in iter { /* canonical mint */ }
 //! ```
 use crate::{Superkmer, SplitMode};
-use crate::minimizer_core::{canonical_table, minimizer_positions_deque, minimizer_positions_sticky, materialize_superkmers};
+use crate::minimizer_core::{canonical_lmer, kmer_minimizer, minimizer_positions_deque, minimizer_positions_sticky, materialize_superkmers, mint_of, with_packed};
 use crate::utils::bitpack_fragment;
-use lazy_static::lazy_static;
+use crate::tables::{revcomp_lmer, LazyTables};
 
 /// UHS ry patterns for l=7 (21 patterns, density 0.1640625, sparsity 6.095).
 /// Each pattern is l bits: r=0, y=1, MSB-first (leftmost letter = highest bit).
@@ -100,26 +100,12 @@ use crate::iteratorsyncmers2::{ScoreType, compress_score};
 /// for canonical minimizer selection.
 fn generate_uhs_scores(l: usize) -> Vec<ScoreType> {
     let num_lmers = 1 << (2 * l);
-    let canon_table = canonical_table(l);
     let mut scores = vec![1 as ScoreType; num_lmers];
 
     for lmer in 0..num_lmers {
         let ry_fwd = lmer_to_ry(lmer, l);
-        let (canon_val, is_rc) = canon_table[lmer];
-        // Get the RC l-mer integer
-        let rc_lmer = if is_rc { canon_val as usize } else {
-            // lmer is already canonical, find its RC
-            // RC is the other one: if canon == lmer, then RC is the complement
-            let mut rc = 0usize;
-            let mut v = lmer;
-            for _ in 0..l {
-                rc = (rc << 2) | (3 - (v & 3));
-                v >>= 2;
-            }
-            rc
-        };
-        let ry_rc = lmer_to_ry(rc_lmer, l);
-        if is_uhs_pattern(ry_fwd, l) || is_uhs_pattern(ry_rc, l) {
+        let ry_rc = lmer_to_ry(revcomp_lmer(lmer, l), l);
+        if (is_uhs_pattern(ry_fwd, l) || is_uhs_pattern(ry_rc, l)) && !crate::tables::is_palindrome(lmer, l) {
             scores[lmer] = 0;
         }
     }
@@ -133,49 +119,39 @@ fn generate_uhs_scores(l: usize) -> Vec<ScoreType> {
 /// Generate UHS scores with XOR tiebreaker for context-independent splitting.
 /// Pre-compressed composite: (uhs_priority, canonical_value ^ XOR_CONSTANT).
 fn generate_uhs_mspxor_scores(l: usize) -> Vec<ScoreType> {
-    let base = generate_uhs_scores(l);
-    let canon_table = canonical_table(l);
-    let num_lmers = 1 << (2 * l);
-    let mut scores = vec![0 as ScoreType; num_lmers];
+    let base = uhs_scores(l);
     const XOR_CONSTANT: usize = 0xACE5_ACE5;
-    for fwd in 0..num_lmers {
-        let (canon_val, _) = canon_table[fwd];
-        scores[fwd] = compress_score(base[canon_val as usize], canon_val as usize ^ XOR_CONSTANT);
-    }
-    scores
+    (0..1usize << (2 * l)).map(|fwd| {
+        let (canon_val, _) = canonical_lmer(fwd, l);
+        compress_score(base[canon_val as usize], canon_val as usize ^ XOR_CONSTANT)
+    }).collect()
 }
 
-lazy_static! {
-    static ref UHS_SCORES_7: Vec<ScoreType> = generate_uhs_scores(7);
-    static ref UHS_SCORES_8: Vec<ScoreType> = generate_uhs_scores(8);
-    static ref UHS_SCORES_9: Vec<ScoreType> = generate_uhs_scores(9);
-    static ref UHS_SCORES_11: Vec<ScoreType> = generate_uhs_scores(11);
-    static ref UHS_MSPXOR_SCORES_7: Vec<ScoreType> = generate_uhs_mspxor_scores(7);
-    static ref UHS_MSPXOR_SCORES_8: Vec<ScoreType> = generate_uhs_mspxor_scores(8);
-    static ref UHS_MSPXOR_SCORES_9: Vec<ScoreType> = generate_uhs_mspxor_scores(9);
-    static ref UHS_MSPXOR_SCORES_11: Vec<ScoreType> = generate_uhs_mspxor_scores(11);
-}
+static UHS_SCORES: LazyTables<ScoreType> = LazyTables::new("uhs", generate_uhs_scores);
+static UHS_MSPXOR_SCORES: LazyTables<ScoreType> = LazyTables::new("uhs-mspxor", generate_uhs_mspxor_scores);
 
 fn uhs_scores(l: usize) -> &'static [ScoreType] {
     match l {
-        7 => &UHS_SCORES_7[..],
-        8 => &UHS_SCORES_8[..],
-        9 => &UHS_SCORES_9[..],
-        11 => &UHS_SCORES_11[..],
+        7 | 8 | 9 | 11 => UHS_SCORES.get(l),
         _ => panic!("UHS not defined for l={}", l),
     }
 }
 
 pub fn uhs_mspxor_scores(l: usize) -> &'static [ScoreType] {
     match l {
-        7 => &UHS_MSPXOR_SCORES_7[..],
-        8 => &UHS_MSPXOR_SCORES_8[..],
-        9 => &UHS_MSPXOR_SCORES_9[..],
-        11 => &UHS_MSPXOR_SCORES_11[..],
+        7 | 8 | 9 | 11 => UHS_MSPXOR_SCORES.get(l),
         _ => panic!("UHS not defined for l={}", l),
     }
 }
 
+/// Scores that `mode` uses for UHS minimizers of length l.
+pub fn uhs_mode_scores(l: usize, mode: SplitMode) -> &'static [ScoreType] {
+    match mode {
+        SplitMode::MspXor => uhs_mspxor_scores(l),
+        SplitMode::Sticky | SplitMode::Classical | SplitMode::Msp => uhs_scores(l),
+    }
+}
+
 /// Sliding window using scores selected by mode.
 fn uhs_positions_into(
     storage: &[u64], frag_len: usize, k: usize, l: usize, offset: usize,
@@ -202,6 +178,17 @@ fn uhs_positions_into(
     }
 }
 
+/// Minimizer of the k-mer at `pos` as (offset in the k-mer, l-mer), for the
+/// context-independent modes of `uhs_positions_into`.
+fn uhs_kmer_minimizer(storage: &[u64], pos: usize, k: usize, l: usize, mode: SplitMode) -> Option<(usize, usize)> {
+    match mode {
+        SplitMode::Sticky => None,
+        SplitMode::Classical => Some(kmer_minimizer::<true, _>(storage, pos, k, l, uhs_scores(l))),
+        SplitMode::MspXor => Some(kmer_minimizer::<false, _>(storage, pos, k, l, uhs_mspxor_scores(l))),
+        SplitMode::Msp => Some(kmer_minimizer::<false, _>(storage, pos, k, l, uhs_scores(l))),
+    }
+}
+
 pub struct SuperkmersIterator {
     min_positions: Vec<(usize, usize, usize, usize)>,
     storage: Vec<u64>,
@@ -283,11 +270,7 @@ impl Iterator for SuperkmersIterator {
             frag_end - start_pos
         };
         self.p += 1;
-        let (mint, mint_is_rc) = if self.canonical {
-            canonical_table(self.l)[min_kmer]
-        } else {
-            (min_kmer as u32, false)
-        };
+        let (mint, mint_is_rc) = mint_of(min_kmer, self.l, self.canonical);
         Some(Superkmer {
             start: start_pos,
             mint,
@@ -370,6 +353,24 @@ impl SuperkmerExtractor {
         &self.superkmers
     }
 
+    crate::chunked::process_range_methods!();
+
+    /// Minimizer of a single k-mer (`k` ACGT bases) as (mint, mpos, mint_is_rc), `mpos`
+    /// being its offset in the k-mer. Agrees with the superkmer the extractor assigns to
+    /// that k-mer in any read (the superkmer's `start + mpos` is the k-mer position plus
+    /// `mpos`). `None` for sticky extractors, whose minimizer depends on the context.
+    pub fn minimizer_of(&self, kmer: &[u8]) -> Option<(u32, u16, bool)> {
+        assert_eq!(kmer.len(), self.k, "minimizer_of expects a k-mer");
+        with_packed(kmer, |storage| self.minimizer_of_packed(storage, 0))
+    }
+
+    /// `minimizer_of` for the k-mer at base `pos` of a 2-bit packed sequence.
+    pub fn minimizer_of_packed(&self, storage: &[u64], pos: usize) -> Option<(u32, u16, bool)> {
+        let (offset, lmer) = uhs_kmer_minimizer(storage, pos, self.k, self.l, self.mode)?;
+        let (mint, mint_is_rc) = mint_of(lmer, self.l, self.canonical);
+        Some((mint, offset as u16, mint_is_rc))
+    }
+
     pub fn storage(&self) -> &[u64] {
         &self.storage
     }
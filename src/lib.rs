@@ -1,16 +1,16 @@
-#![feature(generic_const_exprs)]
-#![feature(target_feature_inline_always)]
-#[allow(incomplete_features)]
+#![cfg_attr(feature = "nightly", feature(target_feature_inline_always))]
 pub mod utils;
 pub mod naive;
 pub mod iterator1;
 pub mod iteratormsp;
 pub mod syncmers;
+#[cfg(feature = "debruijn")]
 pub mod iteratorsyncmersmsp;
 pub mod minimizer_core;
 pub mod iteratorsyncmers2;
 pub mod iteratorkmc2;
 pub mod iteratoruhs;
+pub mod iteratorrandom;
 #[cfg(feature = "simd-mini")]
 pub mod iteratorsimdmini;
 #[cfg(feature = "simd-mini")]
@@ -18,11 +18,29 @@ pub mod iteratorsimdmini_cminim;
 #[cfg(feature = "multi-mini")]
 pub mod iteratormultiminimizers;
 pub mod naivesyncmers;
+#[cfg(feature = "nightly")]
 pub mod syncmers_simd_l8k40max;
+#[cfg(feature = "nightly")]
 pub mod syncmers_simd_l9k41max;
+#[cfg(feature = "nightly")]
 pub mod uhs_simd_l8k40max;
+pub mod chunked;
+pub mod compare;
+pub mod crossbucket;
+pub mod dedup;
+pub mod ffi;
+pub mod genome;
+pub mod graph;
+pub mod index;
+pub mod metrics;
+pub mod orientation;
+pub mod partition;
+pub mod sampling;
+pub mod scoretable;
+pub mod sketch;
+pub mod stats;
+pub mod tables;
 use std::cmp::Ordering;
-use lazy_static::lazy_static;
 
 /// Controls how superkmer boundaries are determined.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -39,28 +57,38 @@ pub enum SplitMode {
     MspXor,
 }
 
+/// Canonical (value, is_rc) of every K-mer.
+#[deprecated(since = "0.1.0", note = "use `tables::canonical_lmer`, or `tables::canonical_table` for a packed table")]
 pub fn generate_canonical_table<const K: usize>() -> Vec<(u32, bool)> {
-    let mut table = vec![(0u32, false); 1 << (2 * K)];
-    for fwd in 0..(1 << (2 * K)) {
-        let mut rc = 0usize;
-        let mut v = fwd;
-        for _ in 0..K {
-            rc = (rc << 2) | (3 - (v & 3));
-            v >>= 2;
-        }
-        table[fwd] = if rc < fwd { (rc as u32, true) } else { (fwd as u32, false) };
-    }
-    table
+    (0..1usize << (2 * K)).map(|fwd| tables::canonical_lmer(fwd, K)).collect()
 }
 
-lazy_static! {
-    pub static ref CANONICAL_7: Vec<(u32, bool)> = generate_canonical_table::<7>();
-    pub static ref CANONICAL_8: Vec<(u32, bool)> = generate_canonical_table::<8>();
-    pub static ref CANONICAL_9: Vec<(u32, bool)> = generate_canonical_table::<9>();
-    pub static ref CANONICAL_10: Vec<(u32, bool)> = generate_canonical_table::<10>();
-    pub static ref CANONICAL_11: Vec<(u32, bool)> = generate_canonical_table::<11>();
-    pub static ref CANONICAL_12: Vec<(u32, bool)> = generate_canonical_table::<12>();
+// The statics live in a module so the lazy_static expansion can allow its own uses.
+#[allow(deprecated)]
+mod legacy {
+    use lazy_static::lazy_static;
+
+    lazy_static! {
+        #[deprecated(since = "0.1.0", note = "use `tables::canonical_table(7)`")]
+        pub static ref CANONICAL_7: Vec<(u32, bool)> = legacy_canonical_table(7);
+        #[deprecated(since = "0.1.0", note = "use `tables::canonical_table(8)`")]
+        pub static ref CANONICAL_8: Vec<(u32, bool)> = legacy_canonical_table(8);
+        #[deprecated(since = "0.1.0", note = "use `tables::canonical_table(9)`")]
+        pub static ref CANONICAL_9: Vec<(u32, bool)> = legacy_canonical_table(9);
+        #[deprecated(since = "0.1.0", note = "use `tables::canonical_table(10)`")]
+        pub static ref CANONICAL_10: Vec<(u32, bool)> = legacy_canonical_table(10);
+        #[deprecated(since = "0.1.0", note = "use `tables::canonical_table(11)`")]
+        pub static ref CANONICAL_11: Vec<(u32, bool)> = legacy_canonical_table(11);
+        #[deprecated(since = "0.1.0", note = "use `tables::canonical_table(12)`")]
+        pub static ref CANONICAL_12: Vec<(u32, bool)> = legacy_canonical_table(12);
+    }
+
+    fn legacy_canonical_table(l: usize) -> Vec<(u32, bool)> {
+        (0..1usize << (2 * l)).map(|fwd| crate::tables::canonical_lmer(fwd, l)).collect()
+    }
 }
+#[allow(deprecated)]
+pub use legacy::{CANONICAL_7, CANONICAL_8, CANONICAL_9, CANONICAL_10, CANONICAL_11, CANONICAL_12};
 
 /// A superkmer: a maximal run of consecutive k-mers sharing the same minimizer.
 ///
@@ -71,7 +99,11 @@ lazy_static! {
 /// - `size` — length of the superkmer in bases (≥ k).
 /// - `mpos` — relative position of the minimizer within the superkmer (0-based offset from `start`).
 /// - `mint_is_rc` — `true` if the canonical minimizer is the reverse complement of the forward-strand l-mer
-///   at position `start + mpos`. Always `false` when using `.non_canonical()`.
+///   at position `start + mpos`. Always `false` when using `.non_canonical()`, and for
+///   reverse-complement palindromes (even l), which the syncmer and UHS schemes demote.
+///
+/// The layout is C-compatible, so the C API (`ffi`) hands out result arrays as they are.
+#[repr(C)]
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct Superkmer {
     pub start: usize,
@@ -0,0 +1,151 @@
+//! Empirical quality metrics of a superkmer scheme: minimizer density, superkmer
+//! lengths and context-dependent boundaries.
+//!
+//! A scheme is any function from a sequence to its superkmers, so every iterator of
+//! the crate can be measured the same way:
+//!
+//! ```
+//! use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+//! use rust_superkmers::metrics::random_metrics;
+//! let mut ext = SuperkmerExtractor::mspxor(31, 8);
+//! let m = random_metrics(20_000, 1, 31, |seq| ext.process_with_n(seq).to_vec());
+//! assert!(m.density() > 1.0 / 31.0 && m.density() < 0.2);
+//! assert_eq!(m.context_dependent_fraction(), 0.0);
+//! ```
+//!
+//! - Density: distinct minimizer positions (`start + mpos`) per k-mer.
+//! - Superkmer lengths in bases, as a histogram indexed by length.
+//! - A boundary between two adjacent superkmers is context-dependent when the scheme,
+//!   run on the k + 1 bases of the two k-mers around it alone, does not split them:
+//!   the split then depends on earlier k-mers (e.g. sticky tie-breaking).
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+use crate::Superkmer;
+use crate::utils::fasta::open_fasta;
+
+/// Metrics accumulated over one or more sequences.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct SchemeMetrics {
+    pub kmers: u64,
+    pub superkmers: u64,
+    pub minimizer_positions: u64,
+    /// Boundaries between superkmers of adjacent k-mers (N and sequence ends excluded).
+    pub boundaries: u64,
+    pub context_dependent_boundaries: u64,
+    /// Number of superkmers of each length in bases.
+    pub length_histogram: Vec<u64>,
+}
+
+impl SchemeMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Measure `scheme` on one sequence (may contain N).
+    pub fn add_sequence<F: FnMut(&[u8]) -> Vec<Superkmer>>(&mut self, seq: &[u8], k: usize, scheme: &mut F) {
+        if seq.len() < k {
+            return;
+        }
+        let mut sks = scheme(seq);
+        sks.sort_unstable();
+        let mut positions = HashSet::with_capacity(sks.len());
+        for sk in &sks {
+            let size = sk.size as usize;
+            self.kmers += (size - k + 1) as u64;
+            self.superkmers += 1;
+            positions.insert(sk.start + sk.mpos as usize);
+            if self.length_histogram.len() <= size {
+                self.length_histogram.resize(size + 1, 0);
+            }
+            self.length_histogram[size] += 1;
+        }
+        self.minimizer_positions += positions.len() as u64;
+
+        for pair in sks.windows(2) {
+            let last_kmer = pair[0].start + pair[0].size as usize - k;
+            if pair[1].start != last_kmer + 1 {
+                continue;
+            }
+            self.boundaries += 1;
+            if scheme(&seq[last_kmer..last_kmer + k + 1]).len() < 2 {
+                self.context_dependent_boundaries += 1;
+            }
+        }
+    }
+
+    /// Accumulate the metrics of another run.
+    pub fn merge(&mut self, other: &SchemeMetrics) {
+        self.kmers += other.kmers;
+        self.superkmers += other.superkmers;
+        self.minimizer_positions += other.minimizer_positions;
+        self.boundaries += other.boundaries;
+        self.context_dependent_boundaries += other.context_dependent_boundaries;
+        if self.length_histogram.len() < other.length_histogram.len() {
+            self.length_histogram.resize(other.length_histogram.len(), 0);
+        }
+        for (a, b) in self.length_histogram.iter_mut().zip(&other.length_histogram) {
+            *a += b;
+        }
+    }
+
+    /// Distinct minimizer positions per k-mer.
+    pub fn density(&self) -> f64 {
+        ratio(self.minimizer_positions, self.kmers)
+    }
+
+    /// Mean superkmer length in bases.
+    pub fn mean_superkmer_len(&self) -> f64 {
+        let bases: u64 = self.length_histogram.iter().enumerate().map(|(len, &n)| len as u64 * n).sum();
+        ratio(bases, self.superkmers)
+    }
+
+    /// Superkmer length at quantile `q` (0..1), 0 if there are none.
+    pub fn superkmer_len_quantile(&self, q: f64) -> usize {
+        let target = ((self.superkmers as f64 * q).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (len, &n) in self.length_histogram.iter().enumerate() {
+            seen += n;
+            if seen >= target {
+                return len;
+            }
+        }
+        0
+    }
+
+    /// Share of boundaries that are context-dependent.
+    pub fn context_dependent_fraction(&self) -> f64 {
+        ratio(self.context_dependent_boundaries, self.boundaries)
+    }
+}
+
+fn ratio(a: u64, b: u64) -> f64 {
+    if b == 0 { 0.0 } else { a as f64 / b as f64 }
+}
+
+/// Uniform random ACGT sequence from a fixed LCG, identical across platforms.
+pub fn random_sequence(len: usize, seed: u64) -> Vec<u8> {
+    let bases = [b'A', b'C', b'G', b'T'];
+    let mut x = seed;
+    (0..len).map(|_| {
+        x = x.wrapping_mul(6364136223846793005).wrapping_add(1);
+        bases[((x >> 33) % 4) as usize]
+    }).collect()
+}
+
+/// Metrics of `scheme` over a random sequence of length `len`.
+pub fn random_metrics<F: FnMut(&[u8]) -> Vec<Superkmer>>(len: usize, seed: u64, k: usize, mut scheme: F) -> SchemeMetrics {
+    let mut metrics = SchemeMetrics::new();
+    metrics.add_sequence(&random_sequence(len, seed), k, &mut scheme);
+    metrics
+}
+
+/// Metrics of `scheme` over every record of a FASTA file.
+pub fn fasta_metrics<P: AsRef<Path>, F: FnMut(&[u8]) -> Vec<Superkmer>>(path: P, k: usize, mut scheme: F) -> io::Result<SchemeMetrics> {
+    let mut metrics = SchemeMetrics::new();
+    for record in open_fasta(path)? {
+        metrics.add_sequence(&record?.seq, k, &mut scheme);
+    }
+    Ok(metrics)
+}
@@ -5,8 +5,11 @@
 //!
 //! Score tables are generic over the `Score` trait (implemented for u16, u32).
 //! Pre-compressed scores avoid per-lookup bit manipulation in the hot loop.
+//! The windows take any `ScoreSource`: a score table, or an order computed from the
+//! l-mer itself such as `Lexicographic`, which needs no table and works for any l.
 
 use crate::Superkmer;
+pub use crate::tables::canonical_lmer;
 
 /// Trait for score table entries. Scores are pre-compressed during table generation
 /// so the hot loop just widens to usize — no bit shifting needed.
@@ -27,6 +30,46 @@ impl Score for u32 {
     fn to_usize(self) -> usize { self as usize }
 }
 
+/// Where the sliding windows get l-mer scores from.
+pub trait ScoreSource {
+    type Score: Score;
+    fn score(&self, lmer: usize) -> Self::Score;
+
+    /// # Safety
+    /// `lmer` must be a valid l-mer for this source (below 4^l for tables).
+    #[inline(always)]
+    unsafe fn score_unchecked(&self, lmer: usize) -> Self::Score {
+        self.score(lmer)
+    }
+}
+
+impl<S: Score> ScoreSource for [S] {
+    type Score = S;
+    #[inline(always)]
+    fn score(&self, lmer: usize) -> S { self[lmer] }
+    #[inline(always)]
+    unsafe fn score_unchecked(&self, lmer: usize) -> S { *self.get_unchecked(lmer) }
+}
+
+/// Lexicographic order of the canonical (or, if not `canonical`, forward) l-mer,
+/// XORed with `xor`. The poly-A l-mer (value 0) is demoted after every other l-mer,
+/// as in rust-debruijn's MSP. Scores fit in 2l + 1 bits, so l goes up to `tables::MAX_L`.
+#[derive(Clone, Copy, Debug)]
+pub struct Lexicographic {
+    pub l: usize,
+    pub canonical: bool,
+    pub xor: u32,
+}
+
+impl ScoreSource for Lexicographic {
+    type Score = u32;
+    #[inline(always)]
+    fn score(&self, lmer: usize) -> u32 {
+        let val = if self.canonical { canonical_lmer(lmer, self.l).0 } else { lmer as u32 };
+        (((val == 0) as u32) << (2 * self.l)) | (val ^ self.xor)
+    }
+}
+
 /// Convert ASCII DNA base to 2-bit encoding: A=0, C=1, G=2, T=3.
 #[inline(always)]
 pub fn base_from_ascii(b: u8) -> usize {
@@ -81,14 +124,16 @@ pub fn get_kmer_value(data: &[u64], base_pos: usize, l: usize) -> usize {
 }
 
 /// Look up the canonical table for a given l-mer length.
+#[deprecated(since = "0.1.0", note = "use `tables::canonical_lmer`, or `tables::canonical_table` for a packed table")]
+#[allow(deprecated)]
 pub fn canonical_table(l: usize) -> &'static [(u32, bool)] {
     match l {
-        7 => &*crate::CANONICAL_7,
-        8 => &*crate::CANONICAL_8,
-        9 => &*crate::CANONICAL_9,
-        10 => &*crate::CANONICAL_10,
-        11 => &*crate::CANONICAL_11,
-        12 => &*crate::CANONICAL_12,
+        7 => &crate::CANONICAL_7,
+        8 => &crate::CANONICAL_8,
+        9 => &crate::CANONICAL_9,
+        10 => &crate::CANONICAL_10,
+        11 => &crate::CANONICAL_11,
+        12 => &crate::CANONICAL_12,
         _ => panic!("Unsupported l={} for canonical lookup", l),
     }
 }
@@ -118,6 +163,10 @@ fn unpack_pos<const CLASSICAL: bool>(packed: usize) -> usize {
     }
 }
 
+/// Output and scratch buffers of one `minimizer_positions_deque` pass, as
+/// (min_positions, scores_buf, deque), for callers that hand them on together.
+pub(crate) type WindowBuffers<'a> = (&'a mut Vec<(usize, usize, usize, usize)>, &'a mut Vec<usize>, &'a mut Vec<usize>);
+
 /// Block-decomposition sliding window minimum (no deque).
 ///
 /// Divides positions into blocks of size w. Precomputes prefix-min and suffix-min
@@ -130,9 +179,9 @@ fn unpack_pos<const CLASSICAL: bool>(packed: usize) -> usize {
 /// Emits (kmer_start, minimizer_pos, minimizer_kmer, fragment_end) tuples.
 /// All positions are absolute (offset already added).
 #[inline(always)]
-pub fn minimizer_positions_deque<const CLASSICAL: bool, S: Score>(
+pub fn minimizer_positions_deque<const CLASSICAL: bool, T: ScoreSource + ?Sized>(
     storage: &[u64], frag_len: usize, k: usize, l: usize, offset: usize,
-    scores: &[S], min_positions: &mut Vec<(usize, usize, usize, usize)>,
+    scores: &T, min_positions: &mut Vec<(usize, usize, usize, usize)>,
     scores_buf: &mut Vec<usize>, deque: &mut Vec<usize>,
 ) {
     let frag_end = offset + frag_len;
@@ -150,11 +199,11 @@ pub fn minimizer_positions_deque<const CLASSICAL: bool, S: Score>(
     // Pass 1: compute packed (score, position) values via rolling kmer
     unsafe {
         let mut rolling = get_kmer_value(storage, 0, l);
-        *scores_buf.get_unchecked_mut(0) = pack_score_pos::<CLASSICAL>((*scores.get_unchecked(rolling)).to_usize(), 0);
+        *scores_buf.get_unchecked_mut(0) = pack_score_pos::<CLASSICAL>(scores.score_unchecked(rolling).to_usize(), 0);
         for pos in 1..num_lmers {
             let new_base = get_base(storage, pos + l - 1);
             rolling = ((rolling << 2) | new_base) & mask;
-            *scores_buf.get_unchecked_mut(pos) = pack_score_pos::<CLASSICAL>((*scores.get_unchecked(rolling)).to_usize(), pos);
+            *scores_buf.get_unchecked_mut(pos) = pack_score_pos::<CLASSICAL>(scores.score_unchecked(rolling).to_usize(), pos);
         }
     }
 
@@ -212,14 +261,49 @@ pub fn minimizer_positions_deque<const CLASSICAL: bool, S: Score>(
     }
 }
 
+/// Minimizer of the single k-mer at base `pos` of `storage`, with the same ordering
+/// and tie-breaking as `minimizer_positions_deque::<CLASSICAL, _>`, so it agrees with
+/// the window minimum reported for that k-mer in any enclosing fragment.
+/// Returns (minimizer offset within the k-mer, minimizer l-mer value).
+pub fn kmer_minimizer<const CLASSICAL: bool, T: ScoreSource + ?Sized>(storage: &[u64], pos: usize, k: usize, l: usize, scores: &T) -> (usize, usize) {
+    let mask = (1usize << (l * 2)) - 1;
+    let mut rolling = get_kmer_value(storage, pos, l);
+    let mut best = pack_score_pos::<CLASSICAL>(scores.score(rolling).to_usize(), 0);
+    for i in 1..=k - l {
+        rolling = ((rolling << 2) | get_base(storage, pos + i + l - 1)) & mask;
+        best = best.min(pack_score_pos::<CLASSICAL>(scores.score(rolling).to_usize(), i));
+    }
+    let offset = unpack_pos::<CLASSICAL>(best);
+    (offset, get_kmer_value(storage, pos + offset, l))
+}
+
+/// Call `f` on the 2-bit packing of a short ASCII sequence (e.g. a single k-mer),
+/// packed on the stack when it fits in 256 bases.
+pub fn with_packed<R>(seq: &[u8], f: impl FnOnce(&[u64]) -> R) -> R {
+    let mut buf = [0u64; 8];
+    if seq.len() <= 32 * buf.len() {
+        let words = seq.len().div_ceil(32);
+        crate::utils::bitpack_fragment_into(seq, &mut buf[..words]);
+        f(&buf[..words])
+    } else {
+        f(&crate::utils::bitpack_fragment(seq))
+    }
+}
+
+/// (mint, mint_is_rc) of a minimizer l-mer, as `materialize_superkmers` reports it.
+#[inline(always)]
+pub fn mint_of(lmer: usize, l: usize, canonical: bool) -> (u32, bool) {
+    if canonical { canonical_lmer(lmer, l) } else { (lmer as u32, false) }
+}
+
 /// Sticky sliding window minimum: single-pass with rescan on falloff.
 /// On entry: new l-mer only replaces if strictly less (ties keep current).
 /// On falloff: rescan picks rightmost among ties (stays in window longest).
 /// This maximizes superkmer length for binary/low-cardinality score tables.
 #[inline(always)]
-pub fn minimizer_positions_sticky<S: Score>(
+pub fn minimizer_positions_sticky<T: ScoreSource + ?Sized>(
     storage: &[u64], frag_len: usize, k: usize, l: usize, offset: usize,
-    scores: &[S], min_positions: &mut Vec<(usize, usize, usize, usize)>,
+    scores: &T, min_positions: &mut Vec<(usize, usize, usize, usize)>,
 ) {
     let frag_end = offset + frag_len;
     if frag_len < k { return; }
@@ -228,13 +312,13 @@ pub fn minimizer_positions_sticky<S: Score>(
     let mask = (1usize << (l * 2)) - 1;
 
     // Find rightmost minimum in a range
-    let find_min = |start: usize, stop: usize| -> (S, usize, usize) {
-        let mut best_val = S::MAX;
+    let find_min = |start: usize, stop: usize| -> (T::Score, usize, usize) {
+        let mut best_val = T::Score::MAX;
         let mut best_pos = start;
         let mut best_kmer = 0;
         for pos in start..=stop {
             let kmer = get_kmer_value(storage, pos, l);
-            let val = scores[kmer];
+            let val = scores.score(kmer);
             if val <= best_val {  // <= means rightmost wins on tie
                 best_val = val;
                 best_pos = pos;
@@ -252,7 +336,7 @@ pub fn minimizer_positions_sticky<S: Score>(
     for i in 1..(frag_len - k + 1) {
         let new_base = get_base(storage, i + k - 1);
         rolling_kmer = ((rolling_kmer << 2) | new_base) & mask;
-        let end_val = scores[rolling_kmer];
+        let end_val = scores.score(rolling_kmer);
         let end_pos = i + w - 1;
 
         if i > min_pos {
@@ -274,7 +358,6 @@ pub fn minimizer_positions_sticky<S: Score>(
 
 /// Convert min_positions to superkmers.
 pub fn materialize_superkmers(min_positions: &[(usize, usize, usize, usize)], k: usize, l: usize, canonical: bool, out: &mut Vec<Superkmer>) {
-    let canon_table = if canonical { Some(canonical_table(l)) } else { None };
     for p in 0..min_positions.len() {
         let (start_pos, min_abs_pos, min_kmer, frag_end) = min_positions[p];
         let size = if p < min_positions.len() - 1 {
@@ -287,11 +370,7 @@ pub fn materialize_superkmers(min_positions: &[(usize, usize, usize, usize)], k:
         } else {
             frag_end - start_pos
         };
-        let (mint, mint_is_rc) = if let Some(table) = canon_table {
-            table[min_kmer]
-        } else {
-            (min_kmer as u32, false)
-        };
+        let (mint, mint_is_rc) = mint_of(min_kmer, l, canonical);
         out.push(Superkmer {
             start: start_pos,
             mint,
@@ -0,0 +1,78 @@
+//! Superkmers in minimizer-canonical orientation.
+//!
+//! A superkmer read on the reverse strand has the same k-mers as its reverse
+//! complement on the forward strand, but `start`/`mpos` and the raw bases differ.
+//! Re-orienting every superkmer so that its minimizer reads as the canonical
+//! l-mer (`mint`) makes the two copies identical, byte for byte, which is what
+//! bucket-level deduplication needs.
+//!
+//! - `mint_is_rc == false`: bases are packed as-is, `mpos` unchanged.
+//! - `mint_is_rc == true`: bases are packed reverse-complemented (via
+//!   `bitpack_fragment_rc_into`), `mpos` becomes `size - mpos - l`.
+//! - Palindromic minimizer (only possible for even l, `rc(m) == m`): the strand
+//!   is ambiguous, so the lexicographically smaller of the two packings is kept.
+//!
+//! If the minimizer l-mer occurs more than once in a superkmer, the two strands
+//! may select different occurrences and the results then differ in `mpos`/orientation.
+//!
+//! Only meaningful for superkmers from canonical extractors; with `.non_canonical()`
+//! every superkmer keeps its forward orientation.
+//!
+//! ```
+//! use rust_superkmers::orientation::canonical_superkmer;
+//! let seq = b"AACTGCACTGCACTGCACTGCACACTGCACTGCACTGCACTGCACACTGCACTGCACTGACTGCACTGCACTGCACTGCACTGCCTGC";
+//! let mut ext = rust_superkmers::iteratorsyncmers2::SuperkmerExtractor::mspxor(21, 8);
+//! for sk in ext.process(seq) {
+//!     let c = canonical_superkmer(seq, sk, 8);
+//!     assert_eq!(c.mint, sk.mint);
+//! }
+//! ```
+use crate::Superkmer;
+use crate::minimizer_core::get_kmer_value;
+use crate::utils::{bitpack_fragment_into, bitpack_fragment_rc_into};
+use crate::tables::is_palindrome;
+
+/// A superkmer detached from its read, in minimizer-canonical orientation.
+/// `bases` is 2-bit packed MSB-first (as `bitpack_fragment`), padding bits zero.
+/// Field order makes the derived `Ord` group by `mint` first.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct CanonicalSuperkmer {
+    pub mint: u32,
+    pub size: u16,
+    pub mpos: u16,
+    pub bases: Vec<u64>,
+}
+
+/// Pack the bases of `sk` (taken from `seq`) in minimizer-canonical orientation
+/// into `out`, which must hold at least `(sk.size + 31) / 32` words.
+/// Returns the minimizer position in that orientation.
+pub fn canonical_superkmer_into(seq: &[u8], sk: &Superkmer, l: usize, out: &mut [u64]) -> u16 {
+    let size = sk.size as usize;
+    let bases = &seq[sk.start..sk.start + size];
+    let num_words = size.div_ceil(32);
+    let rc_mpos = (size - sk.mpos as usize - l) as u16;
+
+    if sk.mint_is_rc {
+        bitpack_fragment_rc_into(bases, &mut out[..num_words]);
+        return rc_mpos;
+    }
+    bitpack_fragment_into(bases, &mut out[..num_words]);
+
+    // Palindromic minimizer: both strands are canonical, pick the smaller packing.
+    if is_palindrome(get_kmer_value(out, sk.mpos as usize, l), l) {
+        let mut rc = vec![0u64; num_words];
+        bitpack_fragment_rc_into(bases, &mut rc);
+        if rc[..] < out[..num_words] {
+            out[..num_words].copy_from_slice(&rc);
+            return rc_mpos;
+        }
+    }
+    sk.mpos
+}
+
+/// Superkmer `sk` of `seq` in minimizer-canonical orientation.
+pub fn canonical_superkmer(seq: &[u8], sk: &Superkmer, l: usize) -> CanonicalSuperkmer {
+    let mut bases = vec![0u64; (sk.size as usize).div_ceil(32)];
+    let mpos = canonical_superkmer_into(seq, sk, l, &mut bases);
+    CanonicalSuperkmer { mint: sk.mint, size: sk.size, mpos, bases }
+}
@@ -0,0 +1,163 @@
+//! Balanced assignment of minimizers to partitions, as KMC does for its bins.
+//!
+//! A first pass (or a sample of the input) counts k-mers per `mint`; `balance_partitions`
+//! then assigns minimizers to P partitions with greedy longest-processing-time
+//! scheduling: largest bucket first, each to the currently lightest partition.
+//! The resulting `PartitionMap` is saved and a second pass routes every superkmer
+//! with `partition_of(sk.mint)`. Only relative counts matter, so counts from a
+//! sample work as well as exact ones.
+//!
+//! Minimizers absent from the counts (unseen in the sample) fall back to
+//! `hash_partition`; they are expected to be small. Plain `mint % P` is not used:
+//! syncmer minimizer values are far from uniform in their low bits.
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use rust_superkmers::partition::balance_partitions;
+//! let counts: HashMap<u32, u64> = [(1, 50), (2, 30), (3, 20), (4, 10), (5, 10)].into_iter().collect();
+//! let map = balance_partitions(&counts, 2);
+//! assert_eq!(map.loads(), &[60, 60]);
+//! assert_ne!(map.partition_of(1), map.partition_of(2));
+//! ```
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::Superkmer;
+use crate::utils::io::{capped, invalid, read_u32, read_u64};
+
+const MAGIC: &[u8; 8] = b"SKPARTMP";
+const VERSION: u32 = 1;
+
+/// Hash-based partition of `mint`, used for minimizers without a recorded count.
+#[inline]
+pub fn hash_partition(mint: u32, num_partitions: usize) -> usize {
+    (((mint as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32) % num_partitions as u64) as usize
+}
+
+/// Minimizer -> partition assignment.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PartitionMap {
+    num_partitions: usize,
+    assignment: HashMap<u32, u32>,
+    loads: Vec<u64>,
+}
+
+/// Assign each minimizer of `counts` (mint -> k-mer count) to one of `num_partitions`
+/// partitions, greedily balancing the total count per partition.
+pub fn balance_partitions(counts: &HashMap<u32, u64>, num_partitions: usize) -> PartitionMap {
+    assert!(num_partitions >= 1 && num_partitions <= u32::MAX as usize, "invalid number of partitions");
+    let mut by_size: Vec<(u32, u64)> = counts.iter().map(|(&m, &c)| (m, c)).collect();
+    by_size.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut heap: BinaryHeap<Reverse<(u64, u32)>> = (0..num_partitions as u32).map(|p| Reverse((0, p))).collect();
+    let mut assignment = HashMap::with_capacity(by_size.len());
+    let mut loads = vec![0u64; num_partitions];
+    for (mint, count) in by_size {
+        let Reverse((load, p)) = heap.pop().unwrap();
+        assignment.insert(mint, p);
+        loads[p as usize] = load + count;
+        heap.push(Reverse((load + count, p)));
+    }
+    PartitionMap { num_partitions, assignment, loads }
+}
+
+impl PartitionMap {
+    pub fn num_partitions(&self) -> usize {
+        self.num_partitions
+    }
+
+    /// Partition of minimizer `mint`.
+    #[inline]
+    pub fn partition_of(&self, mint: u32) -> usize {
+        match self.assignment.get(&mint) {
+            Some(&p) => p as usize,
+            None => hash_partition(mint, self.num_partitions),
+        }
+    }
+
+    /// Predicted load (sum of input counts) of each partition.
+    pub fn loads(&self) -> &[u64] {
+        &self.loads
+    }
+
+    /// Largest predicted load over the mean load (1.0 is perfect balance).
+    pub fn imbalance(&self) -> f64 {
+        let total: u64 = self.loads.iter().sum();
+        if total == 0 {
+            return 1.0;
+        }
+        let max = *self.loads.iter().max().unwrap();
+        max as f64 * self.num_partitions as f64 / total as f64
+    }
+
+    /// Append each superkmer to the vector of its partition (`out.len()` must be P).
+    pub fn route(&self, sks: &[Superkmer], out: &mut [Vec<Superkmer>]) {
+        assert_eq!(out.len(), self.num_partitions);
+        for sk in sks {
+            out[self.partition_of(sk.mint)].push(*sk);
+        }
+    }
+
+    /// Serialize: magic, version u32, P u32, entry count u64, then (mint u32, partition u32)
+    /// sorted by mint, then P loads as u64. All little-endian.
+    pub fn write<W: Write>(&self, mut out: W) -> io::Result<()> {
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&(self.num_partitions as u32).to_le_bytes())?;
+        out.write_all(&(self.assignment.len() as u64).to_le_bytes())?;
+        let mut entries: Vec<(u32, u32)> = self.assignment.iter().map(|(&m, &p)| (m, p)).collect();
+        entries.sort_unstable();
+        for (mint, p) in entries {
+            out.write_all(&mint.to_le_bytes())?;
+            out.write_all(&p.to_le_bytes())?;
+        }
+        for load in &self.loads {
+            out.write_all(&load.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Deserialize a map written by `write`.
+    pub fn read<R: Read>(mut input: R) -> io::Result<PartitionMap> {
+        let mut magic = [0u8; 8];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a partition map file"));
+        }
+        if read_u32(&mut input)? != VERSION {
+            return Err(invalid("unsupported partition map version"));
+        }
+        let num_partitions = read_u32(&mut input)? as usize;
+        if num_partitions == 0 {
+            return Err(invalid("partition map has no partitions"));
+        }
+        let num_entries = read_u64(&mut input)? as usize;
+        let mut assignment = HashMap::with_capacity(capped(num_entries));
+        for _ in 0..num_entries {
+            let mint = read_u32(&mut input)?;
+            let p = read_u32(&mut input)?;
+            if p as usize >= num_partitions {
+                return Err(invalid("partition index out of range"));
+            }
+            assignment.insert(mint, p);
+        }
+        let mut loads = Vec::with_capacity(capped(num_partitions));
+        for _ in 0..num_partitions {
+            loads.push(read_u64(&mut input)?);
+        }
+        Ok(PartitionMap { num_partitions, assignment, loads })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write(&mut out)?;
+        out.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<PartitionMap> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
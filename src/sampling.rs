@@ -0,0 +1,217 @@
+//! Deterministic sampling for fast bucket statistics, with bootstrap confidence intervals.
+//!
+//! The input is cut into units: whole reads, or windows of long sequences overlapping
+//! by k-1 bases (`sample_windows`). A unit is kept when a seeded hash of (sequence index, window
+//! index) falls below `fraction`, so the same seed always selects the same units.
+//!
+//! Each kept unit contributes its own `mint` -> k-mer counts to a `BucketSample`.
+//! `BucketSample::estimate` computes P99 bucket size and Max/Mean over the sample,
+//! scaled to the full input, with 95% intervals from a bootstrap over units.
+//! Sampling noise alone makes the largest buckets look larger, so both metrics are
+//! extrapolated from the full sample and a half of it (see `extrapolated`).
+//! Minimizers rarer than about 1/`fraction` are missed by the sample, which biases
+//! the mean upward; the intervals only cover sampling noise, not that bias.
+//!
+//! ```
+//! use rust_superkmers::sampling::{sample_windows, BucketSample};
+//! use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+//! let seq = b"AACTGCACTGCACTGCACTGCACACTGCACTGCACTGCACTGCACACTGCACTGCACTGACTGCACTGCACTGCACTGCACTGCCTGC".repeat(50);
+//! let mut sample = BucketSample::new(0.5);
+//! let mut ext = SuperkmerExtractor::mspxor(21, 8);
+//! for (start, end) in sample_windows(0, seq.len(), 200, 21, 0.5, 42) {
+//!     sample.add_unit(ext.process(&seq[start..end]), 21);
+//! }
+//! let est = sample.estimate(20, 42);
+//! assert!(est.p99.low <= est.p99.estimate && est.p99.estimate <= est.p99.high);
+//! ```
+use std::collections::HashMap;
+
+use crate::Superkmer;
+
+/// Default window length for sampling long sequences.
+pub const DEFAULT_WINDOW_LEN: usize = 100_000;
+
+#[inline]
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Whether unit `window_idx` of sequence `seq_idx` is part of the sample.
+pub fn is_sampled(seq_idx: u64, window_idx: u64, fraction: f64, seed: u64) -> bool {
+    if fraction >= 1.0 {
+        return true;
+    }
+    let h = splitmix64(splitmix64(seed ^ splitmix64(seq_idx)) ^ window_idx);
+    (h as f64) < fraction * u64::MAX as f64
+}
+
+/// Sampled windows `(start, end)` of a sequence of length `seq_len`. Window `w` holds
+/// the k-mers starting in `[w * window_len, (w + 1) * window_len)`, so consecutive
+/// windows overlap by k-1 bases and every k-mer is in exactly one window. A sequence
+/// shorter than `window_len` is a single unit (a read); one shorter than k has none.
+pub fn sample_windows(seq_idx: u64, seq_len: usize, window_len: usize, k: usize, fraction: f64, seed: u64) -> Vec<(usize, usize)> {
+    let window_len = window_len.max(1);
+    let num_kmers = seq_len.saturating_sub(k - 1);
+    (0..num_kmers.div_ceil(window_len))
+        .filter(|&w| is_sampled(seq_idx, w as u64, fraction, seed))
+        .map(|w| (w * window_len, std::cmp::min((w + 1) * window_len + k - 1, seq_len)))
+        .collect()
+}
+
+/// A 95% confidence interval around a point estimate.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Interval {
+    pub estimate: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+/// Bucket-size metrics estimated from a sample, scaled to the full input.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SampleEstimate {
+    pub p99: Interval,
+    pub max_over_mean: Interval,
+    pub units: usize,
+}
+
+/// Per-unit bucket counts of a sample.
+pub struct BucketSample {
+    fraction: f64,
+    input_kmers: Option<u64>,
+    units: Vec<Vec<(u32, u64)>>,
+}
+
+/// P99 bucket size and Max/Mean (same definitions as `bucket_stats`) of bucket
+/// counts scaled by `scale`.
+fn metrics(counts: &HashMap<u32, u64>, scale: f64) -> (f64, f64) {
+    if counts.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mut sizes: Vec<u64> = counts.values().copied().collect();
+    sizes.sort_unstable();
+    let n = sizes.len();
+    let mean = sizes.iter().sum::<u64>() as f64 / n as f64;
+    (sizes[n * 99 / 100] as f64 * scale, sizes[n - 1] as f64 / mean)
+}
+
+/// Percentile `q` (0..1) of sorted values.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    sorted[((sorted.len() - 1) as f64 * q).round() as usize]
+}
+
+impl BucketSample {
+    /// Empty sample; `fraction` is the sampling rate the units were drawn with.
+    pub fn new(fraction: f64) -> Self {
+        assert!(fraction > 0.0 && fraction <= 1.0, "fraction must be in (0, 1]");
+        BucketSample { fraction, input_kmers: None, units: Vec::new() }
+    }
+
+    /// Number of k-mers in the whole input, when known (e.g. from sequence lengths).
+    /// Estimates are then scaled by the sampled share of k-mers rather than by
+    /// `fraction`, which removes the noise of how many units happened to be drawn.
+    pub fn set_input_kmers(&mut self, input_kmers: u64) {
+        self.input_kmers = Some(input_kmers);
+    }
+
+    /// Add the superkmers of one sampled unit.
+    pub fn add_unit(&mut self, sks: &[Superkmer], k: usize) {
+        let mut counts: HashMap<u32, u64> = HashMap::new();
+        for sk in sks {
+            *counts.entry(sk.mint).or_insert(0) += sk.size as u64 - k as u64 + 1;
+        }
+        self.add_unit_counts(counts);
+    }
+
+    /// Add one sampled unit given as `mint` -> k-mer counts.
+    pub fn add_unit_counts(&mut self, counts: HashMap<u32, u64>) {
+        self.units.push(counts.into_iter().collect());
+    }
+
+    pub fn num_units(&self) -> usize {
+        self.units.len()
+    }
+
+    /// Merged bucket counts of the sample (not scaled).
+    pub fn counts(&self) -> HashMap<u32, u64> {
+        self.merged(self.units.iter())
+    }
+
+    fn merged<'a>(&'a self, units: impl Iterator<Item = &'a Vec<(u32, u64)>>) -> HashMap<u32, u64> {
+        let mut merged = HashMap::new();
+        for unit in units {
+            for &(mint, c) in unit {
+                *merged.entry(mint).or_insert(0) += c;
+            }
+        }
+        merged
+    }
+
+    /// Metrics of the units `picks`, extrapolated to the full input.
+    ///
+    /// Sampling noise inflates the upper tail of bucket sizes, by an amount roughly
+    /// proportional to (1 - f) / f at sampling rate f. The metrics are computed on
+    /// all picked units and on a fixed half of them (rate f / 2), and the difference
+    /// is extrapolated back to f = 1.
+    fn extrapolated(&self, picks: &[usize], seed: u64) -> (f64, f64) {
+        let scaled = |counts: HashMap<u32, u64>, fraction: f64| {
+            let scale = match self.input_kmers {
+                Some(n) => n as f64 / counts.values().sum::<u64>().max(1) as f64,
+                None => 1.0 / fraction,
+            };
+            metrics(&counts, scale)
+        };
+        let f = self.fraction;
+        let full = scaled(self.merged(picks.iter().map(|&i| &self.units[i])), f);
+        if f >= 1.0 {
+            return full;
+        }
+        let half_picks = picks.iter().filter(|&&i| splitmix64(seed ^ i as u64) & 1 == 0);
+        let half = scaled(self.merged(half_picks.map(|&i| &self.units[i])), f / 2.0);
+        (full.0 - (1.0 - f) * (half.0 - full.0), full.1 - (1.0 - f) * (half.1 - full.1))
+    }
+
+    /// Point estimates with 95% bootstrap intervals from `replicates` resamplings of
+    /// the units, drawn deterministically from `seed`.
+    pub fn estimate(&self, replicates: usize, seed: u64) -> SampleEstimate {
+        let n = self.units.len();
+        let all: Vec<usize> = (0..n).collect();
+        let (p99, max_over_mean) = self.extrapolated(&all, seed);
+
+        let mut p99s = Vec::with_capacity(replicates);
+        let mut ratios = Vec::with_capacity(replicates);
+        let mut state = splitmix64(seed);
+        for _ in 0..replicates {
+            if n == 0 {
+                break;
+            }
+            let picks: Vec<usize> = (0..n).map(|_| {
+                state = splitmix64(state);
+                (state % n as u64) as usize
+            }).collect();
+            let (p, r) = self.extrapolated(&picks, seed);
+            p99s.push(p);
+            ratios.push(r);
+        }
+        p99s.sort_by(|a, b| a.total_cmp(b));
+        ratios.sort_by(|a, b| a.total_cmp(b));
+
+        // Basic bootstrap: replicates resample units with repetition and are noisier
+        // than the sample itself, so their spread is reflected around the estimate
+        let interval = |estimate: f64, boot: &[f64]| {
+            if boot.is_empty() {
+                return Interval { estimate, low: estimate, high: estimate };
+            }
+            let low = (2.0 * estimate - quantile(boot, 0.975)).min(estimate);
+            let high = (2.0 * estimate - quantile(boot, 0.025)).max(estimate);
+            Interval { estimate, low, high }
+        };
+        SampleEstimate {
+            p99: interval(p99, &p99s),
+            max_over_mean: interval(max_over_mean, &ratios),
+            units: n,
+        }
+    }
+}
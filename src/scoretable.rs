@@ -0,0 +1,382 @@
+//! Score tables as files: a versioned binary format for a minimizer order, and
+//! loaders for externally computed orders (ranked l-mer lists, dense rank arrays).
+//!
+//! A `ScoreTable` holds one `u32` score per l-mer (lower is better) with the split
+//! mode and canonical flag it is meant for. Built-in tables can be exported with
+//! `ScoreTable::syncmer`, `uhs`, `kmc2`, `random` and `lexicographic`; an order from
+//! another tool (e.g. a ranked list from GreedyMini or DOCKS) is loaded with
+//! `read_ranked_lmers` + `ScoreTable::from_ranks`. `TableExtractor` then cuts reads with any table.
+//!
+//! File layout (little-endian):
+//! ```text
+//! magic "SKSCORES" | version u32 | l u32 | s u32 | mode u8 | canonical u8 | reserved u16
+//! name_len u32 | name | num_scores u64 | scores u32 * num_scores
+//! checksum u64 (FNV-1a 64 of all preceding bytes)
+//! ```
+//!
+//! ```
+//! use rust_superkmers::scoretable::{ScoreTable, TableExtractor};
+//! use rust_superkmers::SplitMode;
+//! let table = ScoreTable::syncmer(8, 2, SplitMode::MspXor, true);
+//! let mut bytes = Vec::new();
+//! table.write(&mut bytes).unwrap();
+//! let loaded = ScoreTable::read(&mut &bytes[..]).unwrap();
+//! assert_eq!(loaded, table);
+//! let mut ext = TableExtractor::new(loaded, 21);
+//! assert!(!ext.process_with_n(b"ACGTACGTTGCAGGCATTACGGACTTAGCATNNACGT").is_empty());
+//! ```
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::minimizer_core::{materialize_superkmers, minimizer_positions_deque, minimizer_positions_sticky, ScoreSource, WindowBuffers};
+use crate::tables::{revcomp_lmer, MAX_L};
+use crate::utils::io::invalid;
+use crate::{SplitMode, Superkmer};
+
+const MAGIC: &[u8; 8] = b"SKSCORES";
+const VERSION: u32 = 1;
+
+/// A score per l-mer with the parameters it was built for.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ScoreTable {
+    /// Free-form scheme name ("syncmer", "uhs", "kmc2", "ranked", ...).
+    pub name: String,
+    pub l: usize,
+    /// Syncmer s-mer length, 0 when not applicable.
+    pub s: usize,
+    pub mode: SplitMode,
+    /// Whether mints are canonical (scores of an l-mer and its reverse complement agree).
+    pub canonical: bool,
+    pub scores: Vec<u32>,
+}
+
+fn mode_code(mode: SplitMode) -> u8 {
+    match mode {
+        SplitMode::Sticky => 0,
+        SplitMode::Classical => 1,
+        SplitMode::Msp => 2,
+        SplitMode::MspXor => 3,
+    }
+}
+
+fn mode_from_code(code: u8) -> io::Result<SplitMode> {
+    match code {
+        0 => Ok(SplitMode::Sticky),
+        1 => Ok(SplitMode::Classical),
+        2 => Ok(SplitMode::Msp),
+        3 => Ok(SplitMode::MspXor),
+        _ => Err(invalid("unknown split mode in score table")),
+    }
+}
+
+/// FNV-1a 64, fed incrementally.
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Writer that checksums what goes through it.
+struct Checksummed<W> {
+    inner: W,
+    hash: Fnv,
+}
+
+impl<W: Write> Checksummed<W> {
+    fn put(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.hash.update(bytes);
+        self.inner.write_all(bytes)
+    }
+}
+
+/// Reader that checksums what comes through it.
+struct Verified<R> {
+    inner: R,
+    hash: Fnv,
+}
+
+impl<R: Read> Verified<R> {
+    fn take(&mut self, n: usize) -> io::Result<Vec<u8>> {
+        let mut buf = vec![0u8; n];
+        self.inner.read_exact(&mut buf).map_err(|_| invalid("truncated score table"))?;
+        self.hash.update(&buf);
+        Ok(buf)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+impl ScoreTable {
+    pub fn new(name: &str, l: usize, s: usize, mode: SplitMode, canonical: bool, scores: Vec<u32>) -> Self {
+        assert!((1..=MAX_L).contains(&l), "score tables need 1 <= l <= {}", MAX_L);
+        assert_eq!(scores.len(), 1 << (2 * l), "a score table has one score per l-mer");
+        ScoreTable { name: name.to_string(), l, s, mode, canonical, scores }
+    }
+
+    /// The closed syncmer scores `iteratorsyncmers2` uses for `mode`.
+    pub fn syncmer(l: usize, s: usize, mode: SplitMode, canonical: bool) -> Self {
+        Self::new("syncmer", l, s, mode, canonical, crate::iteratorsyncmers2::syncmer_mode_scores(l, s, mode))
+    }
+
+    /// The UHS scores `iteratoruhs` uses for `mode`.
+    pub fn uhs(l: usize, mode: SplitMode, canonical: bool) -> Self {
+        Self::new("uhs", l, 0, mode, canonical, crate::iteratoruhs::uhs_mode_scores(l, mode).to_vec())
+    }
+
+    /// The random order `iteratorrandom` uses for `mode` and `seed`.
+    pub fn random(l: usize, mode: SplitMode, canonical: bool, seed: u64) -> Self {
+        Self::new("random", l, 0, mode, canonical, crate::iteratorrandom::random_mode_scores(l, mode, seed).into_owned())
+    }
+
+    /// The KMC2 signature scores `iteratorkmc2` uses for `mode`. l=7..12.
+    pub fn kmc2(l: usize, mode: SplitMode, canonical: bool) -> Self {
+        Self::new("kmc2", l, 0, mode, canonical, crate::iteratorkmc2::kmc2_mode_scores(l, mode).to_vec())
+    }
+
+    /// The lexicographic order `iteratormsp` computes for `mode`, tabulated.
+    pub fn lexicographic(l: usize, mode: SplitMode, canonical: bool) -> Self {
+        let order = crate::iteratormsp::lexicographic_order(l, mode);
+        Self::new("msp", l, 0, mode, canonical, (0..1usize << (2 * l)).map(|v| order.score(v)).collect())
+    }
+
+    /// Table from an external order: `ranks[v]` is the rank of l-mer `v` (lower is
+    /// better). With `canonical`, an l-mer and its reverse complement both get the
+    /// better of their two ranks.
+    pub fn from_ranks(name: &str, l: usize, mode: SplitMode, canonical: bool, ranks: &[u64]) -> io::Result<Self> {
+        if ranks.len() != 1 << (2 * l) {
+            return Err(invalid("rank array does not have one rank per l-mer"));
+        }
+        let rank = |v: usize| u32::try_from(ranks[v]).map_err(|_| invalid("rank does not fit in 32 bits"));
+        let scores = (0..ranks.len()).map(|v| {
+            if canonical { Ok(rank(v)?.min(rank(revcomp_lmer(v, l))?)) } else { rank(v) }
+        }).collect::<io::Result<Vec<u32>>>()?;
+        Ok(Self::new(name, l, 0, mode, canonical, scores))
+    }
+
+    /// Checksum of the serialized table.
+    pub fn checksum(&self) -> u64 {
+        let mut w = Checksummed { inner: io::sink(), hash: Fnv::new() };
+        self.write_body(&mut w).expect("writing to a sink cannot fail");
+        w.hash.0
+    }
+
+    fn write_body<W: Write>(&self, w: &mut Checksummed<W>) -> io::Result<()> {
+        w.put(MAGIC)?;
+        w.put(&VERSION.to_le_bytes())?;
+        w.put(&(self.l as u32).to_le_bytes())?;
+        w.put(&(self.s as u32).to_le_bytes())?;
+        w.put(&[mode_code(self.mode), self.canonical as u8, 0, 0])?;
+        w.put(&(self.name.len() as u32).to_le_bytes())?;
+        w.put(self.name.as_bytes())?;
+        w.put(&(self.scores.len() as u64).to_le_bytes())?;
+        for chunk in self.scores.chunks(4096) {
+            let bytes: Vec<u8> = chunk.iter().flat_map(|s| s.to_le_bytes()).collect();
+            w.put(&bytes)?;
+        }
+        Ok(())
+    }
+
+    pub fn write<W: Write>(&self, out: W) -> io::Result<()> {
+        let mut w = Checksummed { inner: out, hash: Fnv::new() };
+        self.write_body(&mut w)?;
+        let checksum = w.hash.0;
+        w.inner.write_all(&checksum.to_le_bytes())
+    }
+
+    /// Read a table written by `write`, checking version, sizes and checksum.
+    pub fn read<R: Read>(input: R) -> io::Result<Self> {
+        let mut r = Verified { inner: input, hash: Fnv::new() };
+        if r.take(8)? != MAGIC {
+            return Err(invalid("not a score table file"));
+        }
+        if r.u32()? != VERSION {
+            return Err(invalid("unsupported score table version"));
+        }
+        let l = r.u32()? as usize;
+        let s = r.u32()? as usize;
+        let flags = r.take(4)?;
+        let mode = mode_from_code(flags[0])?;
+        let canonical = flags[1] != 0;
+        let name_len = r.u32()? as usize;
+        if name_len > 4096 {
+            return Err(invalid("corrupt score table header"));
+        }
+        let name = String::from_utf8_lossy(&r.take(name_len)?).into_owned();
+        let num_scores = r.u64()?;
+        if !(1..=MAX_L).contains(&l) || num_scores != 1 << (2 * l) {
+            return Err(invalid("corrupt score table header"));
+        }
+        let bytes = r.take(4 * num_scores as usize)?;
+        let scores = bytes.chunks_exact(4).map(|b| u32::from_le_bytes(b.try_into().unwrap())).collect();
+        let expected = r.hash.0;
+        let mut checksum = [0u8; 8];
+        r.inner.read_exact(&mut checksum).map_err(|_| invalid("truncated score table"))?;
+        if u64::from_le_bytes(checksum) != expected {
+            return Err(invalid("score table checksum mismatch"));
+        }
+        Ok(ScoreTable { name, l, s, mode, canonical, scores })
+    }
+
+    /// One-shot superkmers of `seq` (may contain N/n); see `TableExtractor` to reuse buffers.
+    pub fn superkmers_with_n(&self, seq: &[u8], k: usize) -> Vec<Superkmer> {
+        assert!(k >= self.l, "k must be at least l");
+        let (mut min_positions, mut scores_buf, mut deque) = (Vec::new(), Vec::new(), Vec::new());
+        for (offset, fragment) in crate::utils::split_on_n(seq, k) {
+            let storage = crate::utils::bitpack_fragment(fragment);
+            table_positions_into(self, &storage, fragment.len(), k, offset, (&mut min_positions, &mut scores_buf, &mut deque));
+        }
+        let mut superkmers = Vec::new();
+        materialize_superkmers(&min_positions, k, self.l, self.canonical, &mut superkmers);
+        superkmers
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write(&mut out)?;
+        out.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
+
+/// Ranks from a ranked l-mer list: one l-mer per line, best first. Blank lines and
+/// lines starting with '#' are skipped, and only the first whitespace-separated field
+/// is read (so "ACGTACGT 0.25" lines are accepted). Unlisted l-mers share the rank
+/// after the last listed one; an l-mer listed twice keeps its first rank.
+/// Returns (l, ranks).
+pub fn read_ranked_lmers<R: BufRead>(reader: R) -> io::Result<(usize, Vec<u64>)> {
+    let mut listed: Vec<usize> = Vec::new();
+    let mut l = 0;
+    for line in reader.lines() {
+        let line = line?;
+        let Some(lmer) = line.split_whitespace().next().filter(|f| !f.starts_with('#')) else { continue };
+        if l == 0 {
+            l = lmer.len();
+            if !(1..=MAX_L).contains(&l) {
+                return Err(invalid(&format!("l-mers in a ranked list must have 1 to {} bases", MAX_L)));
+            }
+        }
+        if lmer.len() != l {
+            return Err(invalid("l-mers of different lengths in ranked list"));
+        }
+        let mut val = 0;
+        for b in lmer.bytes() {
+            let code = match b | 0x20 {
+                b'a' => 0,
+                b'c' => 1,
+                b'g' => 2,
+                b't' => 3,
+                _ => return Err(invalid("non-ACGT base in ranked list")),
+            };
+            val = (val << 2) | code;
+        }
+        listed.push(val);
+    }
+    if l == 0 {
+        return Err(invalid("empty ranked list"));
+    }
+    let unlisted = listed.len() as u64;
+    let mut ranks = vec![unlisted; 1 << (2 * l)];
+    for (rank, &val) in listed.iter().enumerate().rev() {
+        ranks[val] = rank as u64;
+    }
+    Ok((l, ranks))
+}
+
+/// Ranks from a dense array: one little-endian u64 rank per l-mer, in l-mer order.
+pub fn read_rank_array<R: Read>(mut reader: R, l: usize) -> io::Result<Vec<u64>> {
+    if !(1..=MAX_L).contains(&l) {
+        return Err(invalid(&format!("rank arrays need 1 <= l <= {}", MAX_L)));
+    }
+    let mut bytes = vec![0u8; 8 << (2 * l)];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes.chunks_exact(8).map(|b| u64::from_le_bytes(b.try_into().unwrap())).collect())
+}
+
+/// Sliding window over one fragment with the table's scores and split mode.
+fn table_positions_into(
+    table: &ScoreTable, storage: &[u64], frag_len: usize, k: usize, offset: usize,
+    (min_positions, scores_buf, deque): WindowBuffers,
+) {
+    let (l, scores) = (table.l, &table.scores[..]);
+    match table.mode {
+        SplitMode::Sticky => minimizer_positions_sticky(storage, frag_len, k, l, offset, scores, min_positions),
+        SplitMode::Classical => minimizer_positions_deque::<true, _>(storage, frag_len, k, l, offset, scores, min_positions, scores_buf, deque),
+        SplitMode::Msp | SplitMode::MspXor => minimizer_positions_deque::<false, _>(storage, frag_len, k, l, offset, scores, min_positions, scores_buf, deque),
+    }
+}
+
+/// Reusable superkmer extractor over any `ScoreTable`, following its split mode:
+/// Sticky keeps the current minimizer on ties, Classical takes the rightmost minimum,
+/// Msp and MspXor the leftmost.
+pub struct TableExtractor {
+    table: ScoreTable,
+    k: usize,
+    superkmers: Vec<Superkmer>,
+    min_positions: Vec<(usize, usize, usize, usize)>,
+    storage: Vec<u64>,
+    scores_buf: Vec<usize>,
+    deque: Vec<usize>,
+}
+
+impl TableExtractor {
+    pub fn new(table: ScoreTable, k: usize) -> Self {
+        assert!(k >= table.l, "k must be at least l");
+        TableExtractor {
+            table,
+            k,
+            superkmers: Vec::new(),
+            min_positions: Vec::new(),
+            storage: Vec::new(),
+            scores_buf: Vec::new(),
+            deque: Vec::new(),
+        }
+    }
+
+    pub fn table(&self) -> &ScoreTable {
+        &self.table
+    }
+
+    /// Process a sequence with no N characters.
+    pub fn process(&mut self, seq: &[u8]) -> &[Superkmer] {
+        self.min_positions.clear();
+        self.storage.resize(seq.len().div_ceil(32), 0);
+        crate::utils::bitpack_fragment_into(seq, &mut self.storage);
+        table_positions_into(&self.table, &self.storage, seq.len(), self.k, 0, (&mut self.min_positions, &mut self.scores_buf, &mut self.deque));
+        self.materialize()
+    }
+
+    /// Process a sequence that may contain N/n characters.
+    pub fn process_with_n(&mut self, seq: &[u8]) -> &[Superkmer] {
+        self.min_positions.clear();
+        for (offset, fragment) in crate::utils::split_on_n(seq, self.k) {
+            self.storage.resize(fragment.len().div_ceil(32), 0);
+            crate::utils::bitpack_fragment_into(fragment, &mut self.storage);
+            table_positions_into(&self.table, &self.storage, fragment.len(), self.k, offset, (&mut self.min_positions, &mut self.scores_buf, &mut self.deque));
+        }
+        self.materialize()
+    }
+
+    fn materialize(&mut self) -> &[Superkmer] {
+        self.superkmers.clear();
+        materialize_superkmers(&self.min_positions, self.k, self.table.l, self.table.canonical, &mut self.superkmers);
+        &self.superkmers
+    }
+}
@@ -0,0 +1,270 @@
+//! FracMinHash sketches of canonical k-mers or of distinct minimizers.
+//!
+//! A sketch keeps every item whose 64-bit hash falls below `u64::MAX / scaled`,
+//! so about 1/`scaled` of the distinct items, and the same items in every sketch
+//! built with the same parameters. Jaccard and containment between two sketches
+//! are then estimated on the kept hashes (after downsampling both to the larger
+//! `scaled`). Hashing follows `syncmers::find_syncmers` downsampling: `XxHash64`
+//! with a fixed seed.
+//!
+//! - `SketchKind::Kmers`: canonical k-mers (k <= 63), hashed from their 2-bit encoding.
+//! - `SketchKind::Minimizers`: distinct `mint` values of canonical MspXor superkmers,
+//!   far fewer items per sequence and cheaper to compute. There are only 4^l
+//!   possible values, so unrelated inputs share some by chance: a coarse measure.
+//!
+//! Sketches are stored in a small little-endian binary format (`write`/`read`).
+//!
+//! ```
+//! use rust_superkmers::sketch::{Sketch, SketchKind};
+//! let seq = b"AACTGCACTGCACTGCACTGCACACTGCACTGCACTGCACTGCACACTGCACTGCACTGACTGCACTGCACTGCACTGCACTGCCTGC";
+//! let mut a = Sketch::new(SketchKind::Kmers, 21, 8, 1);
+//! a.add_sequence(seq);
+//! let mut b = Sketch::new(SketchKind::Kmers, 21, 8, 1);
+//! b.add_sequence(&seq[..60]);
+//! assert_eq!(b.containment(&a), 1.0);
+//! assert!(a.jaccard(&b) < 1.0);
+//! ```
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use twox_hash::XxHash64;
+
+use crate::iteratorsyncmers2::SuperkmerExtractor;
+use crate::utils::fasta::open_fasta;
+use crate::utils::io::{invalid, read_bytes, read_u32, read_u64};
+
+const MAGIC: &[u8; 8] = b"SKSKETCH";
+const VERSION: u32 = 1;
+const SEED: u64 = 42;
+
+/// What a sketch samples.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SketchKind {
+    Kmers,
+    Minimizers,
+}
+
+/// A FracMinHash sketch: sorted hashes of the kept items.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Sketch {
+    pub name: String,
+    pub kind: SketchKind,
+    pub k: usize,
+    pub l: usize,
+    pub scaled: u64,
+    hashes: BTreeSet<u64>,
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = XxHash64::with_seed(SEED);
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+fn max_hash(scaled: u64) -> u64 {
+    u64::MAX / scaled
+}
+
+impl Sketch {
+    /// Empty sketch keeping about 1/`scaled` of the items. `l` is the minimizer
+    /// length for `Minimizers`; it is recorded but unused for `Kmers`.
+    pub fn new(kind: SketchKind, k: usize, l: usize, scaled: u64) -> Self {
+        assert!(scaled >= 1, "scaled must be at least 1");
+        if kind == SketchKind::Kmers {
+            assert!((1..=63).contains(&k), "k-mer sketches need 1 <= k <= 63");
+        }
+        Sketch { name: String::new(), kind, k, l, scaled, hashes: BTreeSet::new() }
+    }
+
+    /// Sketch all records of a FASTA file, named after the file.
+    pub fn from_fasta<P: AsRef<Path>>(path: P, kind: SketchKind, k: usize, l: usize, scaled: u64) -> io::Result<Self> {
+        let mut sketch = Self::new(kind, k, l, scaled);
+        sketch.name = path.as_ref().display().to_string();
+        let records = open_fasta(path)?;
+        let mut extractor = (kind == SketchKind::Minimizers).then(|| SuperkmerExtractor::mspxor(k, l));
+        for record in records {
+            sketch.add_with(extractor.as_mut(), &record?.seq);
+        }
+        Ok(sketch)
+    }
+
+    /// Add the items of one sequence (may contain N).
+    pub fn add_sequence(&mut self, seq: &[u8]) {
+        let mut extractor = (self.kind == SketchKind::Minimizers).then(|| SuperkmerExtractor::mspxor(self.k, self.l));
+        self.add_with(extractor.as_mut(), seq);
+    }
+
+    /// `extractor` is only built, and only used, for `Minimizers`.
+    fn add_with(&mut self, extractor: Option<&mut SuperkmerExtractor>, seq: &[u8]) {
+        let threshold = max_hash(self.scaled);
+        match extractor {
+            None => self.add_kmers(seq, threshold),
+            Some(extractor) => {
+                for sk in extractor.process_with_n(seq) {
+                    let h = hash_bytes(&sk.mint.to_le_bytes());
+                    if h <= threshold {
+                        self.hashes.insert(h);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_kmers(&mut self, seq: &[u8], threshold: u64) {
+        let k = self.k;
+        let mask: u128 = (1 << (2 * k)) - 1;
+        let (mut fwd, mut rc, mut valid) = (0u128, 0u128, 0usize);
+        for &b in seq {
+            let code = match b | 0x20 {
+                b'a' => 0,
+                b'c' => 1,
+                b'g' => 2,
+                b't' => 3,
+                _ => {
+                    valid = 0;
+                    continue;
+                }
+            };
+            fwd = ((fwd << 2) | code) & mask;
+            rc = (rc >> 2) | ((3 - code) << (2 * (k - 1)));
+            valid += 1;
+            if valid >= k {
+                let h = hash_bytes(&fwd.min(rc).to_le_bytes());
+                if h <= threshold {
+                    self.hashes.insert(h);
+                }
+            }
+        }
+    }
+
+    /// Kept hashes, sorted.
+    pub fn hashes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.hashes.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Estimated number of distinct items in the sketched input.
+    pub fn cardinality(&self) -> f64 {
+        self.hashes.len() as f64 * self.scaled as f64
+    }
+
+    /// Copy of this sketch keeping only hashes valid at a coarser `scaled`.
+    pub fn downsample(&self, scaled: u64) -> Sketch {
+        assert!(scaled >= self.scaled, "cannot downsample from scaled={} to {}", self.scaled, scaled);
+        let threshold = max_hash(scaled);
+        let hashes = self.hashes.range(..=threshold).copied().collect();
+        Sketch { name: self.name.clone(), kind: self.kind, k: self.k, l: self.l, scaled, hashes }
+    }
+
+    /// Sizes of self, other and their intersection, at the coarser of both `scaled`.
+    fn overlap(&self, other: &Sketch) -> (usize, usize, usize) {
+        assert!(
+            self.kind == other.kind && self.k == other.k && (self.kind == SketchKind::Kmers || self.l == other.l),
+            "incompatible sketches"
+        );
+        let threshold = max_hash(self.scaled.max(other.scaled));
+        let a: Vec<u64> = self.hashes.range(..=threshold).copied().collect();
+        let b: Vec<u64> = other.hashes.range(..=threshold).copied().collect();
+        let (mut i, mut j, mut common) = (0, 0, 0);
+        while i < a.len() && j < b.len() {
+            match a[i].cmp(&b[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    common += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        (a.len(), b.len(), common)
+    }
+
+    /// Estimated Jaccard similarity |A ∩ B| / |A ∪ B|.
+    pub fn jaccard(&self, other: &Sketch) -> f64 {
+        let (a, b, common) = self.overlap(other);
+        let union = a + b - common;
+        if union == 0 { 0.0 } else { common as f64 / union as f64 }
+    }
+
+    /// Estimated fraction of this sketch's items also in `other`, |A ∩ B| / |A|.
+    pub fn containment(&self, other: &Sketch) -> f64 {
+        let (a, _, common) = self.overlap(other);
+        if a == 0 { 0.0 } else { common as f64 / a as f64 }
+    }
+
+    /// Serialize: magic, version u32, kind u8, k u32, l u32, scaled u64, name
+    /// (u32 length + UTF-8), hash count u64, then the sorted hashes as u64. All little-endian.
+    pub fn write<W: Write>(&self, mut out: W) -> io::Result<()> {
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&[match self.kind { SketchKind::Kmers => 0u8, SketchKind::Minimizers => 1 }])?;
+        out.write_all(&(self.k as u32).to_le_bytes())?;
+        out.write_all(&(self.l as u32).to_le_bytes())?;
+        out.write_all(&self.scaled.to_le_bytes())?;
+        out.write_all(&(self.name.len() as u32).to_le_bytes())?;
+        out.write_all(self.name.as_bytes())?;
+        out.write_all(&(self.hashes.len() as u64).to_le_bytes())?;
+        for h in &self.hashes {
+            out.write_all(&h.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Deserialize a sketch written by `write`.
+    pub fn read<R: Read>(mut input: R) -> io::Result<Sketch> {
+        let mut magic = [0u8; 8];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a sketch file"));
+        }
+        if read_u32(&mut input)? != VERSION {
+            return Err(invalid("unsupported sketch version"));
+        }
+        let mut kind = [0u8; 1];
+        input.read_exact(&mut kind)?;
+        let kind = match kind[0] {
+            0 => SketchKind::Kmers,
+            1 => SketchKind::Minimizers,
+            _ => return Err(invalid("unknown sketch kind")),
+        };
+        let k = read_u32(&mut input)? as usize;
+        if kind == SketchKind::Kmers && !(1..=63).contains(&k) {
+            return Err(invalid("k-mer sketches need 1 <= k <= 63"));
+        }
+        let l = read_u32(&mut input)? as usize;
+        let scaled = read_u64(&mut input)?;
+        if scaled == 0 {
+            return Err(invalid("scaled must be at least 1"));
+        }
+        let name_len = read_u32(&mut input)? as usize;
+        let name = read_bytes(&mut input, name_len)?;
+        let name = String::from_utf8(name).map_err(|_| invalid("sketch name is not UTF-8"))?;
+        let count = read_u64(&mut input)?;
+        let mut hashes = BTreeSet::new();
+        for _ in 0..count {
+            hashes.insert(read_u64(&mut input)?);
+        }
+        Ok(Sketch { name, kind, k, l, scaled, hashes })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write(&mut out)?;
+        out.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Sketch> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
@@ -0,0 +1,233 @@
+//! Bucket-size statistics (k-mers per minimizer), as printed by `bucket_stats`,
+//! with machine-readable JSON and TSV output for tracking schemes over time.
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use rust_superkmers::stats::BucketStats;
+//! let counts: HashMap<u32, u64> = [(0, 10), (1, 30), (2, 20)].into_iter().collect();
+//! let stats = BucketStats::from_counts(&counts, 60, 12, 31, 8, 2);
+//! assert_eq!(stats.max, 30);
+//! assert_eq!(stats.top, vec![(1, 30), (2, 20)]);
+//! assert!(stats.to_json().starts_with("{\"label\":\"\",\"k\":31,"));
+//! ```
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Buckets with sizes in `[lo, hi]` (powers of two).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HistogramBin {
+    pub lo: u64,
+    pub hi: u64,
+    pub minimizers: u64,
+    pub kmers: u64,
+}
+
+/// Distribution of bucket sizes of one run.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BucketStats {
+    /// Free-form name of the scheme (e.g. `syncmer:mspxor`), copied to the outputs.
+    pub label: String,
+    pub k: usize,
+    pub l: usize,
+    pub distinct_minimizers: usize,
+    pub total_kmers: u64,
+    pub total_superkmers: u64,
+    pub min: u64,
+    pub p1: u64,
+    pub p5: u64,
+    pub p25: u64,
+    pub median: u64,
+    pub mean: f64,
+    pub p75: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub max: u64,
+    /// Log2 histogram, by increasing size.
+    pub histogram: Vec<HistogramBin>,
+    /// Largest buckets as (mint, k-mers), largest first.
+    pub top: Vec<(u32, u64)>,
+}
+
+/// Column names of `BucketStats::to_tsv`.
+const TSV_COLUMNS: [&str; 22] = [
+    "label", "k", "l", "distinct_minimizers", "total_kmers", "total_superkmers",
+    "min", "p1", "p5", "p25", "median", "mean", "p75", "p95", "p99", "max",
+    "max_over_mean", "max_over_median", "density", "mean_superkmer_len", "top_mint", "top_kmers",
+];
+
+impl BucketStats {
+    /// Statistics of `bucket_counts` (mint -> k-mers), keeping the `top_n` largest buckets.
+    pub fn from_counts(bucket_counts: &HashMap<u32, u64>, total_kmers: u64, total_superkmers: u64, k: usize, l: usize, top_n: usize) -> Self {
+        let mut sizes: Vec<u64> = bucket_counts.values().copied().collect();
+        sizes.sort_unstable();
+        let n = sizes.len();
+        let at = |pct: usize| if n == 0 { 0 } else { sizes[n * pct / 100] };
+
+        let mut histogram: Vec<HistogramBin> = Vec::new();
+        for &size in &sizes {
+            let bin = 64 - size.leading_zeros();
+            let lo = if bin == 0 { 0 } else { 1u64 << (bin - 1) };
+            if histogram.last().is_none_or(|b| b.lo != lo) {
+                histogram.push(HistogramBin { lo, hi: (1u64 << bin) - 1, minimizers: 0, kmers: 0 });
+            }
+            let b = histogram.last_mut().unwrap();
+            b.minimizers += 1;
+            b.kmers += size;
+        }
+
+        let mut top: Vec<(u32, u64)> = bucket_counts.iter().map(|(&m, &c)| (m, c)).collect();
+        top.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        top.truncate(top_n);
+
+        BucketStats {
+            label: String::new(),
+            k,
+            l,
+            distinct_minimizers: n,
+            total_kmers,
+            total_superkmers,
+            min: at(0),
+            p1: at(1),
+            p5: at(5),
+            p25: at(25),
+            median: at(50),
+            mean: if n == 0 { 0.0 } else { sizes.iter().sum::<u64>() as f64 / n as f64 },
+            p75: at(75),
+            p95: at(95),
+            p99: at(99),
+            max: sizes.last().copied().unwrap_or(0),
+            histogram,
+            top,
+        }
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.distinct_minimizers == 0
+    }
+
+    pub fn max_over_mean(&self) -> f64 {
+        ratio(self.max as f64, self.mean)
+    }
+
+    pub fn max_over_median(&self) -> f64 {
+        ratio(self.max as f64, self.median as f64)
+    }
+
+    /// Superkmers per k-mer.
+    pub fn density(&self) -> f64 {
+        ratio(self.total_superkmers as f64, self.total_kmers as f64)
+    }
+
+    /// Mean superkmer length in bases.
+    pub fn mean_superkmer_len(&self) -> f64 {
+        if self.total_superkmers == 0 {
+            return 0.0;
+        }
+        self.total_kmers as f64 / self.total_superkmers as f64 + self.k as f64 - 1.0
+    }
+
+    /// Share of all k-mers in histogram bin `bin`.
+    pub fn kmer_fraction(&self, bin: &HistogramBin) -> f64 {
+        ratio(bin.kmers as f64, self.total_kmers as f64)
+    }
+
+    /// One-line JSON object with every field and the derived ratios.
+    pub fn to_json(&self) -> String {
+        let mut s = String::from("{");
+        write!(s, "\"label\":\"{}\",\"k\":{},\"l\":{}", json_escape(&self.label), self.k, self.l).unwrap();
+        write!(s, ",\"distinct_minimizers\":{},\"total_kmers\":{},\"total_superkmers\":{}",
+            self.distinct_minimizers, self.total_kmers, self.total_superkmers).unwrap();
+        for (name, value) in self.percentiles() {
+            write!(s, ",\"{}\":{}", name, value).unwrap();
+        }
+        write!(s, ",\"mean\":{},\"max_over_mean\":{},\"max_over_median\":{},\"density\":{},\"mean_superkmer_len\":{}",
+            json_f64(self.mean), json_f64(self.max_over_mean()), json_f64(self.max_over_median()),
+            json_f64(self.density()), json_f64(self.mean_superkmer_len())).unwrap();
+        s.push_str(",\"histogram\":[");
+        for (i, b) in self.histogram.iter().enumerate() {
+            if i > 0 {
+                s.push(',');
+            }
+            write!(s, "{{\"lo\":{},\"hi\":{},\"minimizers\":{},\"kmers\":{},\"kmer_fraction\":{}}}",
+                b.lo, b.hi, b.minimizers, b.kmers, json_f64(self.kmer_fraction(b))).unwrap();
+        }
+        s.push_str("],\"top\":[");
+        for (i, &(mint, kmers)) in self.top.iter().enumerate() {
+            if i > 0 {
+                s.push(',');
+            }
+            write!(s, "{{\"mint\":{},\"minimizer\":\"{}\",\"kmers\":{}}}", mint, decode_mint(mint, self.l), kmers).unwrap();
+        }
+        s.push_str("]}");
+        s
+    }
+
+    /// Header line for `to_tsv`.
+    pub fn tsv_header() -> String {
+        TSV_COLUMNS.join("\t")
+    }
+
+    /// One TSV row of the scalar fields (the histogram is JSON-only; only the
+    /// largest bucket is kept). Rows of several runs share `tsv_header`.
+    pub fn to_tsv(&self) -> String {
+        let (top_mint, top_kmers) = self.top.first().map_or((String::new(), String::new()), |&(m, c)| (m.to_string(), c.to_string()));
+        let mut fields = vec![
+            self.label.replace(['\t', '\n'], " "),
+            self.k.to_string(),
+            self.l.to_string(),
+            self.distinct_minimizers.to_string(),
+            self.total_kmers.to_string(),
+            self.total_superkmers.to_string(),
+        ];
+        fields.extend(self.percentiles()[..5].iter().map(|(_, v)| v.to_string()));
+        fields.push(format!("{:.3}", self.mean));
+        fields.extend(self.percentiles()[5..].iter().map(|(_, v)| v.to_string()));
+        fields.push(format!("{:.3}", self.max_over_mean()));
+        fields.push(format!("{:.3}", self.max_over_median()));
+        fields.push(format!("{:.6}", self.density()));
+        fields.push(format!("{:.3}", self.mean_superkmer_len()));
+        fields.push(top_mint);
+        fields.push(top_kmers);
+        debug_assert_eq!(fields.len(), TSV_COLUMNS.len());
+        fields.join("\t")
+    }
+
+    fn percentiles(&self) -> [(&'static str, u64); 9] {
+        [
+            ("min", self.min), ("p1", self.p1), ("p5", self.p5), ("p25", self.p25), ("median", self.median),
+            ("p75", self.p75), ("p95", self.p95), ("p99", self.p99), ("max", self.max),
+        ]
+    }
+}
+
+fn ratio(a: f64, b: f64) -> f64 {
+    if b == 0.0 { 0.0 } else { a / b }
+}
+
+/// JSON has no NaN/inf; ratios are already 0 on empty input.
+fn json_f64(x: f64) -> String {
+    if x.is_finite() { format!("{}", x) } else { "null".to_string() }
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Minimizer string of `mint` (2-bit, MSB-first).
+pub fn decode_mint(mint: u32, l: usize) -> String {
+    (0..l).rev().map(|i| b"ACGT"[((mint >> (2 * i)) & 3) as usize] as char).collect()
+}
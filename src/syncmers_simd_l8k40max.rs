@@ -14,7 +14,7 @@
 use std::arch::x86_64::*;
 
 use crate::Superkmer;
-use crate::minimizer_core::{canonical_table, base_from_ascii};
+use crate::minimizer_core::base_from_ascii;
 use lazy_static::lazy_static;
 
 // ---------------------------------------------------------------------------
@@ -25,11 +25,10 @@ use lazy_static::lazy_static;
 /// Uses canonical syncmer status so fwd/RC pairs get the same priority (context-independent).
 fn generate_syncmer_bit_table(l: usize) -> Vec<u8> {
     let scores = crate::iteratorsyncmers2::generate_syncmer_scores_with_s(l, 2);
-    let canon_table = crate::minimizer_core::canonical_table(l);
     let num_lmers = 1 << (2 * l);
     let mut bits = vec![0u8; num_lmers / 8];
     for lmer in 0..num_lmers {
-        let (canon_val, _) = canon_table[lmer];
+        let (canon_val, _) = crate::tables::canonical_lmer(lmer, l);
         if scores[canon_val as usize] == 0 {
             bits[lmer / 8] |= 1 << (lmer % 8);
         }
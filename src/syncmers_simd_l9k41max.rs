@@ -13,7 +13,7 @@
 use std::arch::x86_64::*;
 
 use crate::Superkmer;
-use crate::minimizer_core::{canonical_table, base_from_ascii};
+use crate::minimizer_core::base_from_ascii;
 use lazy_static::lazy_static;
 
 // ---------------------------------------------------------------------------
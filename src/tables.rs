@@ -0,0 +1,120 @@
+//! Lookup tables shared by the minimizer schemes: canonical l-mers and score tables,
+//! generated on first use per (scheme, l) and accounted for in `memory_footprint()`.
+//!
+//! Canonical l-mers are computed inline with a bit-parallel reverse complement, so the
+//! hot loops need no table. `canonical_table(l)` is kept for callers that want one; each
+//! entry is a `u32` with the rc flag in the top bit (4 bytes per l-mer, 64 MB at l=12).
+//!
+//! ```
+//! use rust_superkmers::tables::{canonical_lmer, canonical_table, unpack_canonical};
+//! let acgtt = 0b00_01_10_11_11; // ACGTT, reverse complement AACGT
+//! assert_eq!(canonical_lmer(acgtt, 5), (0b00_00_01_10_11, true));
+//! assert_eq!(unpack_canonical(canonical_table(5)[acgtt]), canonical_lmer(acgtt, 5));
+//! ```
+//!
+//! For even l, an l-mer can be its own reverse complement. Such palindromes have no
+//! orientation (`canonical_lmer` reports them as forward), so the score tables of the
+//! syncmer and UHS schemes demote them like homopolymers: they are only chosen as a
+//! minimizer when the window offers nothing better.
+use std::sync::{Mutex, OnceLock};
+
+/// Top bit of a packed canonical entry: set when the canonical l-mer is the reverse complement.
+pub const RC_FLAG: u32 = 1 << 31;
+
+/// Largest l for which tables can be generated (canonical values keep the top bit free).
+pub const MAX_L: usize = 15;
+
+/// Reverse complement of an l-mer (2 bits per base, MSB-first), l in 1..=32.
+#[inline(always)]
+pub fn revcomp_lmer(val: usize, l: usize) -> usize {
+    // Complement, then reverse the 2-bit groups of the whole word: pairs, nibbles, bytes
+    let mut x = !(val as u64);
+    x = ((x >> 2) & 0x3333_3333_3333_3333) | ((x & 0x3333_3333_3333_3333) << 2);
+    x = ((x >> 4) & 0x0F0F_0F0F_0F0F_0F0F) | ((x & 0x0F0F_0F0F_0F0F_0F0F) << 4);
+    (x.swap_bytes() >> (64 - 2 * l)) as usize
+}
+
+/// (canonical l-mer, is_rc): the smaller of `val` and its reverse complement, with
+/// `is_rc` set only when the reverse complement is strictly smaller.
+#[inline(always)]
+pub fn canonical_lmer(val: usize, l: usize) -> (u32, bool) {
+    let rc = revcomp_lmer(val, l);
+    if rc < val { (rc as u32, true) } else { (val as u32, false) }
+}
+
+/// Whether `val` is its own reverse complement (only possible for even l).
+#[inline(always)]
+pub fn is_palindrome(val: usize, l: usize) -> bool {
+    l.is_multiple_of(2) && revcomp_lmer(val, l) == val
+}
+
+#[inline(always)]
+pub fn pack_canonical((canon, is_rc): (u32, bool)) -> u32 {
+    if is_rc { canon | RC_FLAG } else { canon }
+}
+
+#[inline(always)]
+pub fn unpack_canonical(entry: u32) -> (u32, bool) {
+    (entry & !RC_FLAG, entry & RC_FLAG != 0)
+}
+
+/// Packed canonical entry of every l-mer.
+pub fn generate_canonical_table(l: usize) -> Vec<u32> {
+    (0..1usize << (2 * l)).map(|fwd| pack_canonical(canonical_lmer(fwd, l))).collect()
+}
+
+static CANONICAL: LazyTables<u32> = LazyTables::new("canonical", generate_canonical_table);
+
+/// Packed canonical table for `l` (see `unpack_canonical`), generated on first use.
+pub fn canonical_table(l: usize) -> &'static [u32] {
+    CANONICAL.get(l)
+}
+
+/// A generated table and its size.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TableInfo {
+    pub name: &'static str,
+    pub l: usize,
+    pub bytes: usize,
+}
+
+static GENERATED: Mutex<Vec<TableInfo>> = Mutex::new(Vec::new());
+
+/// Tables generated so far in this process, in generation order.
+pub fn memory_footprint() -> Vec<TableInfo> {
+    GENERATED.lock().unwrap().clone()
+}
+
+/// Total size in bytes of the tables generated so far.
+pub fn total_bytes() -> usize {
+    GENERATED.lock().unwrap().iter().map(|t| t.bytes).sum()
+}
+
+/// One table per l for a scheme, generated on first use and recorded in
+/// `memory_footprint()`.
+pub struct LazyTables<T: 'static> {
+    name: &'static str,
+    generate: fn(usize) -> Vec<T>,
+    tables: [OnceLock<Box<[T]>>; MAX_L + 1],
+}
+
+impl<T> LazyTables<T> {
+    pub const fn new(name: &'static str, generate: fn(usize) -> Vec<T>) -> Self {
+        LazyTables { name, generate, tables: [const { OnceLock::new() }; MAX_L + 1] }
+    }
+
+    #[inline(always)]
+    pub fn get(&'static self, l: usize) -> &'static [T] {
+        assert!(l <= MAX_L, "No {} table for l={} (at most {})", self.name, l, MAX_L);
+        self.tables[l].get_or_init(|| {
+            let table = (self.generate)(l).into_boxed_slice();
+            GENERATED.lock().unwrap().push(TableInfo { name: self.name, l, bytes: std::mem::size_of_val(&*table) });
+            table
+        })
+    }
+
+    /// Whether the table for `l` has been generated.
+    pub fn is_generated(&self, l: usize) -> bool {
+        self.tables.get(l).is_some_and(|t| t.get().is_some())
+    }
+}
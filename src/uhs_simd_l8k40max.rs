@@ -12,7 +12,7 @@
 use std::arch::x86_64::*;
 
 use crate::Superkmer;
-use crate::minimizer_core::{canonical_table, base_from_ascii};
+use crate::minimizer_core::base_from_ascii;
 use lazy_static::lazy_static;
 
 // ---------------------------------------------------------------------------
@@ -283,9 +283,14 @@ unsafe fn simd_kernel(
     macro_rules! issue_gather {
         () => {{
             // Gather from ry table (32 bytes, single cache line)
-            let is_uhs = _mm256_i32gather_epi32::<1>(
-                ry_uhs_table.as_ptr() as *const i32, rolling_ry);
             let canon = simd_min_u32(rolling_fwd, rolling_rc);
+            // Palindromes (fwd == rc) and homopolymers (canonical all-A) are demoted
+            // like in the scalar table
+            let demoted = _mm256_or_si256(
+                _mm256_cmpeq_epi32(rolling_fwd, rolling_rc),
+                _mm256_cmpeq_epi32(canon, _mm256_setzero_si256()));
+            let is_uhs = _mm256_andnot_si256(demoted,
+                _mm256_i32gather_epi32::<1>(ry_uhs_table.as_ptr() as *const i32, rolling_ry));
             // is_rc tracking
             let not_rc = _mm256_cmpeq_epi32(canon, rolling_fwd);
             let is_rc_mask = _mm256_xor_si256(not_rc, all_ones);
@@ -1,3 +1,6 @@
+pub mod fasta;
+pub mod io;
+
 /// Split a byte sequence on N/n characters, returning (offset, fragment) pairs.
 /// Fragments shorter than `min_len` are skipped.
 pub fn split_on_n(seq: &[u8], min_len: usize) -> Vec<(usize, &[u8])> {
@@ -289,3 +292,35 @@ pub fn bitpack_fragment_rc(fragment: &[u8]) -> Vec<u64> {
     bitpack_fragment_rc_into(fragment, &mut storage);
     storage
 }
+
+/// Copy bases `[start, start + len)` of a 2-bit packed sequence into `out`, re-aligned
+/// so that base `start` lands at position 0 (MSB of `out[0]`).
+/// Bits past `len` in the last word are unspecified; nothing in the crate reads them.
+pub fn copy_packed_range(src: &[u64], start: usize, len: usize, out: &mut Vec<u64>) {
+    let num_words = len.div_ceil(32);
+    out.resize(num_words, 0);
+    let first = start / 32;
+    let shift = (start % 32) * 2;
+    if shift == 0 {
+        out.copy_from_slice(&src[first..first + num_words]);
+        return;
+    }
+    for i in 0..num_words {
+        let hi = src[first + i] << shift;
+        let lo = match src.get(first + i + 1) {
+            Some(&next) => next >> (64 - shift),
+            None => 0,
+        };
+        out[i] = hi | lo;
+    }
+}
+
+/// Decode bases `[start, start + len)` of a 2-bit packed sequence back to uppercase ASCII.
+pub fn decode_packed_range(src: &[u64], start: usize, len: usize, out: &mut Vec<u8>) {
+    const BASES: [u8; 4] = [b'A', b'C', b'G', b'T'];
+    out.clear();
+    out.reserve(len);
+    for pos in start..start + len {
+        out.push(BASES[crate::minimizer_core::get_base(src, pos)]);
+    }
+}
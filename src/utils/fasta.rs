@@ -0,0 +1,60 @@
+//! Streaming FASTA reader shared by the library and the binaries.
+//!
+//! Records are yielded one at a time, so only the current sequence is held in
+//! memory. The name is the first word of the header line; sequence lines are
+//! trimmed and concatenated as-is (case and N are left to the caller). Sequence
+//! data before the first header is an `InvalidData` error rather than a record.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Lines};
+use std::path::Path;
+
+use super::io::invalid;
+
+/// One FASTA record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastaRecord {
+    pub name: String,
+    pub seq: Vec<u8>,
+}
+
+/// Iterator over the records of a FASTA stream, see [`fasta_records`].
+pub struct FastaRecords<R> {
+    lines: Lines<R>,
+    name: Option<String>,
+    seq: Vec<u8>,
+}
+
+/// Iterate over the records of a FASTA stream.
+pub fn fasta_records<R: BufRead>(reader: R) -> FastaRecords<R> {
+    FastaRecords { lines: reader.lines(), name: None, seq: Vec::new() }
+}
+
+/// Open a FASTA file and iterate over its records.
+pub fn open_fasta<P: AsRef<Path>>(path: P) -> io::Result<FastaRecords<BufReader<File>>> {
+    Ok(fasta_records(BufReader::new(File::open(path)?)))
+}
+
+impl<R: BufRead> Iterator for FastaRecords<R> {
+    type Item = io::Result<FastaRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            if let Some(header) = line.strip_prefix('>') {
+                let name = header.split_whitespace().next().unwrap_or("").to_string();
+                if let Some(prev) = self.name.replace(name) {
+                    return Some(Ok(FastaRecord { name: prev, seq: std::mem::take(&mut self.seq) }));
+                }
+            } else if self.name.is_some() {
+                self.seq.extend_from_slice(line.trim().as_bytes());
+            } else if !line.trim().is_empty() {
+                return Some(Err(invalid("sequence line before the first FASTA header")));
+            }
+        }
+        self.name.take().map(|name| Ok(FastaRecord { name, seq: std::mem::take(&mut self.seq) }))
+    }
+}
@@ -0,0 +1,43 @@
+//! Little-endian readers shared by the on-disk formats (partition maps, sketches,
+//! score tables, packed genomes).
+//!
+//! Counts and lengths come from the file itself, so a corrupt header must not decide
+//! how much memory is reserved: `capped` bounds preallocation, `read_bytes` and the
+//! collections grow past it only as the data actually arrives.
+
+use std::io::{self, Read};
+
+/// Largest number of items reserved up front for a count read from a file.
+const MAX_PREALLOC: usize = 1 << 16;
+
+pub(crate) fn read_u32<R: Read>(input: &mut R) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    input.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+pub(crate) fn read_u64<R: Read>(input: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    input.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Read exactly `len` bytes, failing with `UnexpectedEof` if the input is shorter.
+pub(crate) fn read_bytes<R: Read>(input: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(capped(len));
+    input.take(len as u64).read_to_end(&mut buf)?;
+    if buf.len() < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(buf)
+}
+
+/// Capacity to reserve for `count` items read from a file.
+#[inline]
+pub(crate) fn capped(count: usize) -> usize {
+    count.min(MAX_PREALLOC)
+}
+
+pub(crate) fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,93 @@
+use rust_superkmers::Superkmer;
+use rust_superkmers::chunked::{chunk_ranges, stitch_superkmers};
+use rust_superkmers::metrics::random_sequence;
+
+#[test]
+fn test_chunk_ranges_cover_each_kmer_once() {
+    let k = 31;
+    for &(len, n) in &[(31, 1), (31, 4), (100, 3), (1000, 7), (1000, 2000), (30, 2)] {
+        let ranges = chunk_ranges(len, k, n);
+        let mut covered = vec![0u8; (len + 1).saturating_sub(k)];
+        for &(start, end) in &ranges {
+            assert!(end - start >= k && end <= len);
+            for c in &mut covered[start..=end - k] {
+                *c += 1;
+            }
+        }
+        assert!(covered.iter().all(|&c| c == 1), "len={} n={}", len, n);
+        assert!(ranges.len() <= n);
+    }
+}
+
+/// Extract `seq` in `n` chunks with `process_range`, stitch, and return the result.
+macro_rules! chunked {
+    ($ctor:expr, $seq:expr, $k:expr, $n:expr) => {{
+        let mut ext = $ctor;
+        let parts: Vec<Vec<Superkmer>> = chunk_ranges($seq.len(), $k, $n).into_iter()
+            .map(|(start, end)| ext.process_range(&$seq, start, end).to_vec())
+            .collect();
+        stitch_superkmers(&parts, $k)
+    }};
+}
+
+#[test]
+fn test_stitch_matches_single_pass_syncmers2() {
+    use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+    let (k, l) = (31, 8);
+    for seed in 0..20 {
+        let seq = random_sequence(3000, seed);
+        for n in [2, 5, 17, 300, 2970] {
+            let single = SuperkmerExtractor::classical(k, l).process(&seq).to_vec();
+            assert_eq!(chunked!(SuperkmerExtractor::classical(k, l), seq, k, n), single, "classical seed={} n={}", seed, n);
+            let single = SuperkmerExtractor::msp(k, l).process(&seq).to_vec();
+            assert_eq!(chunked!(SuperkmerExtractor::msp(k, l), seq, k, n), single, "msp seed={} n={}", seed, n);
+            let single = SuperkmerExtractor::mspxor_non_canonical(k, 9).process(&seq).to_vec();
+            assert_eq!(chunked!(SuperkmerExtractor::mspxor_non_canonical(k, 9), seq, k, n), single, "mspxor seed={} n={}", seed, n);
+        }
+    }
+}
+
+#[test]
+fn test_stitch_matches_single_pass_uhs() {
+    use rust_superkmers::iteratoruhs::SuperkmerExtractor;
+    let (k, l) = (31, 8);
+    for seed in 0..10 {
+        let seq = random_sequence(2000, 100 + seed);
+        let single = SuperkmerExtractor::mspxor(k, l).process(&seq).to_vec();
+        for n in [3, 64] {
+            assert_eq!(chunked!(SuperkmerExtractor::mspxor(k, l), seq, k, n), single, "seed={} n={}", seed, n);
+        }
+    }
+}
+
+#[test]
+fn test_stitch_with_n_does_not_cross_fragments() {
+    use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+    let (k, l) = (31, 8);
+    let mut seq = random_sequence(2000, 7);
+    seq[500..520].fill(b'N');
+    seq[1200] = b'n';
+    let single = SuperkmerExtractor::mspxor(k, l).process_with_n(&seq).to_vec();
+    for n in [2, 9, 40] {
+        let mut ext = SuperkmerExtractor::mspxor(k, l);
+        let parts: Vec<Vec<Superkmer>> = chunk_ranges(seq.len(), k, n).into_iter()
+            .map(|(start, end)| ext.process_range_with_n(&seq, start, end).to_vec())
+            .collect();
+        assert_eq!(stitch_superkmers(&parts, k), single, "n={}", n);
+    }
+}
+
+#[test]
+fn test_stitch_sticky_tiles() {
+    use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+    let (k, l) = (31, 8);
+    let seq = random_sequence(3000, 11);
+    let sks = chunked!(SuperkmerExtractor::new(k, l), seq, k, 13);
+    let mut next = 0;
+    for sk in &sks {
+        assert_eq!(sk.start, next);
+        assert!(sk.mpos as usize + l <= sk.size as usize);
+        next = sk.start + sk.size as usize - k + 1;
+    }
+    assert_eq!(next, seq.len() - k + 1);
+}
@@ -0,0 +1,110 @@
+use rust_superkmers::compare::{compare_random, compare_schemes, random_input};
+use rust_superkmers::iteratorsyncmers2::{SuperkmerExtractor, SuperkmersIterator};
+use rust_superkmers::metrics::random_sequence;
+
+#[test]
+fn test_extractor_matches_iterator_on_random_inputs() {
+    let (k, l) = (31, 8);
+    let mut sticky = SuperkmerExtractor::new(k, l);
+    let result = compare_random(
+        |s| sticky.process_with_n(s).to_vec(),
+        |s| SuperkmersIterator::new_with_n(s, k, l).collect(),
+        k, l, 300, 3,
+    );
+    match result {
+        Ok(n) => assert_eq!(n, 300),
+        Err(d) => panic!("{}", d),
+    }
+
+    let mut mspxor = SuperkmerExtractor::mspxor(k, l);
+    let result = compare_random(
+        |s| mspxor.process_with_n(s).to_vec(),
+        |s| SuperkmersIterator::mspxor_with_n(s, k, l).collect(),
+        k, l, 300, 4,
+    );
+    match result {
+        Ok(n) => assert_eq!(n, 300),
+        Err(d) => panic!("{}", d),
+    }
+}
+
+#[test]
+fn test_random_inputs_cover_edge_cases() {
+    let k = 31;
+    let inputs: Vec<Vec<u8>> = (0..500).map(|case| random_input(case, 9, k)).collect();
+    assert_eq!(inputs, (0..500).map(|case| random_input(case, 9, k)).collect::<Vec<_>>());
+    assert!(inputs.iter().any(|s| s.len() == k - 1));
+    assert!(inputs.iter().any(|s| s.len() == k));
+    assert!(inputs.iter().any(|s| s.len() == k + 1));
+    assert!(inputs.iter().any(|s| s.contains(&b'N')));
+    assert!(inputs.iter().any(|s| s.iter().any(|b| b.is_ascii_lowercase())));
+}
+
+#[test]
+fn test_divergence_is_reported_and_shrunk() {
+    let (k, l) = (31, 8);
+    let mut ext = SuperkmerExtractor::mspxor(k, l);
+    let mut truncated = SuperkmerExtractor::mspxor(k, l);
+    // b loses every superkmer starting at or after position 100
+    let d = compare_random(
+        |s| ext.process_with_n(s).to_vec(),
+        |s| truncated.process_with_n(s).iter().filter(|sk| sk.start < 100).copied().collect(),
+        k, l, 300, 5,
+    ).unwrap_err();
+    assert!(d.a.unwrap().start >= 100);
+    assert_eq!(d.b, None);
+    // Shrinking leaves little more than the first superkmer past position 100
+    assert!(d.seq.len() <= 100 + 2 * k, "{}", d);
+    let report = d.to_string();
+    assert!(report.contains("(no superkmer)") && report.contains("seq["));
+
+    let seqs = [random_sequence(200, 1), random_sequence(200, 2)];
+    let mut plain = SuperkmerExtractor::mspxor(k, l);
+    let mut again = SuperkmerExtractor::mspxor(k, l);
+    let ok = compare_schemes(|s| plain.process(s).to_vec(), |s| again.process(s).to_vec(), seqs.iter().map(|s| &s[..]), l);
+    assert_eq!(ok, Ok(2));
+}
+
+/// The sticky syncmers2 iterator against the rust-debruijn `Scanner` wrapper.
+#[cfg(feature = "debruijn")]
+#[test]
+fn test_syncmers2_matches_syncmersmsp() {
+    let l = 8;
+    for k in [21, 31] {
+        let result = compare_random(
+            |s| rust_superkmers::iteratorsyncmersmsp::superkmers_with_n(s, k, l),
+            |s| SuperkmersIterator::new_with_n(s, k, l).collect(),
+            k, l, 300, 6,
+        );
+        match result {
+            Ok(n) => assert_eq!(n, 300),
+            Err(d) => panic!("k={}: {}", k, d),
+        }
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "nightly"))]
+#[test]
+fn test_simd_batch_matches_scalar_mspxor() {
+    if !is_x86_feature_detected!("avx2") {
+        return;
+    }
+    let (k, l) = (31, 8);
+    let mut simd = rust_superkmers::syncmers_simd_l8k40max::SimdBatchExtractor::new(k, l);
+    let mut scalar = SuperkmerExtractor::mspxor(k, l);
+    let seqs: Vec<Vec<u8>> = (0..40).map(|i| random_sequence(150, 100 + i)).collect();
+    let result = compare_schemes(
+        |s| {
+            let batch: [&[u8]; 8] = [s; 8];
+            let results = unsafe { simd.process_batch(&batch) };
+            results[0].clone()
+        },
+        |s| scalar.process(s).to_vec(),
+        seqs.iter().map(|s| &s[..]),
+        l,
+    );
+    match result {
+        Ok(n) => assert_eq!(n, 40),
+        Err(d) => panic!("{}", d),
+    }
+}
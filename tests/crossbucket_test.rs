@@ -0,0 +1,58 @@
+use rust_superkmers::crossbucket::{check_fasta, CrossBucketChecker};
+use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+use rust_superkmers::metrics::random_sequence;
+
+fn revcomp(seq: &[u8]) -> Vec<u8> {
+    seq.iter().rev().map(|&b| match b { b'A' => b'T', b'C' => b'G', b'G' => b'C', _ => b'A' }).collect()
+}
+
+/// Overlapping 150 bp reads of a 20 kbp reference, every other one reverse-complemented.
+fn reads() -> Vec<Vec<u8>> {
+    let reference = random_sequence(20_000, 11);
+    (0..20_000 - 150).step_by(37).enumerate().map(|(i, start)| {
+        let read = &reference[start..start + 150];
+        if i % 2 == 0 { read.to_vec() } else { revcomp(read) }
+    }).collect()
+}
+
+#[test]
+fn test_context_independent_schemes_are_consistent() {
+    let (k, l) = (31, 8);
+    for mut ext in [SuperkmerExtractor::mspxor(k, l), SuperkmerExtractor::msp(k, l)] {
+        let mut checker = CrossBucketChecker::new(k);
+        for read in reads() {
+            checker.add_sequence(&read, &mut |s| ext.process_with_n(s).to_vec());
+        }
+        let report = checker.report(5);
+        assert!(report.distinct_kmers > 19_000);
+        assert!(report.is_consistent(), "{:?}", report.examples);
+        assert_eq!(report.fraction(), 0.0);
+    }
+}
+
+#[test]
+fn test_sticky_reports_offending_kmers() {
+    let (k, l) = (31, 8);
+    let mut ext = SuperkmerExtractor::new(k, l);
+    let mut checker = CrossBucketChecker::new(k);
+    let reads = reads();
+    for read in &reads {
+        checker.add_sequence(read, &mut |s| ext.process_with_n(s).to_vec());
+    }
+    let report = checker.report(3);
+    assert!(report.cross_bucket_kmers > 0 && report.fraction() < 1.0);
+    assert_eq!(report.examples.len(), 3);
+    for (kmer, mints) in &report.examples {
+        assert_eq!(kmer.len(), k);
+        assert!(*kmer <= revcomp(kmer));
+        assert!(mints.len() >= 2);
+    }
+
+    // Same result through a FASTA file
+    let path = std::env::temp_dir().join("crossbucket_test_reads.fa");
+    let fasta: String = reads.iter().enumerate().map(|(i, r)| format!(">r{}\n{}\n", i, String::from_utf8_lossy(r))).collect();
+    std::fs::write(&path, fasta).unwrap();
+    let from_file = check_fasta(&path, k, 3, |s| ext.process_with_n(s).to_vec()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(from_file, report);
+}
@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+use rust_superkmers::dedup::{dedup_exact, merge_overlapping};
+use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+use rust_superkmers::orientation::{CanonicalSuperkmer, canonical_superkmer};
+use rust_superkmers::utils::decode_packed_range;
+use rust_superkmers::metrics::random_sequence;
+
+fn rc(seq: &[u8]) -> Vec<u8> {
+    seq.iter().rev().map(|&b| match b { b'A' => b'T', b'C' => b'G', b'G' => b'C', _ => b'A' }).collect()
+}
+
+fn canonical_kmers(sks: &[CanonicalSuperkmer], k: usize) -> HashSet<Vec<u8>> {
+    let mut kmers = HashSet::new();
+    let mut ascii = Vec::new();
+    for sk in sks {
+        decode_packed_range(&sk.bases, 0, sk.size as usize, &mut ascii);
+        for kmer in ascii.windows(k) {
+            kmers.insert(std::cmp::min(kmer.to_vec(), rc(kmer)));
+        }
+    }
+    kmers
+}
+
+/// 30x error-free reads of length 150 from both strands of `genome`.
+fn bucket_from_reads(genome: &[u8], k: usize, l: usize) -> Vec<CanonicalSuperkmer> {
+    let mut ext = SuperkmerExtractor::mspxor(k, l);
+    let mut x = 12345u64;
+    let mut all = Vec::new();
+    for _ in 0..genome.len() * 30 / 150 {
+        x = x.wrapping_mul(6364136223846793005).wrapping_add(1);
+        let start = (x >> 33) as usize % (genome.len() - 150);
+        let mut read = genome[start..start + 150].to_vec();
+        if (x >> 20) & 1 == 1 {
+            read = rc(&read);
+        }
+        all.extend(ext.process(&read).iter().map(|sk| canonical_superkmer(&read, sk, l)));
+    }
+    all
+}
+
+#[test]
+fn test_dedup_exact_counts() {
+    let (k, l) = (31, 8);
+    let bucket = bucket_from_reads(&random_sequence(3000, 1), k, l);
+    let counted = dedup_exact(bucket.clone());
+    assert_eq!(counted.iter().map(|&(_, c)| c as usize).sum::<usize>(), bucket.len());
+    assert!(counted.windows(2).all(|w| w[0].0 < w[1].0));
+    for (sk, count) in &counted {
+        assert_eq!(bucket.iter().filter(|b| *b == sk).count(), *count as usize);
+    }
+    // Reads from both strands collapse onto the same canonical superkmers
+    assert!(counted.len() * 3 < bucket.len());
+}
+
+#[test]
+fn test_merge_overlapping_recovers_genome_superkmers() {
+    let (k, l) = (31, 8);
+    let genome = random_sequence(5000, 2);
+    let bucket = bucket_from_reads(&genome, k, l);
+    let merged = merge_overlapping(bucket.clone(), k);
+    assert_eq!(canonical_kmers(&merged, k), canonical_kmers(&bucket, k));
+
+    // With full coverage the maximal superkmers are the genome's own superkmers
+    let expected: HashSet<CanonicalSuperkmer> = SuperkmerExtractor::mspxor(k, l).process(&genome)
+        .iter().map(|sk| canonical_superkmer(&genome, sk, l)).collect();
+    let found = merged.iter().filter(|sk| expected.contains(sk)).count();
+    assert!(found * 100 >= expected.len() * 95, "{}/{} genome superkmers recovered", found, expected.len());
+    assert!(merged.len() * 10 <= expected.len() * 11);
+}
+
+#[test]
+fn test_merge_requires_k_minus_1_overlap() {
+    let (k, l) = (31, 8);
+    let genome = random_sequence(400, 3);
+    let sks = SuperkmerExtractor::mspxor(k, l).process(&genome).to_vec();
+    let sk = *sks.iter().max_by_key(|sk| sk.size).unwrap();
+    let size = sk.size as usize;
+    assert!(size > k + 2);
+    let piece = |from: usize, to: usize| {
+        let mut part = sk;
+        part.start += from;
+        part.size = (to - from) as u16;
+        part.mpos -= from as u16;
+        canonical_superkmer(&genome, &part, l)
+    };
+    let whole = canonical_superkmer(&genome, &sk, l);
+
+    // First and last k-mer only: overlap too short, nothing to merge
+    let apart = merge_overlapping(vec![piece(0, k), piece(size - k, size)], k);
+    assert_eq!(apart.len(), 2);
+
+    // Add the k-mers in between: everything joins back into the original superkmer
+    let mut parts = vec![piece(0, k), piece(size - k, size), piece(1, size - 1)];
+    assert_eq!(merge_overlapping(parts.clone(), k), vec![whole.clone()]);
+    parts.push(whole.clone());
+    assert_eq!(merge_overlapping(parts, k), vec![whole.clone()]);
+
+    // A chain of single k-mers, each overlapping the next by k-1, joins in one sweep
+    let kmers: Vec<CanonicalSuperkmer> = (0..=size - k).rev().map(|i| piece(i, i + k)).collect();
+    assert_eq!(merge_overlapping(kmers, k), vec![whole]);
+}
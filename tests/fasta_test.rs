@@ -0,0 +1,23 @@
+use rust_superkmers::utils::fasta::{fasta_records, FastaRecord};
+
+fn records(text: &str) -> std::io::Result<Vec<FastaRecord>> {
+    fasta_records(text.as_bytes()).collect()
+}
+
+#[test]
+fn test_fasta_records() {
+    let parsed = records("\n>r0 first read\nACGT\nacgN  \n>r1\n>r2\nTTTT\n").unwrap();
+    let expected = vec![
+        FastaRecord { name: "r0".to_string(), seq: b"ACGTacgN".to_vec() },
+        FastaRecord { name: "r1".to_string(), seq: Vec::new() },
+        FastaRecord { name: "r2".to_string(), seq: b"TTTT".to_vec() },
+    ];
+    assert_eq!(parsed, expected);
+    assert!(records("").unwrap().is_empty());
+}
+
+#[test]
+fn test_fasta_records_rejects_sequence_before_header() {
+    let err = records("ACGT\n>r0\nACGT\n").expect_err("preamble accepted");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,99 @@
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+use rust_superkmers::ffi::*;
+use rust_superkmers::{iteratorkmc2, iteratormsp, iteratorsyncmers2, iteratoruhs, Superkmer};
+use rust_superkmers::metrics::random_sequence;
+
+fn new_extractor(spec: &str, k: usize, l: usize) -> Result<*mut SkExtractor, SkStatus> {
+    let spec = CString::new(spec).unwrap();
+    let mut ext = ptr::null_mut();
+    match unsafe { sk_extractor_new(spec.as_ptr(), k, l, &mut ext) } {
+        SkStatus::SkOk => Ok(ext),
+        status => {
+            assert!(ext.is_null());
+            Err(status)
+        }
+    }
+}
+
+fn process(ext: *mut SkExtractor, seq: &[u8], with_n: bool) -> Vec<Superkmer> {
+    let (mut sks, mut count) = (ptr::null(), 0);
+    assert_eq!(unsafe { sk_extractor_process(ext, seq.as_ptr(), seq.len(), with_n, &mut sks, &mut count) }, SkStatus::SkOk);
+    if count == 0 { Vec::new() } else { unsafe { std::slice::from_raw_parts(sks, count) }.to_vec() }
+}
+
+fn storage(ext: *mut SkExtractor) -> Vec<u64> {
+    let (mut words, mut count) = (ptr::null(), 0);
+    assert_eq!(unsafe { sk_extractor_storage(ext, &mut words, &mut count) }, SkStatus::SkOk);
+    unsafe { std::slice::from_raw_parts(words, count) }.to_vec()
+}
+
+#[test]
+fn test_matches_rust_extractors() {
+    let (k, l) = (31, 8);
+    let mut read = random_sequence(500, 3);
+    read[200] = b'N';
+    let clean = random_sequence(300, 4);
+
+    let ext = new_extractor("syncmer:mspxor", k, l).unwrap();
+    let mut rust = iteratorsyncmers2::SuperkmerExtractor::mspxor(k, l);
+    assert_eq!(process(ext, &read, true), rust.process_with_n(&read));
+    assert_eq!(process(ext, &clean, false), rust.process(&clean));
+    assert_eq!(storage(ext), rust.storage());
+    unsafe { sk_extractor_free(ext) };
+
+    let ext = new_extractor("uhs:mspxor:nc", k, l).unwrap();
+    let mut rust = iteratoruhs::SuperkmerExtractor::mspxor_non_canonical(k, l);
+    assert_eq!(process(ext, &read, true), rust.process_with_n(&read));
+    unsafe { sk_extractor_free(ext) };
+
+    let ext = new_extractor("kmc2:msp:nc", k, 10).unwrap();
+    let mut rust = iteratorkmc2::SuperkmerExtractor::msp_non_canonical(k, 10);
+    assert_eq!(process(ext, &read, true), rust.process_with_n(&read));
+    assert_eq!(storage(ext), rust.storage());
+    unsafe { sk_extractor_free(ext) };
+
+    let ext = new_extractor("msp:mspxor", k, 13).unwrap();
+    let mut rust = iteratormsp::SuperkmerExtractor::mspxor(k, 13);
+    assert_eq!(process(ext, &read, true), rust.process_with_n(&read));
+    unsafe { sk_extractor_free(ext) };
+}
+
+#[test]
+fn test_errors_instead_of_panics() {
+    assert_eq!(new_extractor("syncmer:mspxor", 31, 12).unwrap_err(), SkStatus::SkInvalidParameters);
+    assert_eq!(new_extractor("syncmer:mspxor", 5, 8).unwrap_err(), SkStatus::SkInvalidParameters);
+    assert_eq!(new_extractor("uhs:msp", 31, 8).unwrap_err(), SkStatus::SkInvalidScheme);
+    assert_eq!(new_extractor("kmc2", 31, 6).unwrap_err(), SkStatus::SkInvalidParameters);
+    assert_eq!(new_extractor("kmc2:lexicographic", 31, 8).unwrap_err(), SkStatus::SkInvalidScheme);
+    assert_eq!(new_extractor("lexicographic", 31, 8).unwrap_err(), SkStatus::SkInvalidScheme);
+    assert_eq!(new_extractor("msp", 31, 16).unwrap_err(), SkStatus::SkInvalidParameters);
+    assert_eq!(new_extractor("syncmer:xor", 31, 8).unwrap_err(), SkStatus::SkInvalidScheme);
+
+    let mut ext = ptr::null_mut();
+    assert_eq!(unsafe { sk_extractor_new(ptr::null(), 31, 8, &mut ext) }, SkStatus::SkNullPointer);
+    let ext = new_extractor("syncmer", 31, 8).unwrap();
+    let mut count = 0;
+    assert_eq!(unsafe { sk_extractor_process(ext, b"ACGT".as_ptr(), 4, false, ptr::null_mut(), &mut count) }, SkStatus::SkNullPointer);
+    // Shorter than k: no superkmers, not an error
+    assert!(process(ext, b"ACGT", false).is_empty());
+    assert!(process(ext, b"", true).is_empty());
+    unsafe { sk_extractor_free(ext) };
+    unsafe { sk_extractor_free(ptr::null_mut()) };
+
+    let msg = unsafe { CStr::from_ptr(sk_status_message(SkStatus::SkInvalidParameters)) };
+    assert_eq!(msg.to_str().unwrap(), "k or l not supported by the scheme");
+}
+
+#[test]
+fn test_header_declares_every_function() {
+    let header = std::fs::read_to_string("include/rust_superkmers.h").unwrap();
+    let source = std::fs::read_to_string("src/ffi.rs").unwrap();
+    let functions: Vec<&str> = source.split("extern \"C\" fn ").skip(1).map(|s| s.split('(').next().unwrap()).collect();
+    assert_eq!(functions.len(), 5);
+    for f in functions {
+        assert!(header.contains(&format!("{}(", f)), "{} missing from the header, regenerate it with cbindgen", f);
+    }
+    assert!(header.contains("typedef struct Superkmer {"));
+}
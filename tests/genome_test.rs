@@ -0,0 +1,131 @@
+use rust_superkmers::{Superkmer, SplitMode};
+use rust_superkmers::genome::{pack_fasta, PackedGenome};
+use rust_superkmers::iteratorsyncmers2::{SuperkmerExtractor, SuperkmersIterator};
+use rust_superkmers::metrics::random_sequence;
+
+/// Write a multi-contig FASTA (with N-runs and lowercase) and pack it.
+fn packed_fixture(name: &str) -> (Vec<Vec<u8>>, PackedGenome) {
+    let mut contigs = vec![random_sequence(5000, 1), random_sequence(31, 2), random_sequence(20, 3), random_sequence(3000, 4)];
+    contigs[0][1000..1040].fill(b'N');
+    contigs[0][2500] = b'n';
+    contigs[3][..10].fill(b'N');
+    contigs[3][100..400].make_ascii_lowercase();
+
+    let dir = std::env::temp_dir();
+    let fasta = dir.join(format!("{}.fa", name));
+    let packed = dir.join(format!("{}.skg", name));
+    let mut text = String::new();
+    for (i, c) in contigs.iter().enumerate() {
+        text.push_str(&format!(">ctg{} description\n", i));
+        for line in c.chunks(60) {
+            text.push_str(std::str::from_utf8(line).unwrap());
+            text.push('\n');
+        }
+    }
+    std::fs::write(&fasta, text).unwrap();
+    pack_fasta(&fasta, &packed).unwrap();
+    (contigs, PackedGenome::open(&packed).unwrap())
+}
+
+#[test]
+fn test_packed_genome_index() {
+    let (contigs, genome) = packed_fixture("skg_index");
+    assert!(PackedGenome::is_packed_genome(std::env::temp_dir().join("skg_index.skg")));
+    assert!(!PackedGenome::is_packed_genome(std::env::temp_dir().join("skg_index.fa")));
+    assert_eq!(genome.contigs().len(), 4);
+    assert_eq!(genome.contigs()[0].name, "ctg0");
+    assert_eq!(genome.contigs()[0].n_runs, vec![(1000, 40), (2500, 1)]);
+    assert_eq!(genome.contigs()[3].n_runs, vec![(0, 10)]);
+    for (idx, c) in contigs.iter().enumerate() {
+        assert_eq!(genome.contigs()[idx].len, c.len());
+        let mut ascii = Vec::new();
+        rust_superkmers::utils::decode_packed_range(genome.contig_storage(idx), 0, c.len(), &mut ascii);
+        for (i, (&a, &b)) in ascii.iter().zip(c.iter()).enumerate() {
+            let expected = if b == b'N' || b == b'n' { b'A' } else { b.to_ascii_uppercase() };
+            assert_eq!(a, expected, "contig {} base {}", idx, i);
+        }
+    }
+}
+
+#[test]
+fn test_packed_genome_rejects_corrupt_n_runs() {
+    let _ = packed_fixture("skg_corrupt");
+    let bytes = std::fs::read(std::env::temp_dir().join("skg_corrupt.skg")).unwrap();
+    let index_offset = u64::from_le_bytes(bytes[16..24].try_into().unwrap()) as usize;
+    // ctg0's first N-run: after name_len, "ctg0", len, word_offset and num_n_runs
+    let run = index_offset + 4 + 4 + 8 + 8 + 4;
+    for (start, len) in [(4990u64, 40u64), (3000, 10), (u64::MAX, 2)] {
+        let mut corrupt = bytes.clone();
+        corrupt[run..run + 8].copy_from_slice(&start.to_le_bytes());
+        corrupt[run + 8..run + 16].copy_from_slice(&len.to_le_bytes());
+        let path = std::env::temp_dir().join("skg_corrupt_runs.skg");
+        std::fs::write(&path, corrupt).unwrap();
+        let err = PackedGenome::open(&path).err().expect("corrupt N-run accepted");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn test_pack_fasta_rejects_sequence_before_header() {
+    let dir = std::env::temp_dir();
+    let fasta = dir.join("skg_preamble.fa");
+    let packed = dir.join("skg_preamble.skg");
+    std::fs::write(&fasta, "ACGTACGT\n>ctg0\nACGTACGTACGT\n").unwrap();
+    let err = pack_fasta(&fasta, &packed).expect_err("preamble packed onto the first contig");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // Blank lines ahead of the first header are harmless.
+    std::fs::write(&fasta, "\n>ctg0\nACGTACGTACGT\n").unwrap();
+    pack_fasta(&fasta, &packed).unwrap();
+    let genome = PackedGenome::open(&packed).unwrap();
+    assert_eq!(genome.contigs().len(), 1);
+    assert_eq!(genome.contigs()[0].len, 12);
+}
+
+#[test]
+fn test_packed_genome_matches_single_pass() {
+    let (contigs, genome) = packed_fixture("skg_single_pass");
+    let (k, l) = (31, 8);
+    for mode in [SplitMode::Classical, SplitMode::Msp, SplitMode::MspXor] {
+        for (idx, c) in contigs.iter().enumerate() {
+            let expected: Vec<Superkmer> = match mode {
+                SplitMode::Classical => SuperkmersIterator::classical_with_n(c, k, l).collect(),
+                SplitMode::Msp => SuperkmersIterator::msp_with_n(c, k, l).collect(),
+                _ => SuperkmersIterator::mspxor_with_n(c, k, l).collect(),
+            };
+            assert_eq!(genome.contig_superkmers(idx, k, l, mode, true, 4), expected,
+                "{:?} contig {}", mode, idx);
+
+            // Tiny chunks: many seams, all of which must be stitched back exactly.
+            for chunk_kmers in [1, 7, 100] {
+                let chunked = genome.chunked_superkmers(idx, k, 3, chunk_kmers,
+                    || match mode {
+                        SplitMode::Classical => SuperkmerExtractor::classical(k, l),
+                        SplitMode::Msp => SuperkmerExtractor::msp(k, l),
+                        _ => SuperkmerExtractor::mspxor(k, l),
+                    },
+                    |ext, chunk, len, out| out.extend_from_slice(ext.process_packed(chunk, len)));
+                assert_eq!(chunked, expected, "{:?} contig {} chunk_kmers={}", mode, idx, chunk_kmers);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_packed_genome_sticky_tiles() {
+    let (contigs, genome) = packed_fixture("skg_sticky");
+    let k = 31;
+    let c = &contigs[0];
+    let sks = genome.chunked_superkmers(0, k, 2, 64, || SuperkmerExtractor::new(k, 8),
+        |ext, chunk, len, out| out.extend_from_slice(ext.process_packed(chunk, len)));
+    let mut covered = vec![0u8; c.len() - k + 1];
+    for sk in &sks {
+        for n in &mut covered[sk.start..=sk.start + sk.size as usize - k] {
+            *n += 1;
+        }
+    }
+    for (i, &n) in covered.iter().enumerate() {
+        let has_n = c[i..i + k].iter().any(|&b| b == b'N' || b == b'n');
+        assert_eq!(n, if has_n { 0 } else { 1 }, "k-mer {}", i);
+    }
+}
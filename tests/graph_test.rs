@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use rust_superkmers::graph::{CompactedGraph, canonical_kmer, encode_kmer};
+use rust_superkmers::metrics::random_sequence;
+
+fn rc(seq: &[u8]) -> Vec<u8> {
+    seq.iter().rev().map(|&b| match b { b'A' => b'T', b'C' => b'G', b'G' => b'C', _ => b'A' }).collect()
+}
+
+fn kmer_counts<'a>(seqs: impl IntoIterator<Item = &'a [u8]>, k: usize) -> HashMap<u128, usize> {
+    let mut counts = HashMap::new();
+    for seq in seqs {
+        for kmer in seq.windows(k) {
+            *counts.entry(canonical_kmer(encode_kmer(kmer), k)).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Every input k-mer appears in exactly one unitig, exactly once.
+fn check_unitigs_partition_kmers(graph: &CompactedGraph, input: &[&[u8]], k: usize) {
+    let in_unitigs = kmer_counts(graph.unitigs.iter().map(|u| &u[..]), k);
+    assert!(in_unitigs.values().all(|&c| c == 1));
+    let mut expected = kmer_counts(input.iter().copied(), k);
+    expected.values_mut().for_each(|c| *c = 1);
+    assert_eq!(in_unitigs, expected);
+}
+
+#[test]
+fn test_single_sequence_is_one_unitig() {
+    let (k, l) = (31, 8);
+    let genome = random_sequence(5000, 1);
+    let graph = CompactedGraph::from_sequences([&genome[..]], k, l, 3);
+    assert_eq!(graph.unitigs.len(), 1);
+    assert!(graph.unitigs[0] == genome || graph.unitigs[0] == rc(&genome));
+    assert!(graph.links.is_empty());
+}
+
+#[test]
+fn test_reads_from_both_strands() {
+    let (k, l) = (31, 9);
+    let genome = random_sequence(4000, 2);
+    let reads: Vec<Vec<u8>> = (0..genome.len() - 150).step_by(40)
+        .chain(std::iter::once(genome.len() - 150))
+        .enumerate()
+        .map(|(i, s)| if i % 2 == 0 { genome[s..s + 150].to_vec() } else { rc(&genome[s..s + 150]) })
+        .collect();
+    let input: Vec<&[u8]> = reads.iter().map(|r| &r[..]).collect();
+    let graph = CompactedGraph::from_sequences(input.iter().copied(), k, l, 4);
+    check_unitigs_partition_kmers(&graph, &input, k);
+    assert_eq!(graph.unitigs.len(), 1);
+    assert!(graph.unitigs[0] == genome || graph.unitigs[0] == rc(&genome));
+}
+
+#[test]
+fn test_shared_region_branches() {
+    let (k, l) = (31, 8);
+    let (x, m, y, z, w) = (random_sequence(300, 3), random_sequence(500, 4), random_sequence(300, 5), random_sequence(300, 6), random_sequence(300, 7));
+    let a = [&x[..], &m, &y].concat();
+    let b = [&z[..], &rc(&m), &w].concat();
+    let input = [&a[..], &b[..]];
+    let graph = CompactedGraph::from_sequences(input, k, l, 2);
+    check_unitigs_partition_kmers(&graph, &input, k);
+
+    // x, z -> m -> y, w
+    assert_eq!(graph.unitigs.len(), 5);
+    assert_eq!(graph.links.len(), 4);
+    // The core may extend a base or two where the flanks happen to agree
+    let core = graph.unitigs.iter().position(|u| u.len() >= m.len() && u.len() < m.len() + 5).unwrap();
+    let core_seq = &graph.unitigs[core];
+    assert!(core_seq.windows(m.len()).any(|win| win == &m[..] || win == &rc(&m)[..]));
+    assert!(graph.links.iter().all(|link| link.from == core || link.to == core));
+}
+
+#[test]
+fn test_threads_and_gfa() {
+    let (k, l) = (21, 8);
+    let seqs: Vec<Vec<u8>> = (0..20).map(|i| random_sequence(200, 10 + i % 7)).collect();
+    let input: Vec<&[u8]> = seqs.iter().map(|s| &s[..]).collect();
+    let one = CompactedGraph::from_sequences(input.iter().copied(), k, l, 1);
+    let many = CompactedGraph::from_sequences(input.iter().copied(), k, l, 8);
+    assert_eq!(one.unitigs, many.unitigs);
+    assert_eq!(one.links, many.links);
+    check_unitigs_partition_kmers(&one, &input, k);
+
+    let mut gfa = Vec::new();
+    one.write_gfa(&mut gfa).unwrap();
+    let gfa = String::from_utf8(gfa).unwrap();
+    assert_eq!(gfa.lines().filter(|l| l.starts_with("S\t")).count(), one.unitigs.len());
+    assert_eq!(gfa.lines().filter(|l| l.starts_with("L\t")).count(), one.links.len());
+    let first = gfa.lines().nth(1).unwrap();
+    assert_eq!(first, format!("S\t0\t{}\tLN:i:{}", String::from_utf8_lossy(&one.unitigs[0]), one.unitigs[0].len()));
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use rust_superkmers::genome::{pack_fasta, PackedGenome};
+use rust_superkmers::index::{Hit, MinimizerIndex};
+use rust_superkmers::metrics::random_sequence;
+
+fn rc(seq: &[u8]) -> Vec<u8> {
+    seq.iter().rev().map(|&b| match b { b'A' => b'T', b'C' => b'G', b'G' => b'C', b'N' => b'N', _ => b'A' }).collect()
+}
+
+/// (contig, forward, offset) supported by the most hits.
+fn best_diagonal(hits: &[Hit]) -> (u32, bool, i64) {
+    let mut votes: HashMap<(u32, bool, i64), usize> = HashMap::new();
+    for h in hits {
+        *votes.entry((h.contig, h.forward, h.offset)).or_insert(0) += 1;
+    }
+    *votes.iter().max_by_key(|(_, &n)| n).unwrap().0
+}
+
+#[test]
+fn test_query_recovers_position_and_strand() {
+    let (k, l) = (31, 9);
+    let contigs = [random_sequence(20000, 1), random_sequence(5000, 2), random_sequence(12000, 3)];
+    let mut index = MinimizerIndex::new(k, l);
+    for (i, c) in contigs.iter().enumerate() {
+        assert_eq!(index.add_contig(&format!("ctg{}", i), c), i as u32);
+    }
+    assert!(index.num_entries() >= index.num_minimizers());
+
+    let mut ext = index.extractor();
+    let mut hits = Vec::new();
+    for (contig, start) in [(0, 0), (0, 17321), (1, 2500), (2, 11850), (2, 777)] {
+        let read = contigs[contig][start..start + 150].to_vec();
+        index.query_with(&mut ext, &read, &mut hits);
+        assert_eq!(best_diagonal(&hits), (contig as u32, true, start as i64));
+        assert!(hits.windows(2).all(|w| w[0] <= w[1]));
+
+        let mut read_rc = rc(&read);
+        read_rc[60..63].fill(b'N');
+        assert_eq!(best_diagonal(&index.query(&read_rc)), (contig as u32, false, start as i64));
+    }
+}
+
+#[test]
+fn test_hits_are_consistent_with_entries() {
+    let (k, l) = (21, 8);
+    let reference = random_sequence(3000, 4);
+    let mut index = MinimizerIndex::new(k, l);
+    index.add_contig("ref", &reference);
+    let read = reference[1000..1200].to_vec();
+    let hits = index.query(&read);
+    assert!(!hits.is_empty());
+    for h in &hits {
+        let entry = index.lookup(h.mint).iter().find(|e| e.start + e.mpos as usize == h.ref_pos).unwrap();
+        assert_eq!(entry.contig, h.contig);
+        if h.forward && h.offset == 1000 {
+            assert_eq!(&read[h.query_pos..h.query_pos + l], &reference[h.ref_pos..h.ref_pos + l]);
+        }
+    }
+    assert!(index.lookup(u32::MAX).is_empty());
+}
+
+#[test]
+fn test_index_from_packed_genome() {
+    let (k, l) = (31, 8);
+    let contigs = [random_sequence(4000, 5), random_sequence(2500, 6)];
+    let dir = std::env::temp_dir();
+    let fasta = dir.join("skg_minimizer_index.fa");
+    let packed = dir.join("skg_minimizer_index.skg");
+    let text: String = contigs.iter().enumerate()
+        .map(|(i, c)| format!(">chr{}\n{}\n", i, String::from_utf8_lossy(c)))
+        .collect();
+    std::fs::write(&fasta, text).unwrap();
+    pack_fasta(&fasta, &packed).unwrap();
+    let genome = PackedGenome::open(&packed).unwrap();
+
+    let from_packed = MinimizerIndex::from_packed_genome(&genome, k, l, 2);
+    let mut direct = MinimizerIndex::new(k, l);
+    for (i, c) in contigs.iter().enumerate() {
+        direct.add_contig(&format!("chr{}", i), c);
+    }
+    assert_eq!(from_packed.contig_names(), direct.contig_names());
+    assert_eq!(from_packed.num_entries(), direct.num_entries());
+    let read = &contigs[1][900..1100];
+    assert_eq!(from_packed.query(read), direct.query(read));
+}
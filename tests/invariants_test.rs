@@ -0,0 +1,207 @@
+//! Property-based checks of the invariants every extractor must satisfy, for every
+//! `SplitMode`, on random sequences with Ns, lowercase bases and lengths around k.
+//!
+//! Run:  cargo test --test invariants_test
+//! More cases:  PROPTEST_CASES=10000 cargo test --release --test invariants_test
+
+use proptest::prelude::*;
+use rust_superkmers::{iteratorkmc2, iteratorsyncmers2, iteratoruhs, Superkmer};
+
+/// How a scheme reports its minimizer.
+#[derive(Clone, Copy)]
+enum Mint {
+    /// Forward l-mer at `start + mpos`, `mint_is_rc` always false.
+    Forward,
+    /// min(l-mer, reverse complement), with `mint_is_rc` set when it is the reverse complement.
+    Canonical,
+    /// min(l-mer, reverse complement); `mint_is_rc` is not reported.
+    CanonicalNoFlag,
+}
+
+fn code(b: u8) -> Option<u64> {
+    match b | 0x20 {
+        b'a' => Some(0),
+        b'c' => Some(1),
+        b'g' => Some(2),
+        b't' => Some(3),
+        _ => None,
+    }
+}
+
+fn lmer(seq: &[u8]) -> (u64, u64) {
+    let fwd = seq.iter().fold(0, |acc, &b| (acc << 2) | code(b).unwrap());
+    let rc = seq.iter().rev().fold(0, |acc, &b| (acc << 2) | (3 - code(b).unwrap()));
+    (fwd, rc)
+}
+
+/// Check all invariants of `sks` (superkmers of `seq`); returns an error message.
+fn check(seq: &[u8], sks: &[Superkmer], k: usize, l: usize, mint: Mint) -> Result<(), String> {
+    let valid_kmer = |i: usize| seq[i..i + k].iter().all(|&b| code(b).is_some());
+    let num_kmers = (seq.len() + 1).saturating_sub(k);
+    let mut covered = vec![0u32; num_kmers];
+    for sk in sks {
+        let (start, size, mpos) = (sk.start, sk.size as usize, sk.mpos as usize);
+        if size < k || start + size > seq.len() {
+            return Err(format!("{:?}: bad extent for sequence length {}", sk, seq.len()));
+        }
+        // Never crosses an N: every base of the superkmer is ACGT
+        if let Some(i) = (start..start + size).find(|&i| code(seq[i]).is_none()) {
+            return Err(format!("{:?}: covers non-ACGT base at {}", sk, i));
+        }
+        if mpos + l > size {
+            return Err(format!("{:?}: minimizer past the superkmer end", sk));
+        }
+        // The minimizer must lie in every k-mer of the superkmer
+        if mpos + l > k {
+            return Err(format!("{:?}: minimizer not in the first k-mer", sk));
+        }
+        if mpos + k < size {
+            return Err(format!("{:?}: minimizer not in the last k-mer", sk));
+        }
+        let (fwd, rc) = lmer(&seq[start + mpos..start + mpos + l]);
+        let expected = match mint {
+            Mint::Forward => (fwd, Some(false)),
+            Mint::Canonical => (fwd.min(rc), Some(rc < fwd)),
+            Mint::CanonicalNoFlag => (fwd.min(rc), None),
+        };
+        if sk.mint as u64 != expected.0 || expected.1.is_some_and(|rc| rc != sk.mint_is_rc) {
+            return Err(format!("{:?}: minimizer l-mer {} does not decode to mint (expected {:?})",
+                sk, String::from_utf8_lossy(&seq[start + mpos..start + mpos + l]), expected));
+        }
+        for c in &mut covered[start..start + size - k + 1] {
+            *c += 1;
+        }
+    }
+    // Every valid k-mer exactly once
+    for (i, &c) in covered.iter().enumerate() {
+        if c != valid_kmer(i) as u32 {
+            return Err(format!("k-mer at {} covered {} times", i, c));
+        }
+    }
+    Ok(())
+}
+
+/// DNA with occasional N and lowercase, and lengths clustered around k.
+fn dna(k: usize) -> impl Strategy<Value = Vec<u8>> {
+    let base = prop_oneof![
+        40 => prop::sample::select(b"ACGT".to_vec()),
+        4 => prop::sample::select(b"acgt".to_vec()),
+        1 => Just(b'N'),
+    ];
+    let len = prop_oneof![
+        (k.saturating_sub(2)..k + 3),
+        (0..k + 200),
+    ];
+    len.prop_flat_map(move |n| prop::collection::vec(base.clone(), n))
+}
+
+const L: usize = 8;
+
+fn params() -> impl Strategy<Value = (usize, Vec<u8>)> {
+    prop::sample::select(vec![11usize, 15, 21, 31, 32, 33, 40, 63]).prop_flat_map(|k| (Just(k), dna(k)))
+}
+
+proptest! {
+    #[test]
+    fn syncmers2_extractor_invariants((k, seq) in params()) {
+        type Ctor = fn(usize, usize) -> iteratorsyncmers2::SuperkmerExtractor;
+        let ctors: [(&str, Ctor, Mint); 8] = [
+            ("sticky", iteratorsyncmers2::SuperkmerExtractor::new, Mint::Canonical),
+            ("sticky nc", iteratorsyncmers2::SuperkmerExtractor::non_canonical, Mint::Forward),
+            ("classical", iteratorsyncmers2::SuperkmerExtractor::classical, Mint::Canonical),
+            ("classical nc", iteratorsyncmers2::SuperkmerExtractor::classical_non_canonical, Mint::Forward),
+            ("msp", iteratorsyncmers2::SuperkmerExtractor::msp, Mint::Canonical),
+            ("msp nc", iteratorsyncmers2::SuperkmerExtractor::msp_non_canonical, Mint::Forward),
+            ("mspxor", iteratorsyncmers2::SuperkmerExtractor::mspxor, Mint::Canonical),
+            ("mspxor nc", iteratorsyncmers2::SuperkmerExtractor::mspxor_non_canonical, Mint::Forward),
+        ];
+        for (name, ctor, mint) in ctors {
+            let mut ext = ctor(k, L);
+            let sks = ext.process_with_n(&seq).to_vec();
+            let r = check(&seq, &sks, k, L, mint);
+            prop_assert!(r.is_ok(), "{} k={}: {}", name, k, r.unwrap_err());
+        }
+    }
+
+    #[test]
+    fn syncmers2_iterator_invariants((k, seq) in params()) {
+        let runs: [(&str, Vec<Superkmer>); 4] = [
+            ("sticky", iteratorsyncmers2::SuperkmersIterator::new_with_n(&seq, k, L).collect()),
+            ("classical", iteratorsyncmers2::SuperkmersIterator::classical_with_n(&seq, k, L).collect()),
+            ("msp", iteratorsyncmers2::SuperkmersIterator::msp_with_n(&seq, k, L).collect()),
+            ("mspxor", iteratorsyncmers2::SuperkmersIterator::mspxor_with_n(&seq, k, L).collect()),
+        ];
+        for (name, sks) in runs {
+            let r = check(&seq, &sks, k, L, Mint::Canonical);
+            prop_assert!(r.is_ok(), "{} k={}: {}", name, k, r.unwrap_err());
+        }
+    }
+
+    #[test]
+    fn uhs_invariants((k, seq) in params()) {
+        type Ctor = fn(usize, usize) -> iteratoruhs::SuperkmerExtractor;
+        let ctors: [(&str, Ctor, Mint); 4] = [
+            ("sticky", iteratoruhs::SuperkmerExtractor::new, Mint::Canonical),
+            ("sticky nc", iteratoruhs::SuperkmerExtractor::non_canonical, Mint::Forward),
+            ("mspxor", iteratoruhs::SuperkmerExtractor::mspxor, Mint::Canonical),
+            ("mspxor nc", iteratoruhs::SuperkmerExtractor::mspxor_non_canonical, Mint::Forward),
+        ];
+        for (name, ctor, mint) in ctors {
+            let mut ext = ctor(k, L);
+            let sks = ext.process_with_n(&seq).to_vec();
+            let r = check(&seq, &sks, k, L, mint);
+            prop_assert!(r.is_ok(), "{} k={}: {}", name, k, r.unwrap_err());
+        }
+    }
+
+    #[test]
+    fn kmc2_invariants((k, seq) in params()) {
+        let sks: Vec<Superkmer> = iteratorkmc2::SuperkmersIterator::new_with_n(&seq, k, L).collect();
+        let r = check(&seq, &sks, k, L, Mint::CanonicalNoFlag);
+        prop_assert!(r.is_ok(), "k={}: {}", k, r.unwrap_err());
+    }
+
+    #[test]
+    fn streaming_invariants((k, seq) in params(), split in 0usize..300) {
+        let mut stream = iteratorsyncmers2::StreamingExtractor::mspxor(k, L);
+        let split = split.min(seq.len());
+        let mut sks = stream.feed(&seq[..split]).to_vec();
+        sks.extend_from_slice(stream.feed(&seq[split..]));
+        sks.extend_from_slice(stream.finish());
+        let r = check(&seq, &sks, k, L, Mint::Canonical);
+        prop_assert!(r.is_ok(), "k={} split={}: {}", k, split, r.unwrap_err());
+    }
+}
+
+#[cfg(feature = "simd-mini")]
+proptest! {
+    #[test]
+    fn simdmini_invariants(k in prop::sample::select(vec![15usize, 21, 31, 33, 41]), seq in dna(41)) {
+        use rust_superkmers::iteratorsimdmini;
+        type Ctor = fn(usize, usize) -> iteratorsimdmini::SuperkmerExtractor;
+        let ctors: [(&str, Ctor); 4] = [
+            ("sticky", iteratorsimdmini::SuperkmerExtractor::new),
+            ("classical", iteratorsimdmini::SuperkmerExtractor::classical),
+            ("msp", iteratorsimdmini::SuperkmerExtractor::msp),
+            ("mspxor", iteratorsimdmini::SuperkmerExtractor::mspxor),
+        ];
+        for (name, ctor) in ctors {
+            let mut ext = ctor(k, 9);
+            let sks = ext.process_with_n(&seq).to_vec();
+            let r = check(&seq, &sks, k, 9, Mint::Canonical);
+            prop_assert!(r.is_ok(), "{} k={}: {}", name, k, r.unwrap_err());
+        }
+    }
+}
+
+#[test]
+fn test_exact_k_length() {
+    let k = 31;
+    let seq = b"ACGTACGTTGCAGGCATTACGGACTTAGCAT";
+    assert_eq!(seq.len(), k);
+    let mut ext = iteratorsyncmers2::SuperkmerExtractor::mspxor(k, L);
+    let sks = ext.process_with_n(seq).to_vec();
+    assert_eq!(sks.len(), 1);
+    check(seq, &sks, k, L, Mint::Canonical).unwrap();
+    assert!(ext.process_with_n(&seq[..k - 1]).is_empty());
+}
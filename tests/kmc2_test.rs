@@ -0,0 +1,108 @@
+use rust_superkmers::iteratorkmc2::{SuperkmerExtractor, SuperkmersIterator};
+use rust_superkmers::scoretable::{ScoreTable, TableExtractor};
+use rust_superkmers::tables::canonical_lmer;
+use rust_superkmers::{SplitMode, Superkmer};
+use rust_superkmers::metrics::random_sequence;
+
+fn reads() -> Vec<Vec<u8>> {
+    (0..20u64).map(|i| {
+        let mut read = random_sequence(80 + 41 * i as usize, i + 21);
+        if i % 3 == 1 {
+            read[50] = b'N';
+        }
+        read
+    }).collect()
+}
+
+fn lmer_at(seq: &[u8], pos: usize, l: usize) -> usize {
+    seq[pos..pos + l].iter().fold(0, |acc, &b| (acc << 2) | b"ACGT".iter().position(|&c| c == b).unwrap())
+}
+
+/// KMC2 rule: a canonical signature may not start with A nor contain AA or AC.
+fn allowed(canon: usize, l: usize) -> bool {
+    let bases: Vec<usize> = (0..l).rev().map(|i| (canon >> (2 * i)) & 3).collect();
+    bases[0] != 0 && !bases.windows(2).any(|w| w[0] == 0 && w[1] <= 1)
+}
+
+type Ctors = (fn(&[u8], usize, usize) -> SuperkmersIterator, fn(usize, usize) -> SuperkmerExtractor, SplitMode, bool);
+
+const CTORS: [Ctors; 8] = [
+    (SuperkmersIterator::new_with_n, SuperkmerExtractor::new, SplitMode::Sticky, true),
+    (SuperkmersIterator::non_canonical_with_n, SuperkmerExtractor::non_canonical, SplitMode::Sticky, false),
+    (SuperkmersIterator::classical_with_n, SuperkmerExtractor::classical, SplitMode::Classical, true),
+    (SuperkmersIterator::classical_non_canonical_with_n, SuperkmerExtractor::classical_non_canonical, SplitMode::Classical, false),
+    (SuperkmersIterator::msp_with_n, SuperkmerExtractor::msp, SplitMode::Msp, true),
+    (SuperkmersIterator::msp_non_canonical_with_n, SuperkmerExtractor::msp_non_canonical, SplitMode::Msp, false),
+    (SuperkmersIterator::mspxor_with_n, SuperkmerExtractor::mspxor, SplitMode::MspXor, true),
+    (SuperkmersIterator::mspxor_non_canonical_with_n, SuperkmerExtractor::mspxor_non_canonical, SplitMode::MspXor, false),
+];
+
+#[test]
+fn test_iterator_matches_extractor_and_score_table() {
+    let (k, l) = (31, 9);
+    for (iter, ctor, mode, canonical) in CTORS {
+        let mut ext = ctor(k, l);
+        let mut table = TableExtractor::new(ScoreTable::kmc2(l, mode, canonical), k);
+        for read in reads() {
+            let sks: Vec<Superkmer> = iter(&read, k, l).collect();
+            assert_eq!(ext.process_with_n(&read), &sks[..], "{:?} canonical={}", mode, canonical);
+            assert_eq!(ext.storage(), iter(&read, k, l).storage());
+            // The table extractor also reports the orientation of canonical signatures
+            let from_table: Vec<Superkmer> = table.process_with_n(&read).iter().map(|sk| Superkmer { mint_is_rc: false, ..*sk }).collect();
+            assert_eq!(from_table, sks, "{:?} canonical={}", mode, canonical);
+        }
+    }
+}
+
+/// Each k-mer gets the smallest allowed canonical signature, lexicographically or by
+/// canonical value ^ constant for MspXor, and a disqualified one only when nothing is allowed.
+#[test]
+fn test_signature_order() {
+    for (k, l) in [(21, 7), (31, 10), (33, 12)] {
+        let seq = random_sequence(400, l as u64);
+        let xor = 0xACE5_ACE5 & ((1 << (2 * l)) - 1);
+        for (mode, ctor) in [(SplitMode::Msp, SuperkmerExtractor::msp as fn(usize, usize) -> SuperkmerExtractor), (SplitMode::MspXor, SuperkmerExtractor::mspxor)] {
+            let key = |canon: usize| (!allowed(canon, l), if mode == SplitMode::MspXor { canon ^ xor } else { canon });
+            for sk in ctor(k, l).process(&seq).to_vec() {
+                assert!(!sk.mint_is_rc);
+                for j in sk.start..=sk.start + sk.size as usize - k {
+                    let best = (j..=j + k - l).map(|p| canonical_lmer(lmer_at(&seq, p, l), l).0 as usize).min_by_key(|&c| key(c)).unwrap();
+                    assert_eq!(sk.mint as usize, best, "{:?} l={} k-mer at {}", mode, l, j);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_non_canonical_mints_are_forward() {
+    let (k, l) = (31, 11);
+    let seq = random_sequence(3000, 9);
+    let canonical = SuperkmerExtractor::mspxor(k, l).process(&seq).to_vec();
+    let forward = SuperkmerExtractor::mspxor_non_canonical(k, l).process(&seq).to_vec();
+    assert_eq!(canonical.len(), forward.len());
+    for (c, f) in canonical.iter().zip(&forward) {
+        assert_eq!((c.start, c.size, c.mpos), (f.start, f.size, f.mpos));
+        assert_eq!(f.mint as usize, lmer_at(&seq, f.start + f.mpos as usize, l));
+        assert_eq!(canonical_lmer(f.mint as usize, l).0, c.mint);
+        assert!(!f.mint_is_rc);
+    }
+}
+
+#[test]
+fn test_minimizer_of_agrees_with_extractor() {
+    let (k, l) = (31, 8);
+    for ctor in [SuperkmerExtractor::classical, SuperkmerExtractor::msp_non_canonical, SuperkmerExtractor::mspxor] {
+        let mut ext = ctor(k, l);
+        let query = ctor(k, l);
+        for read in reads() {
+            for sk in ext.process_with_n(&read).to_vec() {
+                for j in sk.start..=sk.start + sk.size as usize - k {
+                    let (mint, mpos, rc) = query.minimizer_of(&read[j..j + k]).unwrap();
+                    assert_eq!((mint, rc), (sk.mint, sk.mint_is_rc));
+                    assert_eq!(j + mpos as usize, sk.start + sk.mpos as usize);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use rust_superkmers::iteratormsp::{superkmers_with_n, SuperkmerExtractor, SuperkmersIterator};
+use rust_superkmers::scoretable::{ScoreTable, TableExtractor};
+use rust_superkmers::tables::canonical_lmer;
+use rust_superkmers::{SplitMode, Superkmer};
+use rust_superkmers::metrics::random_sequence;
+
+fn reads() -> Vec<Vec<u8>> {
+    (0..20u64).map(|i| {
+        let mut read = random_sequence(80 + 41 * i as usize, i + 5);
+        if i % 3 == 1 {
+            read[50] = b'N';
+        }
+        if i % 4 == 2 {
+            // poly-A run, the demoted l-mer
+            read[20..40].fill(b'A');
+        }
+        read
+    }).collect()
+}
+
+fn lmer_at(seq: &[u8], pos: usize, l: usize) -> usize {
+    seq[pos..pos + l].iter().fold(0, |acc, &b| (acc << 2) | b"ACGT".iter().position(|&c| c == b).unwrap())
+}
+
+type Ctors = (fn(&[u8], usize, usize) -> SuperkmersIterator, fn(usize, usize) -> SuperkmerExtractor);
+
+const CTORS: [Ctors; 9] = [
+    (SuperkmersIterator::sticky_with_n, SuperkmerExtractor::sticky),
+    (SuperkmersIterator::non_canonical_with_n, SuperkmerExtractor::non_canonical),
+    (SuperkmersIterator::classical_with_n, SuperkmerExtractor::classical),
+    (SuperkmersIterator::classical_non_canonical_with_n, SuperkmerExtractor::classical_non_canonical),
+    (SuperkmersIterator::msp_with_n, SuperkmerExtractor::msp),
+    (SuperkmersIterator::msp_non_canonical_with_n, SuperkmerExtractor::msp_non_canonical),
+    (SuperkmersIterator::mspxor_with_n, SuperkmerExtractor::mspxor),
+    (SuperkmersIterator::mspxor_non_canonical_with_n, SuperkmerExtractor::mspxor_non_canonical),
+    (SuperkmersIterator::forward_with_n, SuperkmerExtractor::forward),
+];
+
+#[test]
+fn test_iterator_matches_extractor() {
+    for (k, l) in [(31, 8), (21, 13)] {
+        for (i, (iter, ctor)) in CTORS.into_iter().enumerate() {
+            let mut ext = ctor(k, l);
+            for read in reads() {
+                let sks: Vec<Superkmer> = iter(&read, k, l).collect();
+                assert_eq!(ext.process_with_n(&read), &sks[..], "constructor {} l={}", i, l);
+                assert_eq!(ext.storage(), iter(&read, k, l).storage());
+            }
+        }
+    }
+    // The tabulated order cuts the same superkmers
+    let (k, l) = (31, 9);
+    for (mode, ctor) in [(SplitMode::Classical, SuperkmerExtractor::classical as fn(usize, usize) -> SuperkmerExtractor), (SplitMode::MspXor, SuperkmerExtractor::mspxor)] {
+        let mut table = TableExtractor::new(ScoreTable::lexicographic(l, mode, true), k);
+        let mut ext = ctor(k, l);
+        for read in reads() {
+            assert_eq!(table.process_with_n(&read), ext.process_with_n(&read), "{:?}", mode);
+        }
+    }
+}
+
+/// rust-debruijn's `Scanner::scan` on forward l-mers with the poly-A l-mer scored last:
+/// the first window takes its rightmost minimum, an entering l-mer only replaces the
+/// minimizer if strictly smaller, and a minimizer that falls off triggers a rescan.
+fn debruijn_msp(seq: &[u8], k: usize, l: usize) -> Vec<Superkmer> {
+    let score = |pos: usize| match lmer_at(seq, pos, l) { 0 => usize::MAX, v => v };
+    let rightmost_min = |start: usize, stop: usize| (start..=stop).rev().min_by_key(|&p| score(p)).unwrap();
+    let mut changes = vec![(0, rightmost_min(0, k - l))];
+    for i in 1..=seq.len() - k {
+        let (_, min_pos) = *changes.last().unwrap();
+        if i > min_pos {
+            changes.push((i, rightmost_min(i, i + k - l)));
+        } else if score(i + k - l) < score(min_pos) {
+            changes.push((i, i + k - l));
+        }
+    }
+    changes.iter().enumerate().map(|(j, &(start, min_pos))| {
+        let end = changes.get(j + 1).map_or(seq.len(), |&(next, _)| next + k - 1);
+        Superkmer { start, mint: lmer_at(seq, min_pos, l) as u32, size: (end - start) as u16, mpos: (min_pos - start) as u16, mint_is_rc: false }
+    }).collect()
+}
+
+#[test]
+fn test_forward_matches_debruijn_scan() {
+    for (k, l) in [(31, 8), (31, 10), (31, 12), (21, 5), (41, 15)] {
+        let mut ext = SuperkmerExtractor::forward(k, l);
+        for read in reads().iter().filter(|r| !r.contains(&b'N')) {
+            assert_eq!(ext.process(read), &debruijn_msp(read, k, l)[..], "k={} l={}", k, l);
+        }
+        let homopolymer = vec![b'A'; 100];
+        assert_eq!(ext.process(&homopolymer), &debruijn_msp(&homopolymer, k, l)[..]);
+    }
+    // superkmers_with_n is the forward iterator, fragment by fragment
+    let read = &reads()[1];
+    let split: Vec<Superkmer> = read.split(|&b| b == b'N').scan(0, |offset, frag| {
+        let start = *offset;
+        *offset += frag.len() + 1;
+        Some((start, frag))
+    }).filter(|(_, frag)| frag.len() >= 31).flat_map(|(start, frag)| {
+        debruijn_msp(frag, 31, 10).into_iter().map(move |sk| Superkmer { start: sk.start + start, ..sk })
+    }).collect();
+    assert_eq!(superkmers_with_n(read, 31, 10), split);
+    // `new` still takes 2-bit codes
+    let codes: Vec<u8> = reads()[0].iter().map(|&b| b"ACGT".iter().position(|&c| c == b).unwrap() as u8).collect();
+    assert_eq!(SuperkmersIterator::new(&codes, 31, 10).collect::<Vec<_>>(), debruijn_msp(&reads()[0], 31, 10));
+}
+
+/// Each k-mer gets the smallest canonical l-mer, poly-A last, XORed with the constant
+/// for MspXor; Classical and Msp only differ in which occurrence they report.
+#[test]
+fn test_lexicographic_order() {
+    for (k, l) in [(21, 7), (31, 10), (33, 14)] {
+        let xor = 0xACE5_ACE5 & ((1 << (2 * l)) - 1);
+        for (mode, ctor) in [
+            (SplitMode::Classical, SuperkmerExtractor::classical as fn(usize, usize) -> SuperkmerExtractor),
+            (SplitMode::Msp, SuperkmerExtractor::msp),
+            (SplitMode::MspXor, SuperkmerExtractor::mspxor),
+        ] {
+            let key = |canon: usize| (canon == 0, if mode == SplitMode::MspXor { canon ^ xor } else { canon });
+            let mut ext = ctor(k, l);
+            for read in reads().iter().filter(|r| !r.contains(&b'N')) {
+                for sk in ext.process(read).to_vec() {
+                    let m = sk.start + sk.mpos as usize;
+                    assert_eq!(canonical_lmer(lmer_at(read, m, l), l), (sk.mint, sk.mint_is_rc));
+                    for j in sk.start..=sk.start + sk.size as usize - k {
+                        let best = (j..=j + k - l).map(|p| canonical_lmer(lmer_at(read, p, l), l).0 as usize).min_by_key(|&c| key(c)).unwrap();
+                        assert_eq!(sk.mint as usize, best, "{:?} l={} k-mer at {}", mode, l, j);
+                        assert_eq!(ext.minimizer_of(&read[j..j + k]), Some((sk.mint, (m - j) as u16, sk.mint_is_rc)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_non_canonical_mints_are_forward() {
+    let (k, l) = (31, 11);
+    let seq = random_sequence(3000, 9);
+    let canonical = SuperkmerExtractor::mspxor(k, l).process(&seq).to_vec();
+    let forward = SuperkmerExtractor::mspxor_non_canonical(k, l).process(&seq).to_vec();
+    assert_eq!(canonical.len(), forward.len());
+    for (c, f) in canonical.iter().zip(&forward) {
+        assert_eq!((c.start, c.size, c.mpos), (f.start, f.size, f.mpos));
+        assert_eq!(f.mint as usize, lmer_at(&seq, f.start + f.mpos as usize, l));
+        assert_eq!(canonical_lmer(f.mint as usize, l), (c.mint, c.mint_is_rc));
+        assert!(!f.mint_is_rc);
+    }
+}
+
+/// The native forward extractor against rust-debruijn itself.
+#[cfg(feature = "debruijn")]
+#[test]
+fn test_forward_matches_debruijn_crate() {
+    use debruijn::dna_string::DnaString;
+    use debruijn::kmer::{Kmer8, Kmer10, Kmer12};
+    use debruijn::msp::Scanner;
+    use debruijn::Kmer;
+
+    fn score<K: Kmer>(p: &K) -> usize {
+        if p.to_u64() == 0 { usize::MAX } else { p.to_u64() as usize }
+    }
+
+    fn scan<K: Kmer>(seq: &[u8], k: usize) -> Vec<Superkmer> {
+        let dnastring = DnaString::from_acgt_bytes(seq);
+        Scanner::new(&dnastring, score::<K>, k).scan().into_iter().map(|msp| Superkmer {
+            start: msp.start as usize,
+            mint: msp.minimizer.to_u64() as u32,
+            size: msp.len as u16,
+            mpos: (msp.minimizer_pos - msp.start) as u16,
+            mint_is_rc: false,
+        }).collect()
+    }
+
+    let k = 31;
+    for read in reads().iter().filter(|r| !r.contains(&b'N')) {
+        assert_eq!(SuperkmerExtractor::forward(k, 8).process(read), &scan::<Kmer8>(read, k)[..]);
+        assert_eq!(SuperkmerExtractor::forward(k, 10).process(read), &scan::<Kmer10>(read, k)[..]);
+        assert_eq!(SuperkmerExtractor::forward(k, 12).process(read), &scan::<Kmer12>(read, k)[..]);
+    }
+}
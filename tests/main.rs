@@ -2,7 +2,10 @@ use std::fs;
 use std::collections::HashSet;
 use std::hash::Hash;
 use std::fmt::Debug;
-use rust_superkmers::{Superkmer, naive, naive::superkmer_to_verbose, iterator1};
+use rust_superkmers::{naive, naive::superkmer_to_verbose, iterator1};
+#[cfg(feature = "debruijn")]
+use rust_superkmers::Superkmer;
+#[cfg(feature = "debruijn")]
 use debruijn::dna_string::DnaString;
 
 fn compare_sets<T: Eq + Hash + Debug + std::cmp::Ord>(set1: HashSet<T>, set2: HashSet<T>) {
@@ -24,18 +27,21 @@ fn main() {
         .split("\n").collect::<Vec<&str>>()[1].to_string();
 
     // Test iteratorsyncmers2 vs iteratorsyncmersmsp (should be identical)
-    let dnastring = DnaString::from_dna_string(&contents).to_bytes();
-    let iter = rust_superkmers::iteratorsyncmersmsp::SuperkmersIterator::new(&dnastring, 31, 8);
-    let syncmers_truth: Vec<Superkmer> = iter.collect();
-
-    let iter = rust_superkmers::iteratorsyncmers2::SuperkmersIterator::new(contents.as_bytes(), 31, 8);
-    let syncmers_test: Vec<Superkmer> = iter.collect();
-
-    let set1: HashSet<_> = syncmers_truth.into_iter().collect();
-    let set2: HashSet<_> = syncmers_test.into_iter().collect();
-    println!("syncmers k=31 l=8 testfile={}", genome_file);
-    println!("iteratorsyncmersmsp: {} superkmers, iteratorsyncmers2: {} superkmers", set1.len(), set2.len());
-    compare_sets(set1, set2);
+    #[cfg(feature = "debruijn")]
+    {
+        let dnastring = DnaString::from_dna_string(&contents).to_bytes();
+        let iter = rust_superkmers::iteratorsyncmersmsp::SuperkmersIterator::new(&dnastring, 31, 8);
+        let syncmers_truth: Vec<Superkmer> = iter.collect();
+
+        let iter = rust_superkmers::iteratorsyncmers2::SuperkmersIterator::new(contents.as_bytes(), 31, 8);
+        let syncmers_test: Vec<Superkmer> = iter.collect();
+
+        let set1: HashSet<_> = syncmers_truth.into_iter().collect();
+        let set2: HashSet<_> = syncmers_test.into_iter().collect();
+        println!("syncmers k=31 l=8 testfile={}", genome_file);
+        println!("iteratorsyncmersmsp: {} superkmers, iteratorsyncmers2: {} superkmers", set1.len(), set2.len());
+        compare_sets(set1, set2);
+    }
 
     // Stress test iterator1 vs naive over various parameters
     for m in vec![4, 5, 7, 11, 14] {
@@ -0,0 +1,56 @@
+use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+use rust_superkmers::metrics::{fasta_metrics, random_metrics, random_sequence, SchemeMetrics};
+
+#[test]
+fn test_scheme_metrics_random() {
+    let (k, l) = (31, 8);
+    let mut sticky = SuperkmerExtractor::new(k, l);
+    let mut classical = SuperkmerExtractor::classical(k, l);
+    let mut mspxor = SuperkmerExtractor::mspxor(k, l);
+    let a = random_metrics(100_000, 7, k, |s| sticky.process_with_n(s).to_vec());
+    let b = random_metrics(100_000, 7, k, |s| classical.process_with_n(s).to_vec());
+    let c = random_metrics(100_000, 7, k, |s| mspxor.process_with_n(s).to_vec());
+    for m in [&a, &b, &c] {
+        assert_eq!(m.kmers, 100_000 - k as u64 + 1);
+        assert_eq!(m.boundaries, m.superkmers - 1);
+        assert_eq!(m.length_histogram.iter().sum::<u64>(), m.superkmers);
+        assert!(m.superkmer_len_quantile(0.0) >= k && m.superkmer_len_quantile(1.0) <= 2 * k - l);
+        assert!(m.mean_superkmer_len() > k as f64);
+    }
+    // Sticky tie-breaking makes some splits depend on history; the other modes never do
+    assert!(a.context_dependent_fraction() > 0.0);
+    assert_eq!(b.context_dependent_fraction(), 0.0);
+    assert_eq!(c.context_dependent_fraction(), 0.0);
+    // Sticky keeps the current minimizer on ties: fewest superkmers
+    assert!(a.superkmers <= b.superkmers);
+}
+
+#[test]
+fn test_scheme_metrics_fasta_and_merge() {
+    let (k, l) = (31, 8);
+    let mut ext = SuperkmerExtractor::mspxor(k, l);
+    let m = fasta_metrics("tests/ecoli.genome.100k.fa", k, |s| ext.process_with_n(s).to_vec()).unwrap();
+    assert!(m.kmers > 90_000);
+    assert!(m.density() > 0.0 && m.density() < 0.2);
+
+    // Merging two runs equals measuring both sequences in one accumulator
+    let (x, y) = (random_sequence(5_000, 1), random_sequence(3_000, 2));
+    let mut scheme = |s: &[u8]| ext.process_with_n(s).to_vec();
+    let mut both = SchemeMetrics::new();
+    both.add_sequence(&x, k, &mut scheme);
+    both.add_sequence(&y, k, &mut scheme);
+    let mut merged = SchemeMetrics::new();
+    merged.add_sequence(&x, k, &mut scheme);
+    let mut other = SchemeMetrics::new();
+    other.add_sequence(&y, k, &mut scheme);
+    merged.merge(&other);
+    assert_eq!(merged, both);
+
+    // N splits sequences: no boundary is counted across it
+    let mut with_n = x.clone();
+    with_n[2_500] = b'N';
+    let mut split = SchemeMetrics::new();
+    split.add_sequence(&with_n, k, &mut scheme);
+    assert_eq!(split.kmers, (5_000 - k as u64 + 1) - k as u64);
+    assert_eq!(split.boundaries, split.superkmers - 2);
+}
@@ -0,0 +1,120 @@
+use rust_superkmers::utils::bitpack_fragment;
+use rust_superkmers::{iteratorkmc2, iteratormsp, iteratorrandom, iteratorsyncmers2, iteratoruhs, Superkmer};
+use rust_superkmers::metrics::random_sequence;
+
+fn revcomp(seq: &[u8]) -> Vec<u8> {
+    seq.iter().rev().map(|&b| match b { b'A' => b'T', b'C' => b'G', b'G' => b'C', _ => b'A' }).collect()
+}
+
+/// Random reads, some with Ns.
+fn reads() -> Vec<Vec<u8>> {
+    (0..40u64).map(|i| {
+        let mut read = random_sequence(50 + 37 * i as usize % 300, i + 1);
+        if i % 3 == 0 {
+            let n = read.len();
+            read[(i as usize * 13) % n] = b'N';
+        }
+        read
+    }).collect()
+}
+
+/// Every k-mer of every superkmer gets the superkmer's minimizer from `minimizer_of`;
+/// with `exact_pos`, also the same minimizer position in the read.
+fn check<F: FnMut(&[u8]) -> (u32, u16, bool)>(seq: &[u8], sks: &[Superkmer], k: usize, exact_pos: bool, mut minimizer_of: F) {
+    assert!(!sks.is_empty() || seq.len() < 2 * k);
+    for sk in sks {
+        for j in sk.start..=sk.start + sk.size as usize - k {
+            let (mint, mpos, rc) = minimizer_of(&seq[j..j + k]);
+            assert_eq!((mint, rc), (sk.mint, sk.mint_is_rc), "k-mer at {} of superkmer {:?}", j, sk);
+            if exact_pos {
+                assert_eq!(j + mpos as usize, sk.start + sk.mpos as usize, "k-mer at {} of superkmer {:?}", j, sk);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_syncmers2_agrees_with_extractor() {
+    type Ctor = fn(usize, usize) -> iteratorsyncmers2::SuperkmerExtractor;
+    let ctors: [Ctor; 6] = [
+        iteratorsyncmers2::SuperkmerExtractor::classical,
+        iteratorsyncmers2::SuperkmerExtractor::classical_non_canonical,
+        iteratorsyncmers2::SuperkmerExtractor::msp,
+        iteratorsyncmers2::SuperkmerExtractor::msp_non_canonical,
+        iteratorsyncmers2::SuperkmerExtractor::mspxor,
+        iteratorsyncmers2::SuperkmerExtractor::mspxor_non_canonical,
+    ];
+    for (k, l) in [(21, 8), (31, 9), (33, 8), (63, 9)] {
+        for ctor in ctors {
+            let mut ext = ctor(k, l);
+            let query = ctor(k, l);
+            for read in reads() {
+                let sks = ext.process_with_n(&read).to_vec();
+                check(&read, &sks, k, true, |kmer| query.minimizer_of(kmer).unwrap());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_uhs_agrees_with_extractor() {
+    for (k, l) in [(21, 7), (31, 8), (33, 9), (41, 11)] {
+        for ctor in [iteratoruhs::SuperkmerExtractor::mspxor, iteratoruhs::SuperkmerExtractor::mspxor_non_canonical] {
+            let mut ext = ctor(k, l);
+            let query = ctor(k, l);
+            for read in reads() {
+                let sks = ext.process_with_n(&read).to_vec();
+                check(&read, &sks, k, true, |kmer| query.minimizer_of(kmer).unwrap());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_kmc2_agrees_with_iterator() {
+    for k in [21, 31, 63] {
+        for read in reads() {
+            let sks: Vec<Superkmer> = iteratorkmc2::SuperkmersIterator::new_with_n(&read, k, 8).collect();
+            check(&read, &sks, k, false, |kmer| iteratorkmc2::minimizer_of(kmer, 8));
+        }
+    }
+}
+
+#[test]
+fn test_packed_matches_ascii() {
+    let (k, l) = (31, 8);
+    let seq = random_sequence(500, 7);
+    let storage = bitpack_fragment(&seq);
+    let ext = iteratorsyncmers2::SuperkmerExtractor::mspxor(k, l);
+    let uhs = iteratoruhs::SuperkmerExtractor::mspxor(k, l);
+    for j in 0..=seq.len() - k {
+        let kmer = &seq[j..j + k];
+        assert_eq!(ext.minimizer_of_packed(&storage, j), ext.minimizer_of(kmer));
+        assert_eq!(uhs.minimizer_of_packed(&storage, j), uhs.minimizer_of(kmer));
+        assert_eq!(iteratorkmc2::minimizer_of_packed(&storage, j, k, l), iteratorkmc2::minimizer_of(kmer, l));
+    }
+}
+
+#[test]
+fn test_canonical_mint_is_strand_independent() {
+    let (k, l) = (31, 8);
+    let seq = random_sequence(300, 5);
+    let ext = iteratorsyncmers2::SuperkmerExtractor::mspxor(k, l);
+    for kmer in seq.windows(k) {
+        assert_eq!(ext.minimizer_of(kmer).unwrap().0, ext.minimizer_of(&revcomp(kmer)).unwrap().0);
+        assert_eq!(iteratorkmc2::minimizer_of(kmer, l).0, iteratorkmc2::minimizer_of(&revcomp(kmer), l).0);
+    }
+}
+
+#[test]
+fn test_sticky_has_no_minimizer_of() {
+    let kmer = random_sequence(31, 3);
+    let storage = bitpack_fragment(&kmer);
+    let syncmers = iteratorsyncmers2::SuperkmerExtractor::new(31, 8);
+    assert_eq!(syncmers.minimizer_of(&kmer), None);
+    assert_eq!(syncmers.minimizer_of_packed(&storage, 0), None);
+    assert_eq!(iteratoruhs::SuperkmerExtractor::new(31, 8).minimizer_of(&kmer), None);
+    assert_eq!(iteratorkmc2::SuperkmerExtractor::new(31, 8).minimizer_of(&kmer), None);
+    assert_eq!(iteratorrandom::SuperkmerExtractor::new(31, 8).minimizer_of(&kmer), None);
+    assert_eq!(iteratormsp::SuperkmerExtractor::sticky(31, 8).minimizer_of(&kmer), None);
+}
@@ -410,3 +410,62 @@ fn test_scalar_known_values() {
     assert_eq!(bitpack_fragment_rc_scalar(&[b'A'; 32]), vec![0xFFFF_FFFF_FFFF_FFFF]);
     assert_eq!(bitpack_fragment_rc_scalar(&[b'T'; 32]), vec![0u64]);
 }
+
+// ---- minimizer-canonical orientation ----
+
+#[test]
+fn test_canonical_superkmer_strand_invariant() {
+    use rust_superkmers::orientation::canonical_superkmer;
+    let bases = [b'A', b'C', b'G', b'T'];
+    let mut rng = rand::rng();
+
+    for &l in &[8, 9] {
+        let mut fwd_ext = rust_superkmers::iteratorsyncmers2::SuperkmerExtractor::mspxor(31, l);
+        let mut rc_ext = rust_superkmers::iteratorsyncmers2::SuperkmerExtractor::mspxor(31, l);
+        let (mut total, mut matched) = (0, 0);
+        for _ in 0..50 {
+            let seq: Vec<u8> = (0..500).map(|_| bases[rng.random_range(0..4)]).collect();
+            let seq_rc = rc(&seq);
+            let fwd = fwd_ext.process(&seq);
+            let rev = rc_ext.process(&seq_rc);
+            for sk in fwd {
+                total += 1;
+                let mirror_start = seq.len() - sk.start - sk.size as usize;
+                if let Some(other) = rev.iter().find(|o| o.start == mirror_start && o.size == sk.size) {
+                    matched += 1;
+                    // Same minimizer value at two positions: each strand may pick a different one
+                    if other.mpos as usize != sk.size as usize - sk.mpos as usize - l {
+                        continue;
+                    }
+                    let a = canonical_superkmer(&seq, sk, l);
+                    let b = canonical_superkmer(&seq_rc, other, l);
+                    assert_eq!(a, b, "l={} fwd={:?} rc={:?}", l, sk, other);
+                    assert_eq!(a.bases.len(), (sk.size as usize).div_ceil(32));
+                }
+            }
+        }
+        // mspxor is context-independent, so nearly every superkmer has a mirror image
+        assert!(matched * 10 >= total * 9, "l={}: only {}/{} mirrored superkmers", l, matched, total);
+    }
+}
+
+#[test]
+fn test_canonical_superkmer_orientation_and_palindrome() {
+    use rust_superkmers::Superkmer;
+    use rust_superkmers::orientation::canonical_superkmer;
+
+    // Minimizer on the reverse strand: bases come out reverse-complemented
+    let seq = b"TTTTTTTTTTGGGGGGGGGCCCCC";
+    let sk = Superkmer { start: 2, mint: 0, size: 20, mpos: 3, mint_is_rc: true };
+    let c = canonical_superkmer(seq, &sk, 9);
+    assert_eq!(c.bases, bitpack_fragment(&rc(&seq[2..22])));
+    assert_eq!(c.mpos, 20 - 3 - 9);
+
+    // Palindromic 8-mer minimizer (AACCGGTT): both strands give the same result
+    let seq = b"GGGTCAACCGGTTCTTA";
+    let seq_rc = rc(seq);
+    let a = Superkmer { start: 0, mint: 0, size: 17, mpos: 5, mint_is_rc: false };
+    let b = Superkmer { start: 0, mint: 0, size: 17, mpos: 4, mint_is_rc: false };
+    assert_eq!(&seq_rc[4..12], b"AACCGGTT");
+    assert_eq!(canonical_superkmer(seq, &a, 8), canonical_superkmer(&seq_rc, &b, 8));
+}
@@ -0,0 +1,106 @@
+use rust_superkmers::tables::{canonical_lmer, is_palindrome, revcomp_lmer};
+use rust_superkmers::{iteratorsyncmers2, iteratoruhs, SplitMode, Superkmer};
+use rust_superkmers::metrics::random_sequence;
+
+/// Random reads with palindromic runs (ACGTACGT..., GAATTC...) spliced in.
+fn palindrome_rich_reads() -> Vec<Vec<u8>> {
+    let motifs: [&[u8]; 3] = [b"ACGTACGTACGT", b"GAATTCGAATTC", b"TTAATTAA"];
+    (0..30u64).map(|i| {
+        let mut read = random_sequence(150, i + 1);
+        for (j, motif) in motifs.iter().enumerate() {
+            let at = (i as usize * 17 + j * 41) % (read.len() - motif.len());
+            read[at..at + motif.len()].copy_from_slice(motif);
+        }
+        read
+    }).collect()
+}
+
+fn lmer_at(seq: &[u8], pos: usize, l: usize) -> usize {
+    seq[pos..pos + l].iter().fold(0, |acc, &b| (acc << 2) | b"ACGT".iter().position(|&c| c == b).unwrap())
+}
+
+#[test]
+fn test_is_palindrome() {
+    let v = |s: &[u8]| s.iter().fold(0, |acc, &b| (acc << 2) | b"ACGT".iter().position(|&c| c == b).unwrap());
+    assert!(is_palindrome(v(b"ACGT"), 4));
+    assert!(is_palindrome(v(b"GAATTC"), 6));
+    assert!(!is_palindrome(v(b"GAATTA"), 6));
+    assert!(!is_palindrome(v(b"ACG"), 3)); // odd l never is
+    for l in [2, 4, 8] {
+        let count = (0..1usize << (2 * l)).filter(|&x| is_palindrome(x, l)).count();
+        assert_eq!(count, 1 << l);
+        for x in (0..1usize << (2 * l)).filter(|&x| is_palindrome(x, l)) {
+            assert_eq!(canonical_lmer(x, l), (x as u32, false));
+            assert_eq!(revcomp_lmer(x, l), x);
+        }
+    }
+}
+
+#[test]
+fn test_palindromes_are_demoted_in_score_tables() {
+    for l in [8, 10] {
+        let base = iteratorsyncmers2::syncmer_mode_scores(l, 2, SplitMode::Sticky);
+        let palindromes: Vec<usize> = (0..1usize << (2 * l)).filter(|&x| is_palindrome(x, l)).collect();
+        assert!(palindromes.iter().all(|&x| base[x] == 1), "l={}", l);
+        let with_s = iteratorsyncmers2::generate_syncmer_scores_with_s(l, 3);
+        assert!(palindromes.iter().all(|&x| with_s[x] == 1), "l={} s=3", l);
+        let mspxor = iteratorsyncmers2::syncmer_mode_scores(l, 2, SplitMode::MspXor);
+        let best_palindrome = palindromes.iter().map(|&x| mspxor[x]).min().unwrap();
+        assert!((0..1usize << (2 * l)).any(|x| mspxor[x] < best_palindrome));
+    }
+    let uhs = iteratoruhs::uhs_mode_scores(8, SplitMode::Sticky);
+    assert!((0..1usize << 16).filter(|&x| is_palindrome(x, 8)).all(|x| uhs[x] == 1));
+}
+
+/// A palindrome is only chosen for a k-mer whose l-mers are all demoted.
+#[test]
+fn test_extractors_avoid_palindromes() {
+    let (k, l) = (31, 8);
+    let base = iteratorsyncmers2::syncmer_mode_scores(l, 2, SplitMode::Sticky);
+    let uhs_base = iteratoruhs::uhs_mode_scores(l, SplitMode::Sticky);
+    let mut chosen = 0;
+    for read in palindrome_rich_reads() {
+        let syncmer_sks: Vec<Superkmer> = iteratorsyncmers2::SuperkmerExtractor::mspxor(k, l).process(&read).to_vec();
+        let uhs_sks: Vec<Superkmer> = iteratoruhs::SuperkmerExtractor::mspxor(k, l).process(&read).to_vec();
+        for (sks, scores) in [(&syncmer_sks, &base[..]), (&uhs_sks, uhs_base)] {
+            for sk in sks {
+                let m = lmer_at(&read, sk.start + sk.mpos as usize, l);
+                if !is_palindrome(m, l) {
+                    continue;
+                }
+                chosen += 1;
+                assert!(!sk.mint_is_rc);
+                for j in sk.start..=sk.start + sk.size as usize - k {
+                    assert!((j..=j + k - l).all(|p| scores[canonical_lmer(lmer_at(&read, p, l), l).0 as usize] == 1),
+                        "palindrome chosen over a syncmer in k-mer at {}: {:?}", j, sk);
+                }
+            }
+        }
+    }
+    // Random flanks nearly always hold a syncmer, so palindromes should not be picked at all
+    assert_eq!(chosen, 0);
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "nightly"))]
+#[test]
+fn test_simd_kernels_follow_the_policy() {
+    if !is_x86_feature_detected!("avx2") {
+        return;
+    }
+    let (k, l) = (31, 8);
+    let mut syncmer_simd = rust_superkmers::syncmers_simd_l8k40max::SimdBatchExtractor::new(k, l);
+    let mut uhs_simd = rust_superkmers::uhs_simd_l8k40max::SimdBatchExtractor::new(k, l);
+    let mut syncmer_scalar = iteratorsyncmers2::SuperkmerExtractor::mspxor(k, l);
+    let mut uhs_scalar = iteratoruhs::SuperkmerExtractor::mspxor(k, l);
+    // Mostly palindromic reads, where the demoted l-mers have to be skipped
+    let mut reads = palindrome_rich_reads();
+    reads.push(b"ACGTACGTAC".repeat(15));
+    reads.push([&b"GAATTCGAATTC"[..], &random_sequence(20, 3), b"TTAATTAA"].concat().repeat(4));
+    for read in &reads {
+        let batch: [&[u8]; 8] = [read; 8];
+        let simd = unsafe { syncmer_simd.process_batch(&batch) }[0].clone();
+        assert_eq!(simd, syncmer_scalar.process(read));
+        let simd = unsafe { uhs_simd.process_batch(&batch) }[0].clone();
+        assert_eq!(simd, uhs_scalar.process(read));
+    }
+}
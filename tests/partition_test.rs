@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+use rust_superkmers::partition::{balance_partitions, hash_partition, PartitionMap};
+use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+use rust_superkmers::metrics::random_sequence;
+
+fn bucket_counts(seq: &[u8], k: usize, l: usize) -> HashMap<u32, u64> {
+    let mut counts = HashMap::new();
+    for sk in SuperkmerExtractor::mspxor(k, l).process(seq) {
+        *counts.entry(sk.mint).or_insert(0) += sk.size as u64 - k as u64 + 1;
+    }
+    counts
+}
+
+#[test]
+fn test_lpt_balances_real_buckets() {
+    let counts = bucket_counts(&random_sequence(200000, 1), 31, 8);
+    let total: u64 = counts.values().sum();
+    for p in [1, 4, 16, 64] {
+        let map = balance_partitions(&counts, p);
+        assert_eq!(map.loads().iter().sum::<u64>(), total);
+        // LPT bound: max load <= mean + largest bucket
+        let largest = *counts.values().max().unwrap();
+        assert!(*map.loads().iter().max().unwrap() <= total / p as u64 + largest);
+        assert!(map.imbalance() < 1.05, "p={} imbalance={}", p, map.imbalance());
+
+        let mut recomputed = vec![0u64; p];
+        for (&mint, &c) in &counts {
+            recomputed[map.partition_of(mint)] += c;
+        }
+        assert_eq!(recomputed, map.loads());
+    }
+}
+
+#[test]
+fn test_sample_assignment_routes_full_input() {
+    let (k, l) = (31, 8);
+    let seq = random_sequence(300000, 2);
+    // Build the map from the first 10% only, then route everything
+    let map = balance_partitions(&bucket_counts(&seq[..30000], k, l), 8);
+    let sks = SuperkmerExtractor::mspxor(k, l).process(&seq).to_vec();
+    let mut parts = vec![Vec::new(); 8];
+    map.route(&sks, &mut parts);
+    assert_eq!(parts.iter().map(|p| p.len()).sum::<usize>(), sks.len());
+    let loads: Vec<u64> = parts.iter().map(|p| p.iter().map(|sk| sk.size as u64 - k as u64 + 1).sum()).collect();
+    let mean = loads.iter().sum::<u64>() as f64 / 8.0;
+    assert!(*loads.iter().max().unwrap() as f64 / mean < 1.2, "{:?}", loads);
+    for (i, part) in parts.iter().enumerate() {
+        assert!(part.iter().all(|sk| map.partition_of(sk.mint) == i));
+    }
+}
+
+#[test]
+fn test_partition_map_roundtrip() {
+    let counts = bucket_counts(&random_sequence(20000, 3), 31, 8);
+    let map = balance_partitions(&counts, 5);
+    let mut bytes = Vec::new();
+    map.write(&mut bytes).unwrap();
+    let back = PartitionMap::read(&bytes[..]).unwrap();
+    assert_eq!(back, map);
+    assert_eq!(back.partition_of(u32::MAX), hash_partition(u32::MAX, 5));
+    assert!(PartitionMap::read(&bytes[..bytes.len() - 1]).is_err());
+    // A corrupt entry count fails on the truncated input instead of reserving it up front
+    let mut corrupt = bytes.clone();
+    corrupt[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(PartitionMap::read(&corrupt[..]).is_err());
+
+    let path = std::env::temp_dir().join("partition_map.skp");
+    map.save(&path).unwrap();
+    assert_eq!(PartitionMap::load(&path).unwrap(), map);
+}
@@ -0,0 +1,120 @@
+use rust_superkmers::iteratorrandom::{random_score, SuperkmerExtractor, SuperkmersIterator, DEFAULT_SEED};
+use rust_superkmers::scoretable::{ScoreTable, TableExtractor};
+use rust_superkmers::tables::canonical_lmer;
+use rust_superkmers::{SplitMode, Superkmer};
+use rust_superkmers::metrics::random_sequence;
+
+fn reads() -> Vec<Vec<u8>> {
+    (0..20u64).map(|i| {
+        let mut read = random_sequence(80 + 41 * i as usize, i + 11);
+        if i % 3 == 2 {
+            read[60] = b'N';
+        }
+        read
+    }).collect()
+}
+
+fn lmer_at(seq: &[u8], pos: usize, l: usize) -> usize {
+    seq[pos..pos + l].iter().fold(0, |acc, &b| (acc << 2) | b"ACGT".iter().position(|&c| c == b).unwrap())
+}
+
+type Ctors = (fn(&[u8], usize, usize) -> SuperkmersIterator, fn(usize, usize) -> SuperkmerExtractor, SplitMode, bool);
+
+const CTORS: [Ctors; 8] = [
+    (SuperkmersIterator::new_with_n, SuperkmerExtractor::new, SplitMode::Sticky, true),
+    (SuperkmersIterator::non_canonical_with_n, SuperkmerExtractor::non_canonical, SplitMode::Sticky, false),
+    (SuperkmersIterator::classical_with_n, SuperkmerExtractor::classical, SplitMode::Classical, true),
+    (SuperkmersIterator::classical_non_canonical_with_n, SuperkmerExtractor::classical_non_canonical, SplitMode::Classical, false),
+    (SuperkmersIterator::msp_with_n, SuperkmerExtractor::msp, SplitMode::Msp, true),
+    (SuperkmersIterator::msp_non_canonical_with_n, SuperkmerExtractor::msp_non_canonical, SplitMode::Msp, false),
+    (SuperkmersIterator::mspxor_with_n, SuperkmerExtractor::mspxor, SplitMode::MspXor, true),
+    (SuperkmersIterator::mspxor_non_canonical_with_n, SuperkmerExtractor::mspxor_non_canonical, SplitMode::MspXor, false),
+];
+
+#[test]
+fn test_iterator_matches_extractor_and_score_table() {
+    let (k, l) = (31, 10);
+    for (iter, ctor, mode, canonical) in CTORS {
+        let mut ext = ctor(k, l);
+        let mut table = TableExtractor::new(ScoreTable::random(l, mode, canonical, DEFAULT_SEED), k);
+        for read in reads() {
+            let sks: Vec<Superkmer> = iter(&read, k, l).collect();
+            assert_eq!(ext.process_with_n(&read), &sks[..], "{:?} canonical={}", mode, canonical);
+            assert_eq!(table.process_with_n(&read), &sks[..], "{:?} canonical={}", mode, canonical);
+            let covered: usize = sks.iter().map(|sk| sk.size as usize - k + 1).sum();
+            let expected: usize = read.split(|&b| b == b'N').map(|f| f.len().saturating_sub(k - 1)).sum();
+            assert_eq!(covered, expected);
+        }
+    }
+}
+
+/// Every k-mer gets the l-mer with the smallest hash of its canonical value.
+#[test]
+fn test_minimizer_is_smallest_hash() {
+    let (k, l) = (21, 7);
+    let seq = random_sequence(600, 5);
+    for (mode, seed) in [(SplitMode::Msp, DEFAULT_SEED), (SplitMode::Classical, DEFAULT_SEED), (SplitMode::Msp, 99)] {
+        let mut ext = match mode {
+            SplitMode::Msp => SuperkmerExtractor::msp(k, l),
+            _ => SuperkmerExtractor::classical(k, l),
+        }.with_seed(seed);
+        for sk in ext.process(&seq).to_vec() {
+            for j in sk.start..=sk.start + sk.size as usize - k {
+                let best = (j..=j + k - l).map(|p| random_score(canonical_lmer(lmer_at(&seq, p, l), l).0, seed)).min().unwrap();
+                let m = sk.start + sk.mpos as usize;
+                assert_eq!(random_score(sk.mint, seed), best);
+                assert_eq!(canonical_lmer(lmer_at(&seq, m, l), l).0, sk.mint);
+                assert!((j..=j + k - l).contains(&m));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_seeds_and_strands() {
+    let (k, l) = (31, 11);
+    let seq = random_sequence(2000, 8);
+    let default: Vec<Superkmer> = SuperkmerExtractor::mspxor(k, l).process(&seq).to_vec();
+    let explicit = SuperkmerExtractor::mspxor(k, l).with_seed(DEFAULT_SEED);
+    assert_eq!(explicit.seed(), DEFAULT_SEED);
+    assert_eq!(SuperkmerExtractor::mspxor(k, l).with_seed(DEFAULT_SEED).process(&seq), &default[..]);
+    assert_ne!(SuperkmerExtractor::mspxor(k, l).with_seed(1).process(&seq), &default[..]);
+    assert_ne!(SuperkmerExtractor::msp(k, l).process(&seq), &default[..]);
+
+    let ext = SuperkmerExtractor::mspxor(k, l).with_seed(3);
+    for kmer in seq.windows(k).step_by(7) {
+        let rc: Vec<u8> = kmer.iter().rev().map(|&b| match b { b'A' => b'T', b'C' => b'G', b'G' => b'C', _ => b'A' }).collect();
+        assert_eq!(ext.minimizer_of(kmer).unwrap().0, ext.minimizer_of(&rc).unwrap().0);
+    }
+}
+
+#[test]
+fn test_minimizer_of_agrees_with_extractor() {
+    let (k, l) = (31, 9);
+    for ctor in [SuperkmerExtractor::classical, SuperkmerExtractor::msp_non_canonical, SuperkmerExtractor::mspxor] {
+        let mut ext = ctor(k, l);
+        let query = ctor(k, l);
+        for read in reads() {
+            for sk in ext.process_with_n(&read).to_vec() {
+                for j in sk.start..=sk.start + sk.size as usize - k {
+                    let (mint, mpos, rc) = query.minimizer_of(&read[j..j + k]).unwrap();
+                    assert_eq!((mint, rc), (sk.mint, sk.mint_is_rc));
+                    assert_eq!(j + mpos as usize, sk.start + sk.mpos as usize);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sticky_superkmers_are_longer() {
+    let (k, l) = (31, 11);
+    let seq = random_sequence(20000, 4);
+    let sticky = SuperkmerExtractor::new(k, l).process(&seq).len();
+    let classical = SuperkmerExtractor::classical(k, l).process(&seq).len();
+    let msp = SuperkmerExtractor::msp(k, l).process(&seq).len();
+    assert!(sticky <= classical && sticky <= msp, "sticky={} classical={} msp={}", sticky, classical, msp);
+    // Density of random minimizers is about 2 / (w + 1)
+    let expected = 2.0 * (seq.len() - k + 1) as f64 / (k - l + 2) as f64;
+    assert!((msp as f64 - expected).abs() < 0.15 * expected, "msp={} expected={}", msp, expected);
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+use rust_superkmers::sampling::{is_sampled, sample_windows, BucketSample};
+use rust_superkmers::metrics::random_sequence;
+
+#[test]
+fn test_sampling_is_deterministic() {
+    let a = sample_windows(3, 10_000_000, 10_000, 31, 0.1, 7);
+    assert_eq!(a, sample_windows(3, 10_000_000, 10_000, 31, 0.1, 7));
+    assert_ne!(a, sample_windows(3, 10_000_000, 10_000, 31, 0.1, 8));
+    assert_ne!(a, sample_windows(4, 10_000_000, 10_000, 31, 0.1, 7));
+    assert!(a.len() > 80 && a.len() < 120, "{}", a.len());
+    assert!(a.iter().all(|&(s, e)| s % 10_000 == 0 && (e - s == 10_030 || e == 10_000_000)));
+
+    // Short sequences are single units; fraction 1 keeps everything
+    assert_eq!(sample_windows(0, 150, 10_000, 31, 1.0, 1), vec![(0, 150)]);
+    assert_eq!(sample_windows(0, 20, 10_000, 31, 1.0, 1), vec![]);
+    // Windows overlap by k-1, so the k-mers across a window boundary are kept
+    assert_eq!(sample_windows(0, 25_000, 10_000, 31, 1.0, 1), vec![(0, 10_030), (10_000, 20_030), (20_000, 25_000)]);
+    assert_eq!(sample_windows(0, 20_030, 10_000, 31, 1.0, 1), vec![(0, 10_030), (10_000, 20_030)]);
+    let kmers: usize = sample_windows(0, 25_000, 10_000, 31, 1.0, 1).iter().map(|&(s, e)| e - s - 30).sum();
+    assert_eq!(kmers, 25_000 - 30);
+    let kept = (0..10_000).filter(|&i| is_sampled(i, 0, 0.25, 99)).count();
+    assert!((kept as f64 / 10_000.0 - 0.25).abs() < 0.02);
+}
+
+#[test]
+fn test_estimates_cover_full_input() {
+    let (k, l) = (31, 8);
+    let genome = random_sequence(3_000_000, 1);
+    let mut ext = SuperkmerExtractor::mspxor(k, l);
+
+    let mut full: HashMap<u32, u64> = HashMap::new();
+    for sk in ext.process(&genome) {
+        *full.entry(sk.mint).or_insert(0) += sk.size as u64 - k as u64 + 1;
+    }
+    let mut sizes: Vec<u64> = full.values().copied().collect();
+    sizes.sort_unstable();
+    let true_p99 = sizes[sizes.len() * 99 / 100] as f64;
+
+    let fraction = 0.2;
+    let mut sample = BucketSample::new(fraction);
+    sample.set_input_kmers((genome.len() - k + 1) as u64);
+    for (start, end) in sample_windows(0, genome.len(), 2_000, k, fraction, 5) {
+        sample.add_unit(ext.process(&genome[start..end]), k);
+    }
+    let est = sample.estimate(50, 5);
+    assert_eq!(est, sample.estimate(50, 5));
+    assert_eq!(est.units, sample.num_units());
+    assert!(est.p99.low <= est.p99.estimate && est.p99.estimate <= est.p99.high);
+    assert!(est.p99.low < est.p99.high);
+    // Extrapolated P99 lands near the full-input value
+    assert!((est.p99.estimate / true_p99 - 1.0).abs() < 0.1, "est {:?} true {}", est.p99, true_p99);
+    assert!(est.p99.low <= true_p99 && true_p99 <= est.p99.high, "est {:?} true {}", est.p99, true_p99);
+    assert!(est.max_over_mean.estimate > 1.0);
+}
@@ -0,0 +1,130 @@
+use rust_superkmers::scoretable::{read_rank_array, read_ranked_lmers, ScoreTable, TableExtractor};
+use rust_superkmers::{iteratorkmc2, iteratorsyncmers2, iteratoruhs, SplitMode, Superkmer};
+use rust_superkmers::metrics::random_sequence;
+
+fn reads() -> Vec<Vec<u8>> {
+    (0..20u64).map(|i| {
+        let mut read = random_sequence(100 + 53 * i as usize, i + 1);
+        if i % 4 == 1 {
+            read[40] = b'N';
+        }
+        read
+    }).collect()
+}
+
+const MODES: [SplitMode; 4] = [SplitMode::Sticky, SplitMode::Classical, SplitMode::Msp, SplitMode::MspXor];
+
+#[test]
+fn test_file_roundtrip() {
+    let table = ScoreTable::syncmer(9, 2, SplitMode::Msp, false);
+    let path = std::env::temp_dir().join("scoretable_roundtrip.sks");
+    table.save(&path).unwrap();
+    let loaded = ScoreTable::load(&path).unwrap();
+    assert_eq!(loaded, table);
+    assert_eq!(loaded.checksum(), table.checksum());
+    assert_ne!(table.checksum(), ScoreTable::syncmer(9, 2, SplitMode::Msp, true).checksum());
+}
+
+#[test]
+fn test_corrupt_files_are_rejected() {
+    let mut bytes = Vec::new();
+    ScoreTable::uhs(7, SplitMode::MspXor, true).write(&mut bytes).unwrap();
+    assert!(ScoreTable::read(&bytes[..]).is_ok());
+
+    let mut flipped = bytes.clone();
+    flipped[100] ^= 1;
+    let err = ScoreTable::read(&flipped[..]).unwrap_err();
+    assert!(err.to_string().contains("checksum"), "{}", err);
+
+    assert!(ScoreTable::read(&bytes[..bytes.len() - 3]).is_err());
+    let mut magic = bytes.clone();
+    magic[0] = b'X';
+    assert!(ScoreTable::read(&magic[..]).is_err());
+    let mut version = bytes.clone();
+    version[8] = 9;
+    assert!(ScoreTable::read(&version[..]).is_err());
+}
+
+#[test]
+fn test_syncmer_table_matches_extractor() {
+    let (k, l) = (31, 8);
+    for (mode, canonical) in MODES.iter().flat_map(|&m| [(m, true), (m, false)]) {
+        let mut ext = match (mode, canonical) {
+            (SplitMode::Sticky, true) => iteratorsyncmers2::SuperkmerExtractor::new(k, l),
+            (SplitMode::Sticky, false) => iteratorsyncmers2::SuperkmerExtractor::non_canonical(k, l),
+            (SplitMode::Classical, true) => iteratorsyncmers2::SuperkmerExtractor::classical(k, l),
+            (SplitMode::Classical, false) => iteratorsyncmers2::SuperkmerExtractor::classical_non_canonical(k, l),
+            (SplitMode::Msp, true) => iteratorsyncmers2::SuperkmerExtractor::msp(k, l),
+            (SplitMode::Msp, false) => iteratorsyncmers2::SuperkmerExtractor::msp_non_canonical(k, l),
+            (SplitMode::MspXor, true) => iteratorsyncmers2::SuperkmerExtractor::mspxor(k, l),
+            (SplitMode::MspXor, false) => iteratorsyncmers2::SuperkmerExtractor::mspxor_non_canonical(k, l),
+        };
+        let table = ScoreTable::syncmer(l, 2, mode, canonical);
+        let mut from_table = TableExtractor::new(table.clone(), k);
+        for read in reads() {
+            let expected = ext.process_with_n(&read).to_vec();
+            assert_eq!(from_table.process_with_n(&read), &expected[..], "{:?} canonical={}", mode, canonical);
+            assert_eq!(table.superkmers_with_n(&read, k), expected);
+        }
+    }
+}
+
+#[test]
+fn test_uhs_and_kmc2_tables_match_iterators() {
+    let (k, l) = (31, 8);
+    let mut uhs = iteratoruhs::SuperkmerExtractor::mspxor(k, l);
+    let mut uhs_table = TableExtractor::new(ScoreTable::uhs(l, SplitMode::MspXor, true), k);
+    let mut kmc2_table = TableExtractor::new(ScoreTable::kmc2(l, SplitMode::Sticky, true), k);
+    for read in reads() {
+        assert_eq!(uhs_table.process_with_n(&read), uhs.process_with_n(&read));
+        // The KMC2 iterator does not report mint_is_rc
+        let from_table: Vec<Superkmer> = kmc2_table.process_with_n(&read).iter().map(|sk| Superkmer { mint_is_rc: false, ..*sk }).collect();
+        let expected: Vec<Superkmer> = iteratorkmc2::SuperkmersIterator::new_with_n(&read, k, l).collect();
+        assert_eq!(from_table, expected);
+    }
+}
+
+#[test]
+fn test_ranked_list() {
+    let text = "# order from some tool\nCCG 0.5\n\nACG\nccg\nTTT extra fields\n";
+    let (l, ranks) = read_ranked_lmers(text.as_bytes()).unwrap();
+    assert_eq!(l, 3);
+    assert_eq!(ranks.len(), 64);
+    let v = |s: &[u8]| s.iter().fold(0, |acc, &b| (acc << 2) | b"ACGT".iter().position(|&c| c == b).unwrap());
+    assert_eq!(ranks[v(b"CCG")], 0);
+    assert_eq!(ranks[v(b"ACG")], 1);
+    assert_eq!(ranks[v(b"TTT")], 3);
+    assert_eq!(ranks[v(b"AAA")], 4);
+
+    let table = ScoreTable::from_ranks("ranked", l, SplitMode::Msp, true, &ranks).unwrap();
+    // CGG is the reverse complement of CCG, AAA of TTT
+    assert_eq!(table.scores[v(b"CGG")], 0);
+    assert_eq!(table.scores[v(b"AAA")], 3);
+    assert_eq!(table.scores[v(b"CGT")], 1);
+    let forward = ScoreTable::from_ranks("ranked", l, SplitMode::Msp, false, &ranks).unwrap();
+    assert_eq!(forward.scores[v(b"CGG")], 4);
+
+    assert!(read_ranked_lmers("ACG\nACGT\n".as_bytes()).is_err());
+    assert!(read_ranked_lmers("ACN\n".as_bytes()).is_err());
+    assert!(read_ranked_lmers("# nothing\n".as_bytes()).is_err());
+}
+
+#[test]
+fn test_rank_array_gives_lexicographic_minimizers() {
+    let (k, l) = (21, 6);
+    let bytes: Vec<u8> = (0..1u64 << (2 * l)).flat_map(|r| r.to_le_bytes()).collect();
+    let ranks = read_rank_array(&bytes[..], l).unwrap();
+    let table = ScoreTable::from_ranks("lexicographic", l, SplitMode::Msp, false, &ranks).unwrap();
+    let seq = random_sequence(500, 9);
+    let mut ext = TableExtractor::new(table, k);
+    for sk in ext.process(&seq) {
+        let code = |b: u8| b"ACGT".iter().position(|&c| c == b).unwrap() as u32;
+        let lmer = |i: usize| seq[i..i + l].iter().fold(0, |acc, &b| (acc << 2) | code(b));
+        for j in sk.start..=sk.start + sk.size as usize - k {
+            assert_eq!((j..=j + k - l).map(lmer).min().unwrap(), sk.mint);
+        }
+    }
+    assert!(read_rank_array(&bytes[..100], l).is_err());
+    assert!(read_rank_array(&bytes[..], 0).is_err());
+    assert!(read_rank_array(&bytes[..], 40).is_err());
+}
@@ -0,0 +1,110 @@
+use rust_superkmers::sketch::{Sketch, SketchKind};
+use rust_superkmers::metrics::random_sequence;
+
+fn rc(seq: &[u8]) -> Vec<u8> {
+    seq.iter().rev().map(|&b| match b { b'A' => b'T', b'C' => b'G', b'G' => b'C', _ => b'A' }).collect()
+}
+
+fn sketch(kind: SketchKind, scaled: u64, seqs: &[&[u8]]) -> Sketch {
+    let mut s = Sketch::new(kind, 31, 9, scaled);
+    for seq in seqs {
+        s.add_sequence(seq);
+    }
+    s
+}
+
+#[test]
+fn test_jaccard_and_containment_estimates() {
+    // A = X+Y, B = Y+Z: J(A, B) = 1/3, C(A in B) = 1/2
+    let (x, y, z) = (random_sequence(40000, 1), random_sequence(40000, 2), random_sequence(40000, 3));
+    let a = [&x[..], &y].concat();
+    let b = [&y[..], &z].concat();
+    let sa = sketch(SketchKind::Kmers, 10, &[&a]);
+    let sb = sketch(SketchKind::Kmers, 10, &[&b]);
+    assert!((sa.jaccard(&sb) - 1.0 / 3.0).abs() < 0.03, "J={}", sa.jaccard(&sb));
+    assert!((sa.containment(&sb) - 0.5).abs() < 0.03, "C={}", sa.containment(&sb));
+    assert_eq!(sketch(SketchKind::Kmers, 10, &[&y]).containment(&sa), 1.0);
+    assert_eq!(sa.jaccard(&sa), 1.0);
+
+    // Minimizer values collide by chance between unrelated sequences (4^l values),
+    // so only the ordering is meaningful
+    let ma = sketch(SketchKind::Minimizers, 1, &[&a]);
+    let mb = sketch(SketchKind::Minimizers, 1, &[&b]);
+    let unrelated = sketch(SketchKind::Minimizers, 1, &[&random_sequence(80000, 7)]);
+    assert!(ma.jaccard(&mb) > ma.jaccard(&unrelated) + 0.1);
+    assert_eq!(sketch(SketchKind::Minimizers, 1, &[&y]).containment(&ma), 1.0);
+    let sa = sketch(SketchKind::Kmers, 10, &[&a]);
+    assert!((sa.cardinality() / 80000.0 - 1.0).abs() < 0.05);
+}
+
+#[test]
+fn test_sketch_is_strand_and_split_invariant() {
+    let seq = random_sequence(20000, 4);
+    for kind in [SketchKind::Kmers, SketchKind::Minimizers] {
+        let fwd = sketch(kind, 5, &[&seq]);
+        assert_eq!(fwd, sketch(kind, 5, &[&rc(&seq)]));
+        // N breaks k-mers but the sketch of the pieces is a subset
+        let mut with_n = seq.clone();
+        with_n[10000] = b'N';
+        let broken = sketch(kind, 5, &[&with_n]);
+        assert_eq!(broken.containment(&fwd), 1.0);
+        assert!(broken.len() <= fwd.len());
+    }
+}
+
+#[test]
+fn test_downsample_and_mixed_scaled() {
+    let seq = random_sequence(50000, 5);
+    let fine = sketch(SketchKind::Kmers, 2, &[&seq[..30000]]);
+    let coarse = sketch(SketchKind::Kmers, 20, &[&seq[10000..]]);
+    assert_eq!(fine.downsample(20), sketch(SketchKind::Kmers, 20, &[&seq[..30000]]));
+    assert_eq!(fine.jaccard(&coarse), fine.downsample(20).jaccard(&coarse));
+    assert!((fine.jaccard(&coarse) - 0.4).abs() < 0.08);
+}
+
+#[test]
+fn test_serialization_roundtrip() {
+    let seq = random_sequence(10000, 6);
+    let mut s = sketch(SketchKind::Minimizers, 3, &[&seq]);
+    s.name = "sample.fa".to_string();
+    let mut bytes = Vec::new();
+    s.write(&mut bytes).unwrap();
+    assert_eq!(Sketch::read(&bytes[..]).unwrap(), s);
+    assert!(Sketch::read(&bytes[..20]).is_err());
+    // A corrupt name length fails on the short input instead of allocating it
+    let mut corrupt = bytes.clone();
+    corrupt[29..33].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(Sketch::read(&corrupt[..]).is_err());
+    bytes[0] = b'X';
+    assert!(Sketch::read(&bytes[..]).is_err());
+
+    let dir = std::env::temp_dir();
+    let fasta = dir.join("sketch_input.fa");
+    let text = format!(">a\n{}\n>b\n{}\n", String::from_utf8_lossy(&seq[..5000]), String::from_utf8_lossy(&seq[5000..]));
+    std::fs::write(&fasta, text).unwrap();
+    let from_file = Sketch::from_fasta(&fasta, SketchKind::Kmers, 31, 9, 3).unwrap();
+    assert_eq!(from_file.len(), sketch(SketchKind::Kmers, 3, &[&seq[..5000], &seq[5000..]]).len());
+    let path = dir.join("sketch_input.sksketch");
+    from_file.save(&path).unwrap();
+    assert_eq!(Sketch::load(&path).unwrap(), from_file);
+}
+
+#[test]
+fn test_read_rejects_kmer_sketch_k_out_of_range() {
+    let s = sketch(SketchKind::Kmers, 3, &[&random_sequence(2000, 7)]);
+    let mut bytes = Vec::new();
+    s.write(&mut bytes).unwrap();
+    assert_eq!(Sketch::read(&bytes[..]).unwrap(), s);
+    for k in [0u32, 64, u32::MAX] {
+        let mut corrupt = bytes.clone();
+        corrupt[13..17].copy_from_slice(&k.to_le_bytes());
+        let err = Sketch::read(&corrupt[..]).expect_err("out-of-range k accepted");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+#[should_panic(expected = "1 <= k <= 63")]
+fn test_new_rejects_zero_k() {
+    Sketch::new(SketchKind::Kmers, 0, 9, 1);
+}
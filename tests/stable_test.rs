@@ -0,0 +1,90 @@
+//! Fingerprints of the core extraction path on the 100 kb E. coli sample. The same
+//! values must come out of the stable build and of `--features nightly`.
+use rust_superkmers::{iteratorkmc2, iteratorsyncmers2, iteratoruhs, Superkmer};
+
+fn read_fasta_seq(path: &str) -> Vec<u8> {
+    std::fs::read_to_string(path)
+        .expect("Failed to read test genome file")
+        .split("\n").collect::<Vec<&str>>()[1]
+        .as_bytes().to_vec()
+}
+
+/// 150 bp reads tiling the genome, as a sequencer would produce them.
+fn reads() -> Vec<Vec<u8>> {
+    read_fasta_seq("tests/ecoli.genome.100k.fa").chunks(150).filter(|r| r.len() == 150).map(|r| r.to_vec()).collect()
+}
+
+/// FNV-1a over every field of every superkmer, read boundaries included.
+fn fingerprint(per_read: impl Iterator<Item = Vec<Superkmer>>) -> u64 {
+    let mut h = 0xcbf2_9ce4_8422_2325u64;
+    let mut eat = |x: u64| {
+        for b in x.to_le_bytes() {
+            h = (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    };
+    for sks in per_read {
+        eat(sks.len() as u64);
+        for sk in sks {
+            eat(sk.start as u64);
+            eat(sk.mint as u64);
+            eat(((sk.size as u64) << 17) | ((sk.mpos as u64) << 1) | sk.mint_is_rc as u64);
+        }
+    }
+    h
+}
+
+type Ctor<E> = fn(usize, usize) -> E;
+
+#[test]
+fn test_syncmers2_fingerprints() {
+    let (k, l) = (31, 8);
+    let ctors: [(&str, Ctor<iteratorsyncmers2::SuperkmerExtractor>, u64); 8] = [
+        ("sticky", iteratorsyncmers2::SuperkmerExtractor::new, 0xe460_3c50_404c_b999),
+        ("sticky-nc", iteratorsyncmers2::SuperkmerExtractor::non_canonical, 0x208a_c042_5dc2_fea6),
+        ("classical", iteratorsyncmers2::SuperkmerExtractor::classical, 0x4094_c118_fcc6_7080),
+        ("classical-nc", iteratorsyncmers2::SuperkmerExtractor::classical_non_canonical, 0xcb95_c0b4_30d7_d1cb),
+        ("msp", iteratorsyncmers2::SuperkmerExtractor::msp, 0x2b4a_5a3a_741a_ef20),
+        ("msp-nc", iteratorsyncmers2::SuperkmerExtractor::msp_non_canonical, 0x3611_5f17_42a0_a683),
+        ("mspxor", iteratorsyncmers2::SuperkmerExtractor::mspxor, 0x5e06_dd77_25ae_c737),
+        ("mspxor-nc", iteratorsyncmers2::SuperkmerExtractor::mspxor_non_canonical, 0x2549_1412_bd7c_48c3),
+    ];
+    let reads = reads();
+    for (name, ctor, expected) in ctors {
+        let mut ext = ctor(k, l);
+        let got = fingerprint(reads.iter().map(|r| ext.process(r).to_vec()));
+        assert_eq!(got, expected, "syncmers2 {}: {:#018x}", name, got);
+    }
+}
+
+#[test]
+fn test_uhs_and_kmc2_fingerprints() {
+    let (k, l) = (31, 8);
+    let reads = reads();
+    let mut uhs = iteratoruhs::SuperkmerExtractor::mspxor(k, l);
+    let got = fingerprint(reads.iter().map(|r| uhs.process(r).to_vec()));
+    assert_eq!(got, 0xb5fc_d6f6_5338_52e5, "uhs mspxor: {:#018x}", got);
+    let got = fingerprint(reads.iter().map(|r| iteratorkmc2::SuperkmersIterator::new(r, k, l).collect()));
+    assert_eq!(got, 0x15e5_60bf_9242_2735, "kmc2: {:#018x}", got);
+}
+
+/// The nightly batch kernels reproduce the scalar fingerprints.
+#[cfg(all(target_arch = "x86_64", feature = "nightly"))]
+#[test]
+fn test_simd_kernels_match_scalar_fingerprints() {
+    if !is_x86_feature_detected!("avx2") {
+        return;
+    }
+    let (k, l) = (31, 8);
+    let reads = reads();
+    let batches = || reads.chunks_exact(8).map(|c| std::array::from_fn::<&[u8], 8, _>(|i| &c[i][..]));
+
+    let mut syncmer_simd = rust_superkmers::syncmers_simd_l8k40max::SimdBatchExtractor::new(k, l);
+    let simd = fingerprint(batches().flat_map(|b| unsafe { syncmer_simd.process_batch(&b) }.clone()));
+    let mut scalar = iteratorsyncmers2::SuperkmerExtractor::mspxor(k, l);
+    assert_eq!(simd, fingerprint(batches().flatten().map(|r| scalar.process(r).to_vec())));
+
+    let mut uhs_simd = rust_superkmers::uhs_simd_l8k40max::SimdBatchExtractor::new(k, l);
+    let simd = fingerprint(batches().flat_map(|b| unsafe { uhs_simd.process_batch(&b) }.clone()));
+    let mut scalar = iteratoruhs::SuperkmerExtractor::mspxor(k, l);
+    assert_eq!(simd, fingerprint(batches().flatten().map(|r| scalar.process(r).to_vec())));
+}
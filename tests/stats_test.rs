@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+use rust_superkmers::stats::{decode_mint, BucketStats, HistogramBin};
+use rust_superkmers::metrics::random_sequence;
+
+#[test]
+fn test_stats_from_counts() {
+    let counts: HashMap<u32, u64> = (0..100u32).map(|m| (m, m as u64 + 1)).collect();
+    let stats = BucketStats::from_counts(&counts, 5050, 1000, 31, 8, 3);
+    assert_eq!((stats.min, stats.median, stats.p99, stats.max), (1, 51, 100, 100));
+    assert_eq!(stats.mean, 50.5);
+    assert_eq!(stats.top, vec![(99, 100), (98, 99), (97, 98)]);
+    assert_eq!(stats.histogram[0], HistogramBin { lo: 1, hi: 1, minimizers: 1, kmers: 1 });
+    assert_eq!(stats.histogram.last().unwrap(), &HistogramBin { lo: 64, hi: 127, minimizers: 37, kmers: (64..=100).sum() });
+    assert_eq!(stats.histogram.iter().map(|b| b.kmers).sum::<u64>(), 5050);
+    assert!((stats.density() - 1000.0 / 5050.0).abs() < 1e-12);
+    assert!((stats.mean_superkmer_len() - (5.05 + 30.0)).abs() < 1e-9);
+    assert_eq!(decode_mint(0b00_01_10_11, 4), "ACGT");
+
+    let empty = BucketStats::from_counts(&HashMap::new(), 0, 0, 31, 8, 20);
+    assert!(empty.is_empty());
+    assert_eq!(empty.max_over_mean(), 0.0);
+    assert!(empty.to_json().ends_with("\"histogram\":[],\"top\":[]}"));
+}
+
+#[test]
+fn test_json_and_tsv_output() {
+    let (k, l) = (31, 8);
+    let seq = random_sequence(50_000, 3);
+    let mut ext = SuperkmerExtractor::mspxor(k, l);
+    let sks = ext.process(&seq);
+    let mut counts: HashMap<u32, u64> = HashMap::new();
+    for sk in sks {
+        *counts.entry(sk.mint).or_insert(0) += sk.size as u64 - k as u64 + 1;
+    }
+    let total_kmers = (seq.len() - k + 1) as u64;
+    let stats = BucketStats::from_counts(&counts, total_kmers, sks.len() as u64, k, l, 5).with_label("syncmer:\"mspxor\"");
+    // Superkmers of a random sequence hold fewer than k k-mers on average
+    assert!(stats.mean_superkmer_len() > k as f64 && stats.mean_superkmer_len() < 2.0 * k as f64);
+
+    let json = stats.to_json();
+    assert!(json.starts_with("{\"label\":\"syncmer:\\\"mspxor\\\"\",\"k\":31,\"l\":8,"));
+    assert!(json.contains(&format!("\"total_kmers\":{},", total_kmers)));
+    assert!(json.contains(&format!("\"p99\":{},", stats.p99)));
+    assert_eq!(json.matches("\"mint\":").count(), 5);
+    assert_eq!(json.matches("\"lo\":").count(), stats.histogram.len());
+    assert!(!json.contains('\n') && !json.contains("NaN"));
+    assert_eq!(json.matches('{').count(), json.matches('}').count());
+
+    let header = BucketStats::tsv_header();
+    let row = stats.to_tsv();
+    let columns: Vec<&str> = header.split('\t').collect();
+    let fields: Vec<&str> = row.split('\t').collect();
+    assert_eq!(columns.len(), fields.len());
+    let field = |name: &str| fields[columns.iter().position(|&c| c == name).unwrap()];
+    assert_eq!(field("label"), "syncmer:\"mspxor\"");
+    assert_eq!(field("max"), stats.max.to_string());
+    assert_eq!(field("top_kmers"), stats.max.to_string());
+    assert_eq!(field("total_superkmers"), sks.len().to_string());
+}
@@ -0,0 +1,75 @@
+use rust_superkmers::Superkmer;
+use rust_superkmers::iteratorsyncmers2::{StreamingExtractor, SuperkmerExtractor};
+use rust_superkmers::metrics::random_sequence;
+
+/// Feed `seq` in pieces whose sizes cycle through `piece_sizes`, then finish.
+fn stream(ext: &mut StreamingExtractor, seq: &[u8], piece_sizes: &[usize]) -> Vec<Superkmer> {
+    let mut out = Vec::new();
+    let mut pos = 0;
+    let mut i = 0;
+    while pos < seq.len() {
+        let end = std::cmp::min(pos + piece_sizes[i % piece_sizes.len()], seq.len());
+        out.extend_from_slice(ext.feed(&seq[pos..end]));
+        assert_eq!(ext.position(), end);
+        pos = end;
+        i += 1;
+    }
+    out.extend_from_slice(ext.finish());
+    out
+}
+
+macro_rules! check_all_modes {
+    ($seq:expr, $k:expr, $l:expr, $pieces:expr) => {
+        for (name, mut one_shot, mut streaming) in [
+            ("sticky", SuperkmerExtractor::new($k, $l), StreamingExtractor::new($k, $l)),
+            ("sticky_nc", SuperkmerExtractor::non_canonical($k, $l), StreamingExtractor::non_canonical($k, $l)),
+            ("classical", SuperkmerExtractor::classical($k, $l), StreamingExtractor::classical($k, $l)),
+            ("msp", SuperkmerExtractor::msp($k, $l), StreamingExtractor::msp($k, $l)),
+            ("mspxor", SuperkmerExtractor::mspxor($k, $l), StreamingExtractor::mspxor($k, $l)),
+            ("mspxor_nc", SuperkmerExtractor::mspxor_non_canonical($k, $l), StreamingExtractor::mspxor_non_canonical($k, $l)),
+        ] {
+            let expected = one_shot.process_with_n(&$seq).to_vec();
+            let got = stream(&mut streaming, &$seq, $pieces);
+            assert_eq!(got, expected, "{} pieces={:?}", name, $pieces);
+        }
+    };
+}
+
+#[test]
+fn test_streaming_matches_one_shot() {
+    for seed in 0..10 {
+        let seq = random_sequence(2000, seed);
+        for pieces in [&[1usize][..], &[7], &[30, 31, 32], &[150], &[5000]] {
+            check_all_modes!(seq, 31, 8, pieces);
+        }
+    }
+}
+
+#[test]
+fn test_streaming_with_n_and_lowercase() {
+    let mut seq = random_sequence(3000, 77);
+    seq[100..130].fill(b'N');
+    seq[700] = b'n';
+    seq[1500..1520].fill(b'N');
+    seq[2990..].fill(b'N');
+    seq[200..900].make_ascii_lowercase();
+    for pieces in [&[1usize][..], &[13, 64], &[300]] {
+        check_all_modes!(seq, 31, 8, pieces);
+        check_all_modes!(seq, 21, 9, pieces);
+    }
+}
+
+#[test]
+fn test_streaming_short_and_reuse() {
+    let mut ext = StreamingExtractor::mspxor(31, 8);
+    assert!(ext.feed(&random_sequence(20, 1)).is_empty());
+    assert!(ext.finish().is_empty());
+    assert_eq!(ext.position(), 0);
+
+    // Exactly k bases: one superkmer, only emitted at finish
+    let seq = random_sequence(31, 2);
+    assert!(ext.feed(&seq).is_empty());
+    let sks = ext.finish().to_vec();
+    assert_eq!(sks, SuperkmerExtractor::mspxor(31, 8).process(&seq).to_vec());
+    assert_eq!(sks.len(), 1);
+}
@@ -0,0 +1,65 @@
+use rust_superkmers::tables::{canonical_lmer, canonical_table, memory_footprint, revcomp_lmer, total_bytes, unpack_canonical};
+use rust_superkmers::iteratorsyncmers2::SuperkmerExtractor;
+
+fn naive_revcomp(val: usize, l: usize) -> usize {
+    let mut rc = 0;
+    let mut v = val;
+    for _ in 0..l {
+        rc = (rc << 2) | (3 - (v & 3));
+        v >>= 2;
+    }
+    rc
+}
+
+#[test]
+fn test_revcomp_matches_naive() {
+    let mut x = 1u64;
+    for l in 1..=32 {
+        let mask = if l == 32 { usize::MAX } else { (1usize << (2 * l)) - 1 };
+        for _ in 0..1000 {
+            x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let val = x as usize & mask;
+            assert_eq!(revcomp_lmer(val, l), naive_revcomp(val, l), "l={} val={:#x}", l, val);
+        }
+    }
+}
+
+#[test]
+fn test_canonical_table_is_packed() {
+    for l in [7, 8, 9] {
+        let table = canonical_table(l);
+        assert_eq!(table.len(), 1 << (2 * l));
+        for (fwd, &entry) in table.iter().enumerate() {
+            let rc = naive_revcomp(fwd, l);
+            let expected = if rc < fwd { (rc as u32, true) } else { (fwd as u32, false) };
+            assert_eq!(unpack_canonical(entry), expected);
+            assert_eq!(canonical_lmer(fwd, l), expected);
+        }
+    }
+}
+
+#[test]
+fn test_footprint_records_generated_tables() {
+    SuperkmerExtractor::mspxor(31, 9).process(b"ACGTACGTTGCAGGCATTACGGACTTAGCATTGCA");
+    canonical_table(10);
+    let tables = memory_footprint();
+    let find = |name: &str, l: usize| tables.iter().find(|t| t.name == name && t.l == l).map(|t| t.bytes);
+    assert_eq!(find("syncmer-mspxor", 9), Some(4 << 18));
+    assert_eq!(find("syncmer", 9), Some(4 << 18));
+    assert_eq!(find("canonical", 10), Some(4 << 20));
+    // Tables are generated once per (scheme, l)
+    canonical_table(10);
+    assert_eq!(memory_footprint().iter().filter(|t| t.name == "canonical" && t.l == 10).count(), 1);
+    assert!(total_bytes() >= (4 << 20) + (8 << 18));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_deprecated_canonical_tables() {
+    let old = rust_superkmers::minimizer_core::canonical_table(8);
+    assert_eq!(old, &rust_superkmers::generate_canonical_table::<8>()[..]);
+    assert_eq!(old, &rust_superkmers::CANONICAL_8[..]);
+    for (val, &entry) in canonical_table(8).iter().enumerate() {
+        assert_eq!(old[val], unpack_canonical(entry));
+    }
+}
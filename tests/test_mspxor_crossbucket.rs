@@ -6,14 +6,8 @@
 ///
 /// Run:  cargo +nightly test test_mspxor_crossbucket -- --nocapture
 
-use std::collections::HashMap;
-
-fn canonical(kmer: &[u8]) -> Vec<u8> {
-    let rc: Vec<u8> = kmer.iter().rev().map(|&b| match b {
-        b'A' => b'T', b'T' => b'A', b'C' => b'G', b'G' => b'C', _ => b
-    }).collect();
-    if kmer <= &rc[..] { kmer.to_vec() } else { rc }
-}
+use rust_superkmers::crossbucket::CrossBucketChecker;
+use rust_superkmers::Superkmer;
 
 fn load_reads() -> Vec<Vec<u8>> {
     let content = std::fs::read_to_string("tests/read50x_ref10K_e001.fa")
@@ -26,39 +20,23 @@ fn load_reads() -> Vec<Vec<u8>> {
 
 fn check_crossbucket<F>(name: &str, reads: &[Vec<u8>], k: usize, mut process: F)
 where
-    F: FnMut(&[u8]) -> Vec<(usize, u16, u32)>, // returns (start, size, mint) per superkmer
+    F: FnMut(&[u8]) -> Vec<Superkmer>,
 {
-    let mut kmer_mints: HashMap<Vec<u8>, Vec<u32>> = HashMap::new();
-
+    let mut checker = CrossBucketChecker::new(k);
     for read in reads {
-        if read.len() < k { continue; }
-        for (start, size, mint) in process(read) {
-            let n_kmers = size as usize - k + 1;
-            for i in 0..n_kmers {
-                let kmer = &read[start + i..start + i + k];
-                let can = canonical(kmer);
-                let entry = kmer_mints.entry(can).or_default();
-                if !entry.contains(&mint) {
-                    entry.push(mint);
-                }
-            }
-        }
+        checker.add_sequence(read, &mut process);
     }
+    let report = checker.report(5);
 
-    let total = kmer_mints.len();
-    let multi_bucket: Vec<_> = kmer_mints.iter()
-        .filter(|(_, mints)| mints.len() > 1)
-        .collect();
-
-    println!("{}: {} distinct canonical k-mers, {} cross-bucket", name, total, multi_bucket.len());
+    println!("{}: {} distinct canonical k-mers, {} cross-bucket", name, report.distinct_kmers, report.cross_bucket_kmers);
 
-    for (kmer, mints) in multi_bucket.iter().take(5) {
+    for (kmer, mints) in &report.examples {
         println!("  {} -> mints {:?}", std::str::from_utf8(kmer).unwrap(), mints);
     }
 
-    assert_eq!(multi_bucket.len(), 0,
+    assert_eq!(report.cross_bucket_kmers, 0,
         "{}: {} k-mers appear in multiple buckets (expected 0)",
-        name, multi_bucket.len());
+        name, report.cross_bucket_kmers);
 }
 
 #[test]
@@ -68,9 +46,7 @@ fn test_mspxor_crossbucket_simdmini() {
 
     let mut extractor = rust_superkmers::iteratorsimdmini::SuperkmerExtractor::mspxor(31, 9);
     check_crossbucket("simdmini:mspxor", &reads, 31, |read| {
-        extractor.process(read).iter()
-            .map(|sk| (sk.start, sk.size, sk.mint))
-            .collect()
+        extractor.process(read).to_vec()
     });
 }
 
@@ -81,9 +57,7 @@ fn test_mspxor_crossbucket_syncmers2() {
 
     let mut extractor = rust_superkmers::iteratorsyncmers2::SuperkmerExtractor::mspxor(31, 8);
     check_crossbucket("syncmers2:mspxor", &reads, 31, |read| {
-        extractor.process(read).iter()
-            .map(|sk| (sk.start, sk.size, sk.mint))
-            .collect()
+        extractor.process(read).to_vec()
     });
 }
 
@@ -92,9 +66,7 @@ fn test_msp_crossbucket_simdmini() {
     let reads = load_reads();
     let mut extractor = rust_superkmers::iteratorsimdmini::SuperkmerExtractor::msp(31, 9);
     check_crossbucket("simdmini:msp", &reads, 31, |read| {
-        extractor.process(read).iter()
-            .map(|sk| (sk.start, sk.size, sk.mint))
-            .collect()
+        extractor.process(read).to_vec()
     });
 }
 
@@ -103,8 +75,6 @@ fn test_msp_crossbucket_syncmers2() {
     let reads = load_reads();
     let mut extractor = rust_superkmers::iteratorsyncmers2::SuperkmerExtractor::msp(31, 8);
     check_crossbucket("syncmers2:msp", &reads, 31, |read| {
-        extractor.process(read).iter()
-            .map(|sk| (sk.start, sk.size, sk.mint))
-            .collect()
+        extractor.process(read).to_vec()
     });
 }